@@ -0,0 +1,228 @@
+use crate::{
+    note_tags::NoteTagRule,
+    romanization::{hash_table, RomanizationTable},
+    EngineType, GameType,
+};
+use sonic_rs::{from_str, prelude::*, to_string, Array, Object, Value};
+use std::{
+    collections::HashMap,
+    fs::{read, read_to_string, write},
+    hash::BuildHasherDefault,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+use xxhash_rust::xxh3::{xxh3_64, xxh3_64_with_seed, Xxh3};
+
+/// Seed, that's used when hashing names.txt entries, so they don't collide with maps.txt entries.
+pub const NAMES_SEED: u64 = 1;
+
+#[derive(Clone, PartialEq)]
+pub struct CacheEntry {
+    /// Hash of the source file, seeded with the settings hash
+    pub source: u64,
+    /// Hash of the translation entries, that were used to produce the output file
+    pub translation: u64,
+    /// Hashes of the original strings, that the source file contains.
+    /// Empty for files, which translation is stored in a separate file.
+    pub keys: Vec<u64>,
+}
+
+/// Incremental cache, that stores hashes of source files and translation entries, so unchanged files can be skipped.
+///
+/// Cache is stored as `.rvpacker-txt-rs-cache.json` next to the metadata file.
+///
+/// Entries are still usable after a writer panics while holding the lock, as each of them is inserted as a whole.
+pub struct Cache {
+    path: PathBuf,
+    settings: u64,
+    entries: Mutex<HashMap<String, CacheEntry, BuildHasherDefault<Xxh3>>>,
+}
+
+impl Cache {
+    /// Loads the cache from the file, or creates an empty one, if file doesn't exist or can't be parsed.
+    /// # Parameters
+    /// * `path` - path to the cache file
+    /// * `settings` - hash of the settings, that affect the output; changing them invalidates all entries
+    pub fn load(path: &Path, settings: u64) -> Self {
+        let mut entries: HashMap<String, CacheEntry, BuildHasherDefault<Xxh3>> = HashMap::default();
+
+        if let Some(obj) = read_to_string(path)
+            .ok()
+            .and_then(|content: String| from_str::<Object>(&content).ok())
+        {
+            for (key, value) in obj.iter() {
+                let (Some(source), Some(translation), Some(keys)) = (
+                    value["source"].as_u64(),
+                    value["translation"].as_u64(),
+                    value["keys"].as_array(),
+                ) else {
+                    continue;
+                };
+
+                entries.insert(
+                    key.to_string(),
+                    CacheEntry {
+                        source,
+                        translation,
+                        keys: keys.iter().filter_map(|key: &Value| key.as_u64()).collect(),
+                    },
+                );
+            }
+        }
+
+        Self {
+            path: path.to_path_buf(),
+            settings,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Hashes source file bytes, seeding the hash with the settings hash.
+    pub fn hash_source(&self, bytes: &[u8]) -> u64 {
+        xxh3_64_with_seed(bytes, self.settings)
+    }
+
+    pub fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned()
+    }
+
+    pub fn insert(&self, key: String, entry: CacheEntry) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, entry);
+    }
+
+    /// Returns true, if cached entry with the key has the same source and translation hashes.
+    pub fn is_unchanged(&self, key: &str, source: u64, translation: u64) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .is_some_and(|entry: &CacheEntry| entry.source == source && entry.translation == translation)
+    }
+
+    pub fn save(&self) {
+        let mut obj: Object = Object::new();
+
+        for (key, entry) in self.entries.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+            let mut entry_obj: Object = Object::new();
+            entry_obj.insert("source", entry.source);
+            entry_obj.insert("translation", entry.translation);
+            entry_obj.insert("keys", Array::from(entry.keys.clone()));
+            obj.insert(key, entry_obj);
+        }
+
+        write(&self.path, to_string(&obj).unwrap()).unwrap();
+    }
+}
+
+/// Computes the hash of the settings, which affect the read and write output.
+/// # Parameters
+/// * `output_path` - directory, which files are written to, or None, when reading. Files written to one directory
+///   mustn't be skipped, when writing to another one, like `data` of `watch --in-game` and `output/data`.
+#[allow(clippy::too_many_arguments)]
+pub fn hash_settings(
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
+    note_tag_rules: &[NoteTagRule],
+    game_type: Option<&GameType>,
+    engine_type: &EngineType,
+    preserve_formatting: bool,
+    pseudo_expansion: Option<u16>,
+    output_path: Option<&Path>,
+) -> u64 {
+    let game_type_id: u8 = match game_type {
+        Some(GameType::Termina) => 1,
+        Some(GameType::LisaRPG) => 2,
+        None => 0,
+    };
+
+    let engine_type_id: u8 = match engine_type {
+        EngineType::XP => 0,
+        EngineType::VX => 1,
+        EngineType::VXAce => 2,
+        EngineType::New => 3,
+    };
+
+    let mut settings: Vec<u8> = vec![
+        romanization_table.is_some() as u8,
+        protect_codes as u8,
+        game_type_id,
+        engine_type_id,
+        preserve_formatting as u8,
+    ];
+
+    if let Some(table) = romanization_table {
+        settings.extend(hash_table(table).to_le_bytes());
+    }
+
+    if let Some(codes) = event_codes {
+        for code in codes {
+            settings.extend(code.to_le_bytes());
+        }
+    }
+
+    for rule in note_tag_rules {
+        settings.extend(rule.rule.as_bytes());
+        settings.push(0);
+    }
+
+    // Pseudo-localized output must not be taken for the translated one on the next write
+    if let Some(expansion) = pseudo_expansion {
+        settings.extend(expansion.to_le_bytes());
+    }
+
+    if let Some(output_path) = output_path {
+        settings.extend(output_path.as_os_str().as_encoded_bytes());
+    }
+
+    xxh3_64(&settings)
+}
+
+/// Hashes contents of multiple files, that are used as translation of a single output file.
+pub fn hash_files(paths: &[&Path]) -> u64 {
+    let mut hasher: Xxh3 = Xxh3::new();
+
+    for path in paths {
+        if let Ok(content) = read(path) {
+            hasher.update(&content);
+        }
+
+        hasher.update(&[0]);
+    }
+
+    hasher.digest()
+}
+
+/// Builds a map of original string hashes to translation string hashes from parallel original and translation files.
+pub fn hash_translation_pairs(
+    original: &str,
+    translated: &str,
+    seed: u64,
+    map: &mut HashMap<u64, u64, BuildHasherDefault<Xxh3>>,
+) {
+    for (original, translated) in original.split('\n').zip(translated.split('\n')) {
+        map.insert(
+            xxh3_64_with_seed(original.as_bytes(), seed),
+            xxh3_64(translated.as_bytes()),
+        );
+    }
+}
+
+/// Combines the translation hashes of keys into a single hash.
+pub fn hash_keys_translation(keys: &[u64], map: &HashMap<u64, u64, BuildHasherDefault<Xxh3>>) -> u64 {
+    let mut hasher: Xxh3 = Xxh3::new();
+
+    for key in keys {
+        hasher.update(&key.to_le_bytes());
+        hasher.update(&map.get(key).copied().unwrap_or_default().to_le_bytes());
+    }
+
+    hasher.digest()
+}
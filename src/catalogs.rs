@@ -0,0 +1,98 @@
+use std::{
+    env::{current_exe, var_os},
+    ffi::OsString,
+    fs::{read_dir, read_to_string},
+    path::PathBuf,
+};
+use toml::{Table, Value};
+
+/// Environment variable, which overrides the directory, that message catalogs are loaded from.
+pub const CATALOGS_DIR_VAR: &str = "RVPACKER_TXT_RS_LOCALES";
+
+/// Languages, which messages are built into the program. Catalogs can fall back only to them.
+pub const BUILTIN_LANGUAGES: [&str; 2] = ["en", "ru"];
+
+/// Message catalog of a language. Messages, which the catalog doesn't contain, are taken from the fallback language.
+pub struct Catalog {
+    /// One of `BUILTIN_LANGUAGES`, `en` if the catalog doesn't set it
+    pub fallback: String,
+    /// Messages keyed by the same names, as fields of the program localization
+    pub messages: Table,
+}
+
+pub enum CatalogError {
+    /// Catalog isn't a valid TOML, contains the parser message
+    Parse(String),
+    InvalidValue(String),
+}
+
+/// Returns the directory with message catalogs: the one from `RVPACKER_TXT_RS_LOCALES` variable, or `locales`
+/// directory next to the executable.
+fn get_catalogs_dir() -> Option<PathBuf> {
+    var_os(CATALOGS_DIR_VAR)
+        .map(PathBuf::from)
+        .or_else(|| Some(current_exe().ok()?.parent()?.join("locales")))
+}
+
+/// Returns languages, which messages are available, both built-in and from catalogs, sorted.
+pub fn list_languages() -> Vec<String> {
+    let mut languages: Vec<String> = BUILTIN_LANGUAGES.iter().map(|language| language.to_string()).collect();
+
+    if let Some(entries) = get_catalogs_dir().and_then(|catalogs_dir: PathBuf| read_dir(catalogs_dir).ok()) {
+        for entry in entries.flatten() {
+            let filename: OsString = entry.file_name();
+
+            if let Some(language) = filename
+                .to_str()
+                .and_then(|filename: &str| filename.strip_suffix(".toml"))
+            {
+                if !languages.iter().any(|available: &String| available == language) {
+                    languages.push(language.to_string());
+                }
+            }
+        }
+    }
+
+    languages.sort();
+    languages
+}
+
+/// Loads message catalog of the language, if it exists. Catalogs of built-in languages override their built-in
+/// messages.
+/// # Parameters
+/// * `language` - language code, which is the name of the catalog file without `.toml` extension
+pub fn load_catalog(language: &str) -> Result<Option<Catalog>, CatalogError> {
+    let Some(catalog_path) =
+        get_catalogs_dir().map(|catalogs_dir: PathBuf| catalogs_dir.join(format!("{language}.toml")))
+    else {
+        return Ok(None);
+    };
+
+    if !catalog_path.exists() {
+        return Ok(None);
+    }
+
+    let mut table: Table = read_to_string(&catalog_path)
+        .map_err(|err| CatalogError::Parse(err.to_string()))?
+        .parse::<Table>()
+        .map_err(|err| CatalogError::Parse(err.to_string()))?;
+
+    let fallback: String = match table.remove("fallback") {
+        Some(Value::String(fallback)) if BUILTIN_LANGUAGES.contains(&fallback.as_str()) => fallback,
+        Some(_) => return Err(CatalogError::InvalidValue(String::from("fallback"))),
+        None if BUILTIN_LANGUAGES.contains(&language) => language.to_string(),
+        None => String::from("en"),
+    };
+
+    let messages: Table = match table.remove("messages") {
+        Some(Value::Table(messages)) => messages,
+        Some(_) => return Err(CatalogError::InvalidValue(String::from("messages"))),
+        None => Table::new(),
+    };
+
+    if let Some(key) = table.keys().next() {
+        return Err(CatalogError::InvalidValue(key.to_string()));
+    }
+
+    Ok(Some(Catalog { fallback, messages }))
+}
@@ -0,0 +1,170 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+
+/// Name of the project configuration file, which is looked up in the current directory.
+pub const CONFIG_FILENAME: &str = "rvpacker.toml";
+
+/// Codes of event commands, which text can be extracted from.
+pub const EVENT_CODES: [u16; 6] = [102, 320, 324, 356, 401, 405];
+
+/// Keys, which both the top level of the configuration and its profiles may contain. They're named after the command
+/// line arguments, which they set.
+const KEYS: [&str; 10] = [
+    "input-dir",
+    "output-dir",
+    "target-language",
+    "disable-processing",
+    "romanize",
+    "disable-custom-processing",
+    "disable-cache",
+    "shuffle-level",
+    "event-codes",
+    "note-tags",
+];
+
+/// Settings of the project configuration file. Settings, which aren't set, fall back to the defaults of the command
+/// line arguments.
+#[derive(Default)]
+pub struct ProjectConfig {
+    pub input_dir: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub target_language: Option<String>,
+    pub disable_processing: Option<Vec<String>>,
+    pub romanize: Option<bool>,
+    pub disable_custom_processing: Option<bool>,
+    pub disable_cache: Option<bool>,
+    pub shuffle_level: Option<u8>,
+    pub event_codes: Option<Vec<u16>>,
+    pub note_tags: Option<Vec<String>>,
+}
+
+pub enum ConfigError {
+    /// File isn't a valid TOML, contains the parser message
+    Parse(String),
+    UnknownKey(String),
+    InvalidValue(String),
+    UnknownProfile(String),
+}
+
+fn get_strings(value: &Value) -> Option<Vec<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|value: &Value| value.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Loads the project configuration. Settings of the profile override the top level settings, and relative paths are
+/// resolved against the directory of the configuration file.
+/// # Parameters
+/// * `config_path` - path to the configuration file
+/// * `profile` - name of the profile from `[profiles.<name>]` table, if any
+pub fn load_config(config_path: &Path, profile: Option<&str>) -> Result<ProjectConfig, ConfigError> {
+    let mut table: Table = read_to_string(config_path)
+        .map_err(|err| ConfigError::Parse(err.to_string()))?
+        .parse::<Table>()
+        .map_err(|err| ConfigError::Parse(err.to_string()))?;
+
+    let profiles: Table = match table.remove("profiles") {
+        Some(Value::Table(profiles)) => profiles,
+        Some(_) => return Err(ConfigError::InvalidValue(String::from("profiles"))),
+        None => Table::new(),
+    };
+
+    // Typos in profiles, which aren't selected, are reported too
+    for (name, profile_table) in profiles.iter() {
+        if let Some(key) = profile_table
+            .as_table()
+            .and_then(|profile_table: &Table| profile_table.keys().find(|key| !KEYS.contains(&key.as_str())))
+        {
+            return Err(ConfigError::UnknownKey(format!("profiles.{name}.{key}")));
+        }
+    }
+
+    if let Some(profile) = profile {
+        match profiles.get(profile) {
+            Some(Value::Table(profile_table)) => table.extend(profile_table.clone()),
+            Some(_) => return Err(ConfigError::InvalidValue(format!("profiles.{profile}"))),
+            None => return Err(ConfigError::UnknownProfile(profile.to_string())),
+        }
+    }
+
+    let config_dir: &Path = config_path.parent().unwrap_or(Path::new(""));
+    let mut config: ProjectConfig = ProjectConfig::default();
+
+    for (key, value) in table.iter() {
+        if !KEYS.contains(&key.as_str()) {
+            return Err(ConfigError::UnknownKey(key.to_string()));
+        }
+
+        let invalid = || ConfigError::InvalidValue(key.to_string());
+
+        match key.as_str() {
+            "input-dir" => config.input_dir = Some(config_dir.join(value.as_str().ok_or_else(invalid)?)),
+            "output-dir" => config.output_dir = Some(config_dir.join(value.as_str().ok_or_else(invalid)?)),
+            "target-language" => config.target_language = Some(value.as_str().ok_or_else(invalid)?.to_string()),
+            "disable-processing" => {
+                let disable_processing: Vec<String> = get_strings(value).ok_or_else(invalid)?;
+
+                if !disable_processing
+                    .iter()
+                    .all(|name: &String| ["maps", "other", "system", "plugins"].contains(&name.as_str()))
+                {
+                    return Err(invalid());
+                }
+
+                config.disable_processing = Some(disable_processing);
+            }
+            "romanize" => config.romanize = Some(value.as_bool().ok_or_else(invalid)?),
+            "disable-custom-processing" => {
+                config.disable_custom_processing = Some(value.as_bool().ok_or_else(invalid)?)
+            }
+            "disable-cache" => config.disable_cache = Some(value.as_bool().ok_or_else(invalid)?),
+            "shuffle-level" => {
+                config.shuffle_level = Some(
+                    value
+                        .as_integer()
+                        .filter(|level: &i64| (0..=2).contains(level))
+                        .ok_or_else(invalid)? as u8,
+                )
+            }
+            "event-codes" => {
+                let event_codes: Option<Vec<u16>> = value.as_array().and_then(|codes| {
+                    codes
+                        .iter()
+                        .map(|code: &Value| {
+                            code.as_integer()
+                                .and_then(|code: i64| u16::try_from(code).ok())
+                                .filter(|code: &u16| EVENT_CODES.contains(code))
+                        })
+                        .collect()
+                });
+
+                config.event_codes = Some(event_codes.ok_or_else(invalid)?);
+            }
+            "note-tags" => config.note_tags = Some(get_strings(value).ok_or_else(invalid)?),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(config)
+}
+
+/// Returns codes of the allowed event commands, which text is extracted. Choice branches (402) follow the
+/// choices (102).
+/// # Parameters
+/// * `allowed_codes` - codes of event commands, which text the file can contain
+/// * `event_codes` - enabled event codes, or None, if all of them are
+pub fn filter_event_codes(allowed_codes: &[u16], event_codes: Option<&[u16]>) -> Vec<u16> {
+    allowed_codes
+        .iter()
+        .copied()
+        .filter(|&code: &u16| {
+            let code: u16 = if code == 402 { 102 } else { code };
+            event_codes.is_none_or(|codes: &[u16]| codes.contains(&code))
+        })
+        .collect()
+}
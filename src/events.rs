@@ -0,0 +1,184 @@
+use sonic_rs::{to_string, to_value, Value};
+use std::io::{stderr, stdout, Write};
+
+/// Format of the program output.
+#[derive(PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    /// Localized messages for humans
+    Text,
+    /// Newline-delimited JSON events for programs, which wrap the CLI. Each line is an object with `event` key, and
+    /// the rest of its keys depend on the event:
+    /// * `started` - `command`
+    /// * `file_started` - `file`
+    /// * `file_parsed` - `file`, `count` of extracted lines
+    /// * `file_written` - `file`, `bytes`
+    /// * `file_skipped` - `file`, `reason`: `exists` or `unchanged`
+    /// * `phase_finished` - `phase`: `maps`, `other`, `system`, `scripts` or `plugins`, number of its `files`, `duration`
+    ///   in seconds
+    /// * `warning` - `code`, localized `message`
+    /// * `message` - localized `message` of commands, which don't report files
+    /// * `error` - localized `message`, after which the program exits with non-zero code
+    /// * `finished` - `command`, `duration` in seconds
+    Json,
+}
+
+/// Reasons of skipping a file.
+pub enum SkipReason {
+    /// Translation already exists, and isn't read again without `--force` or `--append`
+    Exists,
+    /// File is unchanged since the last run, according to the cache
+    Unchanged,
+}
+
+pub enum Event<'a> {
+    Started {
+        command: &'a str,
+    },
+    FileStarted {
+        file: &'a str,
+    },
+    FileParsed {
+        file: &'a str,
+        count: usize,
+    },
+    FileWritten {
+        file: &'a str,
+        bytes: usize,
+    },
+    FileSkipped {
+        file: &'a str,
+        reason: SkipReason,
+    },
+    PhaseFinished {
+        phase: &'a str,
+        files: usize,
+        duration: f64,
+    },
+    Warning {
+        code: &'a str,
+        message: &'a str,
+    },
+    Message {
+        message: &'a str,
+    },
+    Error {
+        message: &'a str,
+    },
+    Finished {
+        command: &'a str,
+        duration: f64,
+    },
+}
+
+/// Where and how events and messages are printed.
+#[derive(Clone, Copy)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// Whether events and messages are printed to stderr, so stdout is left for responses of the server
+    pub reserve_stdout: bool,
+}
+
+impl Output {
+    /// Returns whether events are emitted as JSON instead of text.
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Prints the line to stdout, or to stderr, if stdout is reserved.
+    fn print_line(&self, line: &str) {
+        if self.reserve_stdout {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// Prints the event as a single line of JSON. Fields are printed in the fixed order, starting with `event`,
+    /// which sonic-rs objects don't preserve.
+    /// # Parameters
+    /// * `event` - event to print
+    pub fn emit(&self, event: Event) {
+        let (name, fields): (&str, Vec<(&str, Value)>) = match event {
+            Event::Started { command } => ("started", vec![("command", command.into())]),
+            Event::FileStarted { file } => ("file_started", vec![("file", file.into())]),
+            Event::FileParsed { file, count } => ("file_parsed", vec![("file", file.into()), ("count", count.into())]),
+            Event::FileWritten { file, bytes } => {
+                ("file_written", vec![("file", file.into()), ("bytes", bytes.into())])
+            }
+            Event::FileSkipped { file, reason } => {
+                let reason: &str = match reason {
+                    SkipReason::Exists => "exists",
+                    SkipReason::Unchanged => "unchanged",
+                };
+
+                ("file_skipped", vec![("file", file.into()), ("reason", reason.into())])
+            }
+            Event::PhaseFinished { phase, files, duration } => (
+                "phase_finished",
+                vec![
+                    ("phase", phase.into()),
+                    ("files", files.into()),
+                    ("duration", to_value(&duration).unwrap()),
+                ],
+            ),
+            Event::Warning { code, message } => ("warning", vec![("code", code.into()), ("message", message.into())]),
+            Event::Message { message } => ("message", vec![("message", message.into())]),
+            Event::Error { message } => ("error", vec![("message", message.into())]),
+            Event::Finished { command, duration } => (
+                "finished",
+                vec![("command", command.into()), ("duration", to_value(&duration).unwrap())],
+            ),
+        };
+
+        let mut line: String = format!(r#"{{"event":"{name}""#);
+
+        for (key, value) in fields {
+            line.push_str(&format!(r#","{key}":{}"#, to_string(&value).unwrap()));
+        }
+
+        line.push('}');
+
+        // Failing to print mustn't panic, as errors are printed from the panic hook too
+        let _ = if self.reserve_stdout {
+            writeln!(stderr().lock(), "{line}")
+        } else {
+            writeln!(stdout().lock(), "{line}")
+        };
+    }
+
+    /// Prints the message as text, or as `message` event.
+    /// # Parameters
+    /// * `message` - message to print
+    pub fn print_message(&self, message: &str) {
+        if self.is_json() {
+            self.emit(Event::Message { message });
+        } else {
+            self.print_line(message);
+        }
+    }
+
+    /// Prints the warning as text, or as `warning` event.
+    /// # Parameters
+    /// * `code` - stable identifier of the warning
+    /// * `message` - localized message
+    pub fn print_warning(&self, code: &str, message: &str) {
+        if self.is_json() {
+            self.emit(Event::Warning { code, message });
+        } else {
+            self.print_line(message);
+        }
+    }
+
+    /// Prints how long the command took as text, or as `finished` event.
+    /// # Parameters
+    /// * `command` - name of the subcommand
+    /// * `duration` - duration in seconds
+    /// * `done_in_msg` - message to print before the duration
+    pub fn print_finished(&self, command: &str, duration: f64, done_in_msg: &str) {
+        if self.is_json() {
+            self.emit(Event::Finished { command, duration });
+        } else {
+            self.print_line(&format!("{done_in_msg} {duration}"));
+        }
+    }
+}
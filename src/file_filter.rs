@@ -0,0 +1,126 @@
+use regex::{escape, Regex};
+
+/// Selection of files by patterns of their names without extension, like `Map0*` or `CommonEvents`. `*` matches any
+/// characters, `?` matches a single character, and names are matched case-insensitively. Default filter selects all
+/// files.
+#[derive(Clone, Default)]
+pub struct FileFilter {
+    only: Vec<Regex>,
+    exclude: Vec<Regex>,
+    /// Patterns of the files, which the current request of the server selects, in addition to `only`
+    requested: Option<Vec<Regex>>,
+}
+
+fn compile_pattern(pattern: &str) -> Regex {
+    let regex: String = escape(pattern.trim()).replace(r"\*", ".*").replace(r"\?", ".");
+    Regex::new(&format!("(?i)^{regex}$")).unwrap()
+}
+
+impl FileFilter {
+    /// # Parameters
+    /// * `only` - patterns, one of which the name must match, or empty to select all files
+    /// * `exclude` - patterns, none of which the name must match
+    pub fn new(only: &[String], exclude: &[String]) -> Self {
+        Self {
+            only: only.iter().map(|pattern: &String| compile_pattern(pattern)).collect(),
+            exclude: exclude
+                .iter()
+                .map(|pattern: &String| compile_pattern(pattern))
+                .collect(),
+            requested: None,
+        }
+    }
+
+    /// Returns the filter, which also requires names to match one of the patterns of the server's request.
+    /// # Parameters
+    /// * `requested` - patterns of the request
+    pub fn with_requested(&self, requested: &[String]) -> Self {
+        Self {
+            requested: Some(
+                requested
+                    .iter()
+                    .map(|pattern: &String| compile_pattern(pattern))
+                    .collect(),
+            ),
+            ..self.clone()
+        }
+    }
+
+    /// Returns whether only some of the files are selected.
+    pub fn is_active(&self) -> bool {
+        !self.only.is_empty() || !self.exclude.is_empty() || self.requested.is_some()
+    }
+
+    /// Returns whether the file is selected.
+    /// # Parameters
+    /// * `filename` - name of the file, with or without extension
+    pub fn matches(&self, filename: &str) -> bool {
+        let name: &str = filename.split_once('.').map_or(filename, |(name, _)| name);
+
+        (self.only.is_empty() || self.only.iter().any(|regex: &Regex| regex.is_match(name)))
+            && !self.exclude.iter().any(|regex: &Regex| regex.is_match(name))
+            && self
+                .requested
+                .as_ref()
+                .is_none_or(|requested: &Vec<Regex>| requested.iter().any(|regex: &Regex| regex.is_match(name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|pattern: &&str| pattern.to_string()).collect()
+    }
+
+    #[test]
+    fn star_and_question_mark() {
+        let filter: FileFilter = FileFilter::new(&patterns(&["Map0*", "Troop?"]), &[]);
+
+        assert!(filter.matches("Map001.json"));
+        assert!(filter.matches("Map0"));
+        assert!(!filter.matches("Map100.rvdata2"));
+        assert!(filter.matches("Troops"));
+        assert!(!filter.matches("Troop"));
+        assert!(!filter.matches("Troopss"));
+        assert!(!filter.matches("MapInfos"));
+    }
+
+    #[test]
+    fn case_insensitive_and_literal() {
+        let filter: FileFilter = FileFilter::new(&patterns(&[" commonevents ", "Map[1]+"]), &[]);
+
+        assert!(filter.matches("CommonEvents.json"));
+        assert!(filter.matches("COMMONEVENTS"));
+        assert!(filter.matches("Map[1]+.json"));
+        assert!(!filter.matches("Map11.json"));
+    }
+
+    #[test]
+    fn exclude_overrides_only() {
+        let filter: FileFilter = FileFilter::new(&patterns(&["Map*"]), &patterns(&["map01?"]));
+
+        assert!(filter.matches("Map001.json"));
+        assert!(!filter.matches("Map012.json"));
+        assert!(!filter.matches("Items.json"));
+
+        let filter: FileFilter = FileFilter::new(&[], &patterns(&["*Events"]));
+
+        assert!(filter.matches("Items.json"));
+        assert!(!filter.matches("CommonEvents.json"));
+    }
+
+    #[test]
+    fn request_narrows_filter() {
+        let filter: FileFilter = FileFilter::new(&patterns(&["Map*"]), &[]);
+        let requested: FileFilter = filter.with_requested(&patterns(&["Map001", "Items"]));
+
+        assert!(!FileFilter::default().is_active());
+        assert!(requested.is_active());
+        assert!(requested.matches("Map001.json"));
+        assert!(!requested.matches("Map002.json"));
+        assert!(!requested.matches("Items.json"));
+        assert!(filter.matches("Map002.json"));
+    }
+}
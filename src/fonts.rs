@@ -0,0 +1,233 @@
+use crate::{migrate::read_lines, EngineType};
+use flate2::read::ZlibDecoder;
+use sonic_rs::{from_slice, prelude::*, Value};
+use std::{
+    collections::{BTreeSet, HashSet},
+    ffi::OsString,
+    fs::{read, read_dir},
+    hash::BuildHasherDefault,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use ttf_parser::{fonts_in_collection, Face};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Subdirectories of the translation directory, which contain translation files.
+const TRANSLATION_SUBDIRS: [&str; 3] = ["maps", "other", "plugins"];
+
+/// Extensions of font files, which can be loaded.
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "woff"];
+
+/// Translation line, which contains characters, that none of the game's fonts covers.
+pub struct GlyphIssue {
+    /// Name of the translation file, like `maps_trans.txt`
+    pub filename: String,
+    /// 1-based line number in the translation file
+    pub line: usize,
+    pub chars: Vec<char>,
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// Converts WOFF font, which MZ uses by default, to the plain TTF/OTF data by decompressing its tables.
+fn woff_to_sfnt(woff: &[u8]) -> Option<Vec<u8>> {
+    let flavor: u32 = read_u32(woff, 4)?;
+    let num_tables: u16 = read_u16(woff, 12)?;
+
+    let mut tables: Vec<(u32, u32, Vec<u8>)> = Vec::with_capacity(num_tables as usize);
+
+    for i in 0..num_tables as usize {
+        let entry: usize = 44 + i * 20;
+        let tag: u32 = read_u32(woff, entry)?;
+        let offset: usize = read_u32(woff, entry + 4)? as usize;
+        let compressed_length: usize = read_u32(woff, entry + 8)? as usize;
+        let original_length: usize = read_u32(woff, entry + 12)? as usize;
+        let checksum: u32 = read_u32(woff, entry + 16)?;
+
+        let compressed: &[u8] = woff.get(offset..offset + compressed_length)?;
+
+        let data: Vec<u8> = if compressed_length < original_length {
+            let mut data: Vec<u8> = Vec::with_capacity(original_length);
+            ZlibDecoder::new(compressed).read_to_end(&mut data).ok()?;
+            data
+        } else {
+            compressed.to_vec()
+        };
+
+        tables.push((tag, checksum, data));
+    }
+
+    // Header fields overflow for fonts with more than 4095 tables, which aren't valid
+    let entry_selector: u16 = (num_tables.max(1) as f64).log2().floor() as u16;
+    let search_range: u16 = 1u16.checked_shl(entry_selector.into())?.checked_mul(16)?;
+    let range_shift: u16 = num_tables.checked_mul(16)?.checked_sub(search_range)?;
+
+    let mut sfnt: Vec<u8> = Vec::new();
+    sfnt.extend(flavor.to_be_bytes());
+    sfnt.extend(num_tables.to_be_bytes());
+    sfnt.extend(search_range.to_be_bytes());
+    sfnt.extend(entry_selector.to_be_bytes());
+    sfnt.extend(range_shift.to_be_bytes());
+
+    let mut offset: usize = 12 + tables.len() * 16;
+
+    for (tag, checksum, data) in tables.iter() {
+        sfnt.extend(tag.to_be_bytes());
+        sfnt.extend(checksum.to_be_bytes());
+        sfnt.extend((offset as u32).to_be_bytes());
+        sfnt.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+
+    for (_, _, data) in tables {
+        sfnt.extend(&data);
+        sfnt.resize(sfnt.len().next_multiple_of(4), 0);
+    }
+
+    Some(sfnt)
+}
+
+/// Returns paths of fonts, which the game may render text with. If MZ `System.json` names the main font, and it
+/// exists, only it is returned, otherwise all fonts from `Fonts` or `fonts` folder of the game are.
+/// # Parameters
+/// * `game_path` - path to the game directory
+/// * `system_file_path` - path to the original system file
+/// * `engine_type` - engine type of the game
+pub fn find_fonts(game_path: &Path, system_file_path: &Path, engine_type: &EngineType) -> Vec<PathBuf> {
+    let Some(fonts_path) = ["Fonts", "fonts"]
+        .into_iter()
+        .map(|dirname: &str| game_path.join(dirname))
+        .find(|path: &PathBuf| path.is_dir())
+    else {
+        return Vec::new();
+    };
+
+    if engine_type == EngineType::New {
+        let main_font: Option<String> = read(system_file_path)
+            .ok()
+            .and_then(|bytes: Vec<u8>| from_slice::<Value>(&bytes).ok())
+            .and_then(|system_obj: Value| system_obj["advanced"]["mainFontFilename"].as_str().map(str::to_string));
+
+        if let Some(main_font) = main_font {
+            let main_font_path: PathBuf = fonts_path.join(main_font);
+
+            if main_font_path.is_file() {
+                return vec![main_font_path];
+            }
+        }
+    }
+
+    let mut font_paths: Vec<PathBuf> = read_dir(fonts_path)
+        .unwrap()
+        .flatten()
+        .filter(|entry| {
+            let filename: OsString = entry.file_name();
+
+            filename
+                .to_str()
+                .and_then(|filename: &str| filename.rsplit_once('.'))
+                .is_some_and(|(_, extension)| FONT_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+
+    font_paths.sort();
+    font_paths
+}
+
+/// Checks, that every character of translation files is covered by at least one of the fonts. Whitespace and
+/// control characters are ignored, and fonts, that can't be parsed, are skipped.
+/// # Parameters
+/// * `font_paths` - paths to the fonts
+/// * `translation_path` - path to the translation directory
+pub fn check_glyphs(font_paths: &[PathBuf], translation_path: &Path) -> Vec<GlyphIssue> {
+    let fonts_data: Vec<Vec<u8>> = font_paths
+        .iter()
+        .filter_map(|path: &PathBuf| {
+            let bytes: Vec<u8> = read(path).ok()?;
+
+            if bytes.starts_with(b"wOFF") {
+                woff_to_sfnt(&bytes)
+            } else {
+                Some(bytes)
+            }
+        })
+        .collect();
+
+    let faces: Vec<Face> = fonts_data
+        .iter()
+        .flat_map(|data: &Vec<u8>| {
+            (0..fonts_in_collection(data).unwrap_or(1)).filter_map(|index: u32| Face::parse(data, index).ok())
+        })
+        .collect();
+
+    let mut translations: Vec<(String, Vec<String>)> = Vec::new();
+
+    for subdir in TRANSLATION_SUBDIRS {
+        let Ok(entries) = read_dir(translation_path.join(subdir)) else {
+            continue;
+        };
+
+        let mut filenames: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let filename: String = entry.file_name().into_string().ok()?;
+                filename.ends_with("_trans.txt").then_some(filename)
+            })
+            .collect();
+
+        filenames.sort();
+
+        for filename in filenames {
+            let lines: Vec<String> = read_lines(&translation_path.join(subdir).join(&filename));
+            translations.push((filename, lines));
+        }
+    }
+
+    let used_chars: BTreeSet<char> = translations
+        .iter()
+        .flat_map(|(_, lines)| lines.iter().flat_map(|line: &String| line.chars()))
+        .filter(|char: &char| !char.is_whitespace() && !char.is_control())
+        .collect();
+
+    let uncovered: HashSet<char, BuildHasherDefault<Xxh3>> = used_chars
+        .into_iter()
+        .filter(|char: &char| !faces.iter().any(|face: &Face| face.glyph_index(*char).is_some()))
+        .collect();
+
+    let mut issues: Vec<GlyphIssue> = Vec::new();
+
+    if uncovered.is_empty() {
+        return issues;
+    }
+
+    for (filename, lines) in translations {
+        for (i, line) in lines.iter().enumerate() {
+            let mut chars: Vec<char> = Vec::new();
+
+            for char in line.chars() {
+                if uncovered.contains(&char) && !chars.contains(&char) {
+                    chars.push(char);
+                }
+            }
+
+            if chars.is_empty() {
+                continue;
+            }
+
+            issues.push(GlyphIssue {
+                filename: filename.clone(),
+                line: i + 1,
+                chars,
+            });
+        }
+    }
+
+    issues
+}
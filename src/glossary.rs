@@ -0,0 +1,229 @@
+use crate::{migrate::read_lines, romanization::RomanizationTable, romanize_string, EngineType};
+use indexmap::IndexMap;
+use marshal_rs::load::load;
+use sonic_rs::{from_slice, prelude::*, Value};
+use std::{
+    ffi::OsString,
+    fs::{read, read_dir, write},
+    hash::BuildHasherDefault,
+    path::Path,
+};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Database files, which `name` fields are used as glossary terms.
+const GLOSSARY_SOURCES: [&str; 5] = ["Actors", "Items", "Skills", "Weapons", "Armors"];
+
+/// Translation files, which dialogue is checked for glossary consistency.
+const CHECKED_FILES: [(&str, &str); 2] = [("maps", "maps"), ("other", "commonevents")];
+
+/// Translation line, which original contains a glossary term, but translation lacks its agreed rendering.
+pub struct GlossaryIssue {
+    /// Name of the translation file, like `maps_trans.txt`
+    pub filename: String,
+    /// 1-based line number in the translation file
+    pub line: usize,
+    pub term: String,
+    pub rendering: String,
+}
+
+fn read_pairs(original_path: &Path, translation_path: &Path) -> IndexMap<String, String, BuildHasherDefault<Xxh3>> {
+    read_lines(original_path)
+        .into_iter()
+        .zip(read_lines(translation_path))
+        .collect()
+}
+
+/// Adds terms from names of database entries and map display names to the glossary. Existing terms keep their
+/// renderings, and empty renderings are filled with the current translation of the term. Returns count of added
+/// terms.
+/// # Parameters
+/// * `glossary_path` - path to the glossary.txt file
+/// * `original_path` - path to the directory with original game files
+/// * `maps_path` - path to the translation/maps directory
+/// * `other_path` - path to the translation/other directory
+/// * `romanization_table` - table, which the text was romanized with, or None, if it wasn't
+/// * `engine_type` - engine type of the game
+pub fn seed_glossary(
+    glossary_path: &Path,
+    original_path: &Path,
+    maps_path: &Path,
+    other_path: &Path,
+    romanization_table: Option<&RomanizationTable>,
+    engine_type: &EngineType,
+) -> usize {
+    let glossary_trans_path: &Path = &glossary_path.with_file_name("glossary_trans.txt");
+
+    let mut glossary: IndexMap<String, String, BuildHasherDefault<Xxh3>> =
+        read_pairs(glossary_path, glossary_trans_path);
+    glossary.retain(|term: &String, _| !term.is_empty());
+
+    let initial_len: usize = glossary.len();
+    let name_label: &str = if engine_type == EngineType::New {
+        "name"
+    } else {
+        "__symbol__name"
+    };
+
+    let mut terms: Vec<(String, String)> = Vec::new();
+
+    for entry in read_dir(original_path).unwrap().flatten() {
+        let filename: OsString = entry.file_name();
+        let Some((stem, extension)) = filename.to_str().and_then(|filename: &str| filename.split_once('.')) else {
+            continue;
+        };
+
+        if !GLOSSARY_SOURCES.contains(&stem) || !["json", "rvdata2", "rvdata", "rxdata"].contains(&extension) {
+            continue;
+        }
+
+        let bytes: Vec<u8> = read(entry.path()).unwrap();
+        let obj_arr: Value = if engine_type == EngineType::New {
+            from_slice(&bytes).unwrap()
+        } else {
+            load(&bytes, None, Some("")).unwrap()
+        };
+
+        let processed_filename: String = stem.to_lowercase();
+        let translation: IndexMap<String, String, BuildHasherDefault<Xxh3>> = read_pairs(
+            &other_path.join(format!("{processed_filename}.txt")),
+            &other_path.join(format!("{processed_filename}_trans.txt")),
+        );
+
+        for obj in obj_arr.as_array().unwrap().iter() {
+            let Some(name) = obj[name_label].as_str() else {
+                continue;
+            };
+
+            let mut name: String = name.trim().to_string();
+
+            if let Some(table) = romanization_table {
+                name = romanize_string(name, table);
+            }
+
+            if !name.is_empty() {
+                let rendering: String = translation.get(&name).cloned().unwrap_or_default();
+                terms.push((name, rendering));
+            }
+        }
+    }
+
+    terms.extend(read_pairs(
+        &maps_path.join("names.txt"),
+        &maps_path.join("names_trans.txt"),
+    ));
+
+    for (term, rendering) in terms {
+        if term.is_empty() {
+            continue;
+        }
+
+        let entry: &mut String = glossary.entry(term).or_default();
+
+        if entry.is_empty() {
+            *entry = rendering;
+        }
+    }
+
+    let (terms, renderings): (Vec<String>, Vec<String>) = glossary.into_iter().unzip();
+    write(glossary_path, terms.join("\n")).unwrap();
+    write(glossary_trans_path, renderings.join("\n")).unwrap();
+
+    terms.len() - initial_len
+}
+
+/// Whether the character is a letter or a digit of a script, which separates words with spaces. Chinese, Japanese,
+/// Thai, Lao, Khmer and Myanmar don't, so terms in them have no word boundaries.
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric()
+        && !matches!(
+            char,
+            '\u{0E00}'..='\u{0EFF}'
+                | '\u{1000}'..='\u{109F}'
+                | '\u{1780}'..='\u{17FF}'
+                | '\u{3040}'..='\u{30FF}'
+                | '\u{31F0}'..='\u{31FF}'
+                | '\u{3400}'..='\u{4DBF}'
+                | '\u{4E00}'..='\u{9FFF}'
+                | '\u{F900}'..='\u{FAFF}'
+                | '\u{FF66}'..='\u{FF9F}'
+                | '\u{20000}'..='\u{3FFFF}'
+        )
+}
+
+/// Whether the text contains the term as a whole word, so "Elixir" doesn't match "Elixirs", and "Max" doesn't
+/// match "Maximum". Term's edges in scripts without word boundaries match anywhere.
+/// # Parameters
+/// * `text` - text to search in
+/// * `term` - non-empty term to search for
+fn contains_term(text: &str, term: &str) -> bool {
+    let starts_with_word: bool = term.chars().next().is_some_and(is_word_char);
+    let ends_with_word: bool = term.chars().next_back().is_some_and(is_word_char);
+
+    text.match_indices(term).any(|(index, _)| {
+        let before: Option<char> = text[..index].chars().next_back();
+        let after: Option<char> = text[index + term.len()..].chars().next();
+
+        let joined_before: bool = starts_with_word && before.is_some_and(is_word_char);
+        let joined_after: bool = ends_with_word && after.is_some_and(is_word_char);
+
+        !joined_before && !joined_after
+    })
+}
+
+/// Checks, that translation of dialogue lines, which originals contain glossary terms, contains agreed renderings
+/// of these terms. Terms without rendering are ignored. Comparison is case-insensitive, and terms are matched as
+/// whole words, while renderings may be inflected, so they're matched anywhere.
+/// # Parameters
+/// * `glossary_path` - path to the glossary.txt file
+/// * `translation_path` - path to the translation directory
+pub fn check_glossary(glossary_path: &Path, translation_path: &Path) -> Vec<GlossaryIssue> {
+    let glossary: Vec<(String, String, String, String)> =
+        read_pairs(glossary_path, &glossary_path.with_file_name("glossary_trans.txt"))
+            .into_iter()
+            .filter(|(term, rendering)| !term.is_empty() && !rendering.is_empty())
+            .map(|(term, rendering)| (term.to_lowercase(), rendering.to_lowercase(), term, rendering))
+            .collect();
+
+    let mut issues: Vec<GlossaryIssue> = Vec::new();
+
+    for (subdir, name) in CHECKED_FILES {
+        let originals: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}.txt")));
+        let translations: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}_trans.txt")));
+
+        for (i, (original, translation)) in originals.iter().zip(translations.iter()).enumerate() {
+            if translation.is_empty() {
+                continue;
+            }
+
+            let original: String = original.to_lowercase();
+            let translation: String = translation.to_lowercase();
+
+            for (lowercase_term, lowercase_rendering, term, rendering) in glossary.iter() {
+                if contains_term(&original, lowercase_term) && !translation.contains(lowercase_rendering) {
+                    issues.push(GlossaryIssue {
+                        filename: format!("{name}_trans.txt"),
+                        line: i + 1,
+                        term: term.to_string(),
+                        rendering: rendering.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_match_whole_words() {
+        assert!(contains_term("drink the elixir.", "elixir"));
+        assert!(!contains_term("drink the elixirs.", "elixir"));
+        assert!(!contains_term("maximum hp", "max"));
+        assert!(contains_term("エリクサーを飲む", "エリクサー"));
+        assert!(contains_term("使用回复药水吧", "回复药水"));
+    }
+}
@@ -0,0 +1,219 @@
+//=============================================================================
+// RvpackerLanguages.js
+//=============================================================================
+
+/*:
+ * @target MZ
+ * @plugindesc Switches game text between languages at runtime.
+ * @author rvpacker-txt-rs
+ *
+ * @help This plugin is generated by rvpacker-txt-rs. Language packs are loaded
+ * from data/Languages/<language>.json, and the language is chosen in the
+ * options menu. Dialogue, choices, map names, database names and descriptions
+ * and system terms are translated. Text, which the pack doesn't contain, is
+ * shown as is.
+ */
+
+(function () {
+    "use strict";
+
+    var LANGUAGES = __LANGUAGES__;
+    var DATABASE_FIELDS = ["name", "nickname", "profile", "description", "message1", "message2", "message3", "message4"];
+    var DATABASES = ["$dataActors", "$dataClasses", "$dataSkills", "$dataItems", "$dataWeapons", "$dataArmors", "$dataEnemies", "$dataTroops", "$dataStates"];
+
+    var originalSystem = null;
+
+    function packName(language) {
+        return "$rvpackerLanguage_" + language;
+    }
+
+    function currentPack() {
+        return ConfigManager.language ? window[packName(ConfigManager.language)] : null;
+    }
+
+    function translate(text) {
+        var pack = currentPack();
+
+        if (!pack || typeof text !== "string") {
+            return text;
+        }
+
+        var translation = pack[text.trim()];
+        return translation === undefined ? text : translation;
+    }
+
+    function translateArray(array) {
+        return array ? array.map(translate) : array;
+    }
+
+    function applyDatabase() {
+        DATABASES.forEach(function (name) {
+            (window[name] || []).forEach(function (item) {
+                if (!item) {
+                    return;
+                }
+
+                item._rvpackerOriginal = item._rvpackerOriginal || {};
+
+                DATABASE_FIELDS.forEach(function (field) {
+                    if (typeof item[field] !== "string") {
+                        return;
+                    }
+
+                    if (!(field in item._rvpackerOriginal)) {
+                        item._rvpackerOriginal[field] = item[field];
+                    }
+
+                    item[field] = translate(item._rvpackerOriginal[field]);
+                });
+            });
+        });
+    }
+
+    function applySystem() {
+        if (!$dataSystem) {
+            return;
+        }
+
+        originalSystem = originalSystem || JsonEx.makeDeepCopy($dataSystem);
+
+        $dataSystem.gameTitle = translate(originalSystem.gameTitle);
+        $dataSystem.currencyUnit = translate(originalSystem.currencyUnit);
+
+        ["elements", "skillTypes", "weaponTypes", "armorTypes", "equipTypes"].forEach(function (name) {
+            $dataSystem[name] = translateArray(originalSystem[name]);
+        });
+
+        ["basic", "commands", "params"].forEach(function (name) {
+            $dataSystem.terms[name] = translateArray(originalSystem.terms[name]);
+        });
+
+        Object.keys(originalSystem.terms.messages).forEach(function (name) {
+            $dataSystem.terms.messages[name] = translate(originalSystem.terms.messages[name]);
+        });
+    }
+
+    function applyLanguage() {
+        applyDatabase();
+        applySystem();
+    }
+
+    LANGUAGES.forEach(function (language) {
+        DataManager._databaseFiles.push({ name: packName(language), src: "Languages/" + language + ".json" });
+    });
+
+    ConfigManager.language = "";
+
+    var _ConfigManager_makeData = ConfigManager.makeData;
+    ConfigManager.makeData = function () {
+        var config = _ConfigManager_makeData.call(this);
+        config.language = this.language;
+        return config;
+    };
+
+    var _ConfigManager_applyData = ConfigManager.applyData;
+    ConfigManager.applyData = function (config) {
+        _ConfigManager_applyData.call(this, config);
+        this.language = LANGUAGES.indexOf(config.language) >= 0 ? config.language : "";
+
+        if (DataManager.isDatabaseLoaded()) {
+            applyLanguage();
+        }
+    };
+
+    var _Scene_Boot_start = Scene_Boot.prototype.start;
+    Scene_Boot.prototype.start = function () {
+        applyLanguage();
+        _Scene_Boot_start.call(this);
+    };
+
+    var _Game_Map_displayName = Game_Map.prototype.displayName;
+    Game_Map.prototype.displayName = function () {
+        return translate(_Game_Map_displayName.call(this));
+    };
+
+    // Lines of Show Text are collected, so the whole message can be translated at once, like when writing
+    var _Game_Interpreter_command101 = Game_Interpreter.prototype.command101;
+    Game_Interpreter.prototype.command101 = function () {
+        var lines = [];
+
+        $gameMessage.add = function (text) {
+            lines.push(text.trim());
+        };
+
+        try {
+            var result = _Game_Interpreter_command101.apply(this, arguments);
+        } finally {
+            delete $gameMessage.add;
+        }
+
+        if (lines.length > 0) {
+            translate(lines.join("\n").trim())
+                .split("\n")
+                .forEach(function (line) {
+                    $gameMessage.add(line);
+                });
+        }
+
+        return result;
+    };
+
+    var _Game_Interpreter_setupChoices = Game_Interpreter.prototype.setupChoices;
+    Game_Interpreter.prototype.setupChoices = function (params) {
+        var translatedParams = params.slice();
+        translatedParams[0] = translateArray(params[0]);
+        _Game_Interpreter_setupChoices.call(this, translatedParams);
+    };
+
+    var _Window_Options_addGeneralOptions = Window_Options.prototype.addGeneralOptions;
+    Window_Options.prototype.addGeneralOptions = function () {
+        _Window_Options_addGeneralOptions.call(this);
+        this.addCommand("Language", "language");
+    };
+
+    var _Window_Options_statusText = Window_Options.prototype.statusText;
+    Window_Options.prototype.statusText = function (index) {
+        if (this.commandSymbol(index) === "language") {
+            return ConfigManager.language || "-";
+        }
+
+        return _Window_Options_statusText.call(this, index);
+    };
+
+    function cycleLanguage(window, step) {
+        var choices = [""].concat(LANGUAGES);
+        var index = (choices.indexOf(ConfigManager.language) + step + choices.length) % choices.length;
+
+        ConfigManager.language = choices[index];
+        applyLanguage();
+        window.redrawItem(window.findSymbol("language"));
+        window.playCursorSound();
+    }
+
+    var _Window_Options_processOk = Window_Options.prototype.processOk;
+    Window_Options.prototype.processOk = function () {
+        if (this.commandSymbol(this.index()) === "language") {
+            cycleLanguage(this, 1);
+        } else {
+            _Window_Options_processOk.call(this);
+        }
+    };
+
+    var _Window_Options_cursorRight = Window_Options.prototype.cursorRight;
+    Window_Options.prototype.cursorRight = function () {
+        if (this.commandSymbol(this.index()) === "language") {
+            cycleLanguage(this, 1);
+        } else {
+            _Window_Options_cursorRight.apply(this, arguments);
+        }
+    };
+
+    var _Window_Options_cursorLeft = Window_Options.prototype.cursorLeft;
+    Window_Options.prototype.cursorLeft = function () {
+        if (this.commandSymbol(this.index()) === "language") {
+            cycleLanguage(this, -1);
+        } else {
+            _Window_Options_cursorLeft.apply(this, arguments);
+        }
+    };
+})();
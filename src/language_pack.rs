@@ -0,0 +1,181 @@
+use crate::{
+    events::{Event, Output},
+    migrate::read_lines,
+};
+use sonic_rs::{from_str, prelude::*, to_string, to_string_pretty, Array, Object};
+use std::{
+    ffi::OsString,
+    fs::{create_dir_all, read_dir, read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+/// Name of the companion plugin, which swaps text at runtime.
+const LANGUAGE_PLUGIN_NAME: &str = "RvpackerLanguages";
+
+/// Source of the companion plugin. `__LANGUAGES__` is replaced with the array of exported languages.
+const LANGUAGE_PLUGIN_SOURCE: &str = include_str!("language_pack.js");
+
+/// Subdirectories of the translation directory, which contain text, that the companion plugin can swap.
+const PACKED_SUBDIRS: [&str; 2] = ["maps", "other"];
+
+/// Separator of lines inside of a single translation entry.
+const LINE_SEPARATOR: &str = r"\#";
+
+/// Collects translation pairs of the language. Keys and values are the same strings, which `write_list` and
+/// `write_system` match against game text, so multiline entries are joined with `\n` and trimmed. Untranslated
+/// entries and entries, which translation equals the original, are skipped.
+/// # Parameters
+/// * `translation_path` - path to the translation directory of the language
+fn collect_pairs(translation_path: &Path) -> Object {
+    let mut pack: Object = Object::new();
+
+    for subdir in PACKED_SUBDIRS {
+        let Ok(entries) = read_dir(translation_path.join(subdir)) else {
+            continue;
+        };
+
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let filename: OsString = entry.file_name();
+                let name: &str = filename.to_str()?.strip_suffix(".txt")?;
+
+                // Scripts are Ruby code of older engines, which the plugin can't run anyway
+                if name.ends_with("_trans") || name.ends_with("_obsolete") || name == "scripts" {
+                    None
+                } else {
+                    Some(name.to_string())
+                }
+            })
+            .collect();
+
+        names.sort();
+
+        for name in names {
+            let originals: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}.txt")));
+            let translations: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}_trans.txt")));
+
+            for (original, translation) in originals.iter().zip(translations.iter()) {
+                let original: String = original.replace(LINE_SEPARATOR, "\n").trim().to_string();
+                let translation: String = translation.replace(LINE_SEPARATOR, "\n").trim().to_string();
+
+                if original.is_empty() || translation.is_empty() || original == translation {
+                    continue;
+                }
+
+                pack.insert(&original, translation.as_str());
+            }
+        }
+    }
+
+    pack
+}
+
+/// Adds the companion plugin to the plugins list, if it isn't there yet. The list is formatted the same way
+/// `write_plugins` formats it. Returns size of the written list, if it was written.
+/// # Parameters
+/// * `plugins_file_path` - path to the original `plugins.js`
+/// * `output_plugins_file_path` - path to the output `plugins.js`
+fn register_plugin(plugins_file_path: &Path, output_plugins_file_path: &Path) -> Option<usize> {
+    // Output may already contain translated plugins, which shouldn't be overwritten with the original ones
+    let content: String = read_to_string(output_plugins_file_path)
+        .or_else(|_| read_to_string(plugins_file_path))
+        .unwrap_or_else(|_| String::from("[]"));
+
+    let start: usize = content.find('[').unwrap_or(0);
+    let end: usize = content.rfind(']').map_or(content.len(), |end: usize| end + 1);
+
+    let mut obj_arr: Vec<Object> = from_str(&content[start..end]).unwrap();
+
+    if obj_arr
+        .iter()
+        .any(|obj: &Object| obj.get(&"name").and_then(|name| name.as_str()) == Some(LANGUAGE_PLUGIN_NAME))
+    {
+        return None;
+    }
+
+    let mut plugin_obj: Object = Object::new();
+    plugin_obj.insert("name", LANGUAGE_PLUGIN_NAME);
+    plugin_obj.insert("status", true);
+    plugin_obj.insert("description", "");
+    plugin_obj.insert("parameters", Object::new());
+    obj_arr.push(plugin_obj);
+
+    let content: String = String::from("var $plugins =\n") + &to_string(&obj_arr).unwrap();
+    write(output_plugins_file_path, &content).unwrap();
+
+    Some(content.len())
+}
+
+/// Exports translations of the languages to JSON language packs, keyed by original text, to `data/Languages`, and
+/// generates the companion plugin in `js/plugins`, which lets players switch between the languages in the options
+/// menu. The plugin is also added to `js/plugins.js`.
+/// # Parameters
+/// * `languages` - language codes and paths to their translation directories
+/// * `game_path` - path to the game directory
+/// * `output_path` - path to the output directory
+/// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `file_written_msg` - message to log when file is written
+pub fn export_language_pack(
+    languages: &[(String, PathBuf)],
+    game_path: &Path,
+    output_path: &Path,
+    logging: bool,
+    output: Output,
+    file_written_msg: &str,
+) {
+    let packs_path: &Path = &output_path.join("data/Languages");
+    let plugins_path: &Path = &output_path.join("js/plugins");
+
+    create_dir_all(packs_path).unwrap();
+    create_dir_all(plugins_path).unwrap();
+
+    for (language, translation_path) in languages {
+        let pack_file_path: &Path = &packs_path.join(format!("{language}.json"));
+        let pack_content: String = to_string_pretty(&collect_pairs(translation_path)).unwrap();
+        write(pack_file_path, &pack_content).unwrap();
+
+        if output.is_json() {
+            output.emit(Event::FileWritten {
+                file: &format!("Languages/{language}.json"),
+                bytes: pack_content.len(),
+            });
+        } else if logging {
+            println!("{file_written_msg} {}", pack_file_path.display());
+        }
+    }
+
+    let languages_arr: Array = Array::from(
+        languages
+            .iter()
+            .map(|(language, _)| language.as_str())
+            .collect::<Vec<&str>>(),
+    );
+
+    let plugin_file_path: &Path = &plugins_path.join(format!("{LANGUAGE_PLUGIN_NAME}.js"));
+    let plugin_content: String = LANGUAGE_PLUGIN_SOURCE.replace("__LANGUAGES__", &to_string(&languages_arr).unwrap());
+    write(plugin_file_path, &plugin_content).unwrap();
+
+    if output.is_json() {
+        output.emit(Event::FileWritten {
+            file: &format!("plugins/{LANGUAGE_PLUGIN_NAME}.js"),
+            bytes: plugin_content.len(),
+        });
+    } else if logging {
+        println!("{file_written_msg} {}", plugin_file_path.display());
+    }
+
+    let output_plugins_file_path: &Path = &output_path.join("js/plugins.js");
+
+    if let Some(bytes) = register_plugin(&game_path.join("js/plugins.js"), output_plugins_file_path) {
+        if output.is_json() {
+            output.emit(Event::FileWritten {
+                file: "plugins.js",
+                bytes,
+            });
+        } else if logging {
+            println!("{file_written_msg} {}", output_plugins_file_path.display());
+        }
+    }
+}
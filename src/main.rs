@@ -1,21 +1,67 @@
-use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use clap::{parser::ValueSource, value_parser, Arg, ArgAction, ArgMatches, Command};
 use color_print::{cformat, cstr};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use sonic_rs::{from_str, prelude::*, Object};
+use sonic_rs::{from_str, prelude::*, to_string, Array, Object, Value};
 use std::{
-    env::args,
-    fs::{create_dir_all, read_to_string, write},
+    env::{args, temp_dir},
+    ffi::OsStr,
+    fs::{create_dir_all, read, read_dir, read_to_string, remove_dir_all, remove_file, write},
     io::stdin,
+    panic::{set_hook, PanicHookInfo},
     path::{Path, PathBuf},
     process::exit,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use sys_locale::get_locale;
 
+mod cache;
+mod catalogs;
+mod config;
+mod events;
+mod file_filter;
+mod fonts;
+mod glossary;
+mod language_pack;
+mod merge;
+mod migrate;
+mod note_tags;
+mod placeholders;
+mod pretranslate;
+mod progress;
 mod read;
+mod romanization;
+mod serve;
+mod source_hashes;
+mod sources;
+mod tui;
+mod verify;
+mod watch;
 mod write;
 
+use cache::{hash_settings, Cache};
+use catalogs::{list_languages, load_catalog, Catalog, CatalogError};
+use config::{load_config, ConfigError, ProjectConfig, CONFIG_FILENAME, EVENT_CODES};
+use events::{Event, Output, OutputFormat};
+use file_filter::FileFilter;
+use fonts::{check_glyphs, find_fonts, GlyphIssue};
+use glossary::{check_glossary, seed_glossary, GlossaryIssue};
+use language_pack::export_language_pack;
+use merge::{merge, Conflict};
+use migrate::{extract_plugins, migrate, Migration};
+use note_tags::{parse_note_tag_rules, NoteTagRule};
+use placeholders::{check_placeholders, PlaceholderIssue, PlaceholderIssueKind};
+use pretranslate::{pretranslate, LibreTranslate};
+use progress::{count_files, Progress};
+use romanization::{get_preset, parse_table, table_from_object, table_to_object, RomanizationTable, PRESETS};
+use serve::{serve, CheckResult, Transport};
+use source_hashes::{compare_sources, hash_sources, SourceChange, SourceChangeKind};
+use sources::Sources;
+use tui::{run_editor, EditorError, EditorLabels};
+use verify::{verify, TempDir, Verification};
+use watch::{watch, Writer};
+use write::decode_ini;
+
 #[derive(PartialEq, Clone, Copy)]
 enum GameType {
     Termina,
@@ -100,6 +146,15 @@ struct ProgramLocalization<'a> {
     // Command descriptions
     read_command_desc: &'a str,
     write_command_desc: &'a str,
+    watch_command_desc: &'a str,
+    verify_command_desc: &'a str,
+    migrate_command_desc: &'a str,
+    merge_command_desc: &'a str,
+    check_command_desc: &'a str,
+    pretranslate_command_desc: &'a str,
+    pack_command_desc: &'a str,
+    tui_command_desc: &'a str,
+    serve_command_desc: &'a str,
 
     // Argument descriptions
     input_dir_arg_read_desc: &'a str,
@@ -109,16 +164,44 @@ struct ProgramLocalization<'a> {
     output_dir_arg_write_desc: &'a str,
 
     shuffle_level_arg_desc: &'a str,
+    pseudo_localize_arg_desc: &'a str,
+    min_similarity_arg_desc: &'a str,
+    apply_proposals_arg_desc: &'a str,
+    merge_base_arg_desc: &'a str,
+    merge_translations_arg_desc: &'a str,
+    seed_glossary_arg_desc: &'a str,
+    from_arg_desc: &'a str,
+    to_arg_desc: &'a str,
+    url_arg_desc: &'a str,
+    api_key_arg_desc: &'a str,
+    batch_size_arg_desc: &'a str,
     disable_processing_arg_desc: &'a str,
+    only_arg_desc: &'a str,
+    exclude_arg_desc: &'a str,
 
     romanize_desc: &'a str,
+    romanize_preset_arg_desc: &'a str,
+    romanize_table_arg_desc: &'a str,
+    target_language_arg_desc: &'a str,
+    config_arg_desc: &'a str,
+    no_romanize_desc: &'a str,
+    no_disable_custom_processing_desc: &'a str,
+    no_disable_cache_desc: &'a str,
+    profile_arg_desc: &'a str,
 
     force_arg_desc: &'a str,
     append_arg_desc: &'a str,
 
     disable_custom_processing_desc: &'a str,
+    disable_cache_desc: &'a str,
+    in_game_arg_desc: &'a str,
+    preserve_formatting_arg_desc: &'a str,
+    refuse_source_changes_arg_desc: &'a str,
+    protect_codes_arg_desc: &'a str,
+    note_tags_arg_desc: &'a str,
 
     language_arg_desc: &'a str,
+    output_format_arg_desc: &'a str,
 
     log_arg_desc: &'a str,
     help_arg_desc: &'a str,
@@ -127,8 +210,22 @@ struct ProgramLocalization<'a> {
     input_dir_arg_type: &'a str,
     output_dir_arg_type: &'a str,
     disable_processing_arg_type: &'a str,
+    patterns_arg_type: &'a str,
     shuffle_arg_type: &'a str,
+    expansion_arg_type: &'a str,
+    min_similarity_arg_type: &'a str,
+    merge_base_arg_type: &'a str,
+    merge_translations_arg_type: &'a str,
+    language_code_arg_type: &'a str,
+    url_arg_type: &'a str,
+    api_key_arg_type: &'a str,
+    batch_size_arg_type: &'a str,
+    romanize_table_arg_type: &'a str,
+    note_tags_arg_type: &'a str,
+    config_arg_type: &'a str,
+    profile_arg_type: &'a str,
     language_arg_type: &'a str,
+    output_format_arg_type: &'a str,
 
     // Messages and warnings
     input_dir_not_exist: &'a str,
@@ -136,14 +233,92 @@ struct ProgramLocalization<'a> {
     original_dir_missing: &'a str,
     translation_dirs_missing: &'a str,
     file_written_msg: &'a str,
+    updating_translation_msg: &'a str,
     file_parsed_msg: &'a str,
     file_already_parsed_msg: &'a str,
     file_is_not_parsed_msg: &'a str,
     done_in_msg: &'a str,
+    progress_flag_desc: &'a str,
+    eta_label: &'a str,
+    phase_timings_msg: &'a str,
     force_mode_warning: &'a str,
     custom_processing_enabled_msg: &'a str,
     enabling_romanize_metadata_msg: &'a str,
     disabling_custom_processing_metadata_msg: &'a str,
+    metadata_conflict_msg: &'a str,
+    watching_msg: &'a str,
+    watch_write_failed_msg: &'a str,
+    in_game_requires_original_msg: &'a str,
+    source_changed_msg: &'a str,
+    source_changes_refused_msg: &'a str,
+    source_changed_label: &'a str,
+    source_added_label: &'a str,
+    source_removed_label: &'a str,
+    verify_byte_identical_msg: &'a str,
+    verify_structurally_identical_msg: &'a str,
+    verify_different_msg: &'a str,
+    verify_expected_label: &'a str,
+    verify_found_label: &'a str,
+    verify_succeeded_msg: &'a str,
+    verify_failed_msg: &'a str,
+    migrate_carried_label: &'a str,
+    migrate_proposed_label: &'a str,
+    migrate_untranslated_label: &'a str,
+    migrate_obsolete_label: &'a str,
+    migrate_was_label: &'a str,
+    migrate_translation_label: &'a str,
+    migrate_report_written_msg: &'a str,
+    migrate_proposals_applied_msg: &'a str,
+    merge_base_label: &'a str,
+    merge_no_conflicts_msg: &'a str,
+    merge_conflicts_msg: &'a str,
+    glossary_seeded_msg: &'a str,
+    glossary_missing_msg: &'a str,
+    glossary_inconsistent_msg: &'a str,
+    glossary_check_passed_msg: &'a str,
+    glossary_check_failed_msg: &'a str,
+    fonts_missing_msg: &'a str,
+    glyphs_uncovered_msg: &'a str,
+    glyph_check_passed_msg: &'a str,
+    glyph_check_failed_msg: &'a str,
+    pretranslate_filled_msg: &'a str,
+    pretranslate_skipped_msg: &'a str,
+    pretranslate_failed_msg: &'a str,
+    invalid_romanize_table_msg: &'a str,
+    placeholder_missing_msg: &'a str,
+    placeholder_duplicated_msg: &'a str,
+    placeholder_unknown_msg: &'a str,
+    placeholders_invalid_msg: &'a str,
+    pack_engine_unsupported_msg: &'a str,
+    pack_languages_missing_msg: &'a str,
+    pack_metadata_unsupported_msg: &'a str,
+    config_missing_msg: &'a str,
+    config_parse_failed_msg: &'a str,
+    config_unknown_key_msg: &'a str,
+    config_invalid_value_msg: &'a str,
+    config_unknown_profile_msg: &'a str,
+    tui_file_arg_desc: &'a str,
+    tui_file_arg_type: &'a str,
+    tui_file_missing_msg: &'a str,
+    tui_misaligned_msg: &'a str,
+    tui_original_label: &'a str,
+    tui_translation_label: &'a str,
+    tui_browse_help: &'a str,
+    tui_edit_help: &'a str,
+    tui_search_prompt: &'a str,
+    tui_saved_msg: &'a str,
+    tui_save_refused_msg: &'a str,
+    tui_unsaved_msg: &'a str,
+    tui_no_untranslated_msg: &'a str,
+    tui_not_found_msg: &'a str,
+    tui_nothing_to_undo_msg: &'a str,
+    port_arg_desc: &'a str,
+    port_arg_type: &'a str,
+    serve_listening_msg: &'a str,
+    catalog_parse_failed_msg: &'a str,
+    catalog_invalid_value_msg: &'a str,
+    catalog_unknown_key_msg: &'a str,
+    language_unknown_msg: &'a str,
 
     // Misc
     possible_values: &'a str,
@@ -161,6 +336,207 @@ impl<'a> ProgramLocalization<'a> {
         }
     }
 
+    /// Replaces the message, which field is named by the key. Returns whether the key names a message.
+    /// # Parameters
+    /// * `key` - name of the field
+    /// * `message` - message to set
+    fn set_message(&mut self, key: &str, message: &'a str) -> bool {
+        match key {
+            "about_msg" => self.about_msg = message,
+            "help_template" => self.help_template = message,
+            "subcommand_help_template" => self.subcommand_help_template = message,
+            "read_command_desc" => self.read_command_desc = message,
+            "write_command_desc" => self.write_command_desc = message,
+            "watch_command_desc" => self.watch_command_desc = message,
+            "verify_command_desc" => self.verify_command_desc = message,
+            "migrate_command_desc" => self.migrate_command_desc = message,
+            "merge_command_desc" => self.merge_command_desc = message,
+            "check_command_desc" => self.check_command_desc = message,
+            "pretranslate_command_desc" => self.pretranslate_command_desc = message,
+            "pack_command_desc" => self.pack_command_desc = message,
+            "tui_command_desc" => self.tui_command_desc = message,
+            "serve_command_desc" => self.serve_command_desc = message,
+            "input_dir_arg_read_desc" => self.input_dir_arg_read_desc = message,
+            "input_dir_arg_write_desc" => self.input_dir_arg_write_desc = message,
+            "output_dir_arg_read_desc" => self.output_dir_arg_read_desc = message,
+            "output_dir_arg_write_desc" => self.output_dir_arg_write_desc = message,
+            "shuffle_level_arg_desc" => self.shuffle_level_arg_desc = message,
+            "pseudo_localize_arg_desc" => self.pseudo_localize_arg_desc = message,
+            "min_similarity_arg_desc" => self.min_similarity_arg_desc = message,
+            "apply_proposals_arg_desc" => self.apply_proposals_arg_desc = message,
+            "merge_base_arg_desc" => self.merge_base_arg_desc = message,
+            "merge_translations_arg_desc" => self.merge_translations_arg_desc = message,
+            "seed_glossary_arg_desc" => self.seed_glossary_arg_desc = message,
+            "from_arg_desc" => self.from_arg_desc = message,
+            "to_arg_desc" => self.to_arg_desc = message,
+            "url_arg_desc" => self.url_arg_desc = message,
+            "api_key_arg_desc" => self.api_key_arg_desc = message,
+            "batch_size_arg_desc" => self.batch_size_arg_desc = message,
+            "disable_processing_arg_desc" => self.disable_processing_arg_desc = message,
+            "only_arg_desc" => self.only_arg_desc = message,
+            "exclude_arg_desc" => self.exclude_arg_desc = message,
+            "romanize_desc" => self.romanize_desc = message,
+            "romanize_preset_arg_desc" => self.romanize_preset_arg_desc = message,
+            "romanize_table_arg_desc" => self.romanize_table_arg_desc = message,
+            "target_language_arg_desc" => self.target_language_arg_desc = message,
+            "config_arg_desc" => self.config_arg_desc = message,
+            "no_romanize_desc" => self.no_romanize_desc = message,
+            "no_disable_custom_processing_desc" => self.no_disable_custom_processing_desc = message,
+            "no_disable_cache_desc" => self.no_disable_cache_desc = message,
+            "profile_arg_desc" => self.profile_arg_desc = message,
+            "force_arg_desc" => self.force_arg_desc = message,
+            "append_arg_desc" => self.append_arg_desc = message,
+            "disable_custom_processing_desc" => self.disable_custom_processing_desc = message,
+            "disable_cache_desc" => self.disable_cache_desc = message,
+            "in_game_arg_desc" => self.in_game_arg_desc = message,
+            "preserve_formatting_arg_desc" => self.preserve_formatting_arg_desc = message,
+            "refuse_source_changes_arg_desc" => self.refuse_source_changes_arg_desc = message,
+            "protect_codes_arg_desc" => self.protect_codes_arg_desc = message,
+            "note_tags_arg_desc" => self.note_tags_arg_desc = message,
+            "language_arg_desc" => self.language_arg_desc = message,
+            "output_format_arg_desc" => self.output_format_arg_desc = message,
+            "log_arg_desc" => self.log_arg_desc = message,
+            "help_arg_desc" => self.help_arg_desc = message,
+            "input_dir_arg_type" => self.input_dir_arg_type = message,
+            "output_dir_arg_type" => self.output_dir_arg_type = message,
+            "disable_processing_arg_type" => self.disable_processing_arg_type = message,
+            "patterns_arg_type" => self.patterns_arg_type = message,
+            "shuffle_arg_type" => self.shuffle_arg_type = message,
+            "expansion_arg_type" => self.expansion_arg_type = message,
+            "min_similarity_arg_type" => self.min_similarity_arg_type = message,
+            "merge_base_arg_type" => self.merge_base_arg_type = message,
+            "merge_translations_arg_type" => self.merge_translations_arg_type = message,
+            "language_code_arg_type" => self.language_code_arg_type = message,
+            "url_arg_type" => self.url_arg_type = message,
+            "api_key_arg_type" => self.api_key_arg_type = message,
+            "batch_size_arg_type" => self.batch_size_arg_type = message,
+            "romanize_table_arg_type" => self.romanize_table_arg_type = message,
+            "note_tags_arg_type" => self.note_tags_arg_type = message,
+            "config_arg_type" => self.config_arg_type = message,
+            "profile_arg_type" => self.profile_arg_type = message,
+            "language_arg_type" => self.language_arg_type = message,
+            "output_format_arg_type" => self.output_format_arg_type = message,
+            "input_dir_not_exist" => self.input_dir_not_exist = message,
+            "output_dir_not_exist" => self.output_dir_not_exist = message,
+            "original_dir_missing" => self.original_dir_missing = message,
+            "translation_dirs_missing" => self.translation_dirs_missing = message,
+            "file_written_msg" => self.file_written_msg = message,
+            "updating_translation_msg" => self.updating_translation_msg = message,
+            "file_parsed_msg" => self.file_parsed_msg = message,
+            "file_already_parsed_msg" => self.file_already_parsed_msg = message,
+            "file_is_not_parsed_msg" => self.file_is_not_parsed_msg = message,
+            "done_in_msg" => self.done_in_msg = message,
+            "progress_flag_desc" => self.progress_flag_desc = message,
+            "eta_label" => self.eta_label = message,
+            "phase_timings_msg" => self.phase_timings_msg = message,
+            "force_mode_warning" => self.force_mode_warning = message,
+            "custom_processing_enabled_msg" => self.custom_processing_enabled_msg = message,
+            "enabling_romanize_metadata_msg" => self.enabling_romanize_metadata_msg = message,
+            "disabling_custom_processing_metadata_msg" => self.disabling_custom_processing_metadata_msg = message,
+            "metadata_conflict_msg" => self.metadata_conflict_msg = message,
+            "watching_msg" => self.watching_msg = message,
+            "watch_write_failed_msg" => self.watch_write_failed_msg = message,
+            "in_game_requires_original_msg" => self.in_game_requires_original_msg = message,
+            "source_changed_msg" => self.source_changed_msg = message,
+            "source_changes_refused_msg" => self.source_changes_refused_msg = message,
+            "source_changed_label" => self.source_changed_label = message,
+            "source_added_label" => self.source_added_label = message,
+            "source_removed_label" => self.source_removed_label = message,
+            "verify_byte_identical_msg" => self.verify_byte_identical_msg = message,
+            "verify_structurally_identical_msg" => self.verify_structurally_identical_msg = message,
+            "verify_different_msg" => self.verify_different_msg = message,
+            "verify_expected_label" => self.verify_expected_label = message,
+            "verify_found_label" => self.verify_found_label = message,
+            "verify_succeeded_msg" => self.verify_succeeded_msg = message,
+            "verify_failed_msg" => self.verify_failed_msg = message,
+            "migrate_carried_label" => self.migrate_carried_label = message,
+            "migrate_proposed_label" => self.migrate_proposed_label = message,
+            "migrate_untranslated_label" => self.migrate_untranslated_label = message,
+            "migrate_obsolete_label" => self.migrate_obsolete_label = message,
+            "migrate_was_label" => self.migrate_was_label = message,
+            "migrate_translation_label" => self.migrate_translation_label = message,
+            "migrate_report_written_msg" => self.migrate_report_written_msg = message,
+            "migrate_proposals_applied_msg" => self.migrate_proposals_applied_msg = message,
+            "merge_base_label" => self.merge_base_label = message,
+            "merge_no_conflicts_msg" => self.merge_no_conflicts_msg = message,
+            "merge_conflicts_msg" => self.merge_conflicts_msg = message,
+            "glossary_seeded_msg" => self.glossary_seeded_msg = message,
+            "glossary_missing_msg" => self.glossary_missing_msg = message,
+            "glossary_inconsistent_msg" => self.glossary_inconsistent_msg = message,
+            "glossary_check_passed_msg" => self.glossary_check_passed_msg = message,
+            "glossary_check_failed_msg" => self.glossary_check_failed_msg = message,
+            "fonts_missing_msg" => self.fonts_missing_msg = message,
+            "glyphs_uncovered_msg" => self.glyphs_uncovered_msg = message,
+            "glyph_check_passed_msg" => self.glyph_check_passed_msg = message,
+            "glyph_check_failed_msg" => self.glyph_check_failed_msg = message,
+            "pretranslate_filled_msg" => self.pretranslate_filled_msg = message,
+            "pretranslate_skipped_msg" => self.pretranslate_skipped_msg = message,
+            "pretranslate_failed_msg" => self.pretranslate_failed_msg = message,
+            "invalid_romanize_table_msg" => self.invalid_romanize_table_msg = message,
+            "placeholder_missing_msg" => self.placeholder_missing_msg = message,
+            "placeholder_duplicated_msg" => self.placeholder_duplicated_msg = message,
+            "placeholder_unknown_msg" => self.placeholder_unknown_msg = message,
+            "placeholders_invalid_msg" => self.placeholders_invalid_msg = message,
+            "pack_engine_unsupported_msg" => self.pack_engine_unsupported_msg = message,
+            "pack_languages_missing_msg" => self.pack_languages_missing_msg = message,
+            "pack_metadata_unsupported_msg" => self.pack_metadata_unsupported_msg = message,
+            "config_missing_msg" => self.config_missing_msg = message,
+            "config_parse_failed_msg" => self.config_parse_failed_msg = message,
+            "config_unknown_key_msg" => self.config_unknown_key_msg = message,
+            "config_invalid_value_msg" => self.config_invalid_value_msg = message,
+            "config_unknown_profile_msg" => self.config_unknown_profile_msg = message,
+            "tui_file_arg_desc" => self.tui_file_arg_desc = message,
+            "tui_file_arg_type" => self.tui_file_arg_type = message,
+            "tui_file_missing_msg" => self.tui_file_missing_msg = message,
+            "tui_misaligned_msg" => self.tui_misaligned_msg = message,
+            "tui_original_label" => self.tui_original_label = message,
+            "tui_translation_label" => self.tui_translation_label = message,
+            "tui_browse_help" => self.tui_browse_help = message,
+            "tui_edit_help" => self.tui_edit_help = message,
+            "tui_search_prompt" => self.tui_search_prompt = message,
+            "tui_saved_msg" => self.tui_saved_msg = message,
+            "tui_save_refused_msg" => self.tui_save_refused_msg = message,
+            "tui_unsaved_msg" => self.tui_unsaved_msg = message,
+            "tui_no_untranslated_msg" => self.tui_no_untranslated_msg = message,
+            "tui_not_found_msg" => self.tui_not_found_msg = message,
+            "tui_nothing_to_undo_msg" => self.tui_nothing_to_undo_msg = message,
+            "port_arg_desc" => self.port_arg_desc = message,
+            "port_arg_type" => self.port_arg_type = message,
+            "serve_listening_msg" => self.serve_listening_msg = message,
+            "catalog_parse_failed_msg" => self.catalog_parse_failed_msg = message,
+            "catalog_invalid_value_msg" => self.catalog_invalid_value_msg = message,
+            "catalog_unknown_key_msg" => self.catalog_unknown_key_msg = message,
+            "language_unknown_msg" => self.language_unknown_msg = message,
+            "possible_values" => self.possible_values = message,
+            "example" => self.example = message,
+            "default_value" => self.default_value = message,
+            "when_reading" => self.when_reading = message,
+            "when_writing" => self.when_writing = message,
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Replaces messages with ones of the catalog, and keeps messages, that the catalog doesn't contain. Returns keys,
+    /// which don't name a message, or which value isn't a string.
+    /// # Parameters
+    /// * `catalog` - catalog to apply
+    fn apply_catalog(&mut self, catalog: &'a Catalog) -> Vec<&'a str> {
+        let mut invalid_keys: Vec<&'a str> = Vec::new();
+
+        for (key, value) in catalog.messages.iter() {
+            if !value
+                .as_str()
+                .is_some_and(|message: &str| self.set_message(key, message))
+            {
+                invalid_keys.push(key);
+            }
+        }
+
+        invalid_keys
+    }
+
     fn load_english() -> Self {
         ProgramLocalization {
             // About message and templates
@@ -183,6 +559,33 @@ impl<'a> ProgramLocalization<'a> {
             write_command_desc: cstr!(
                 r#"<bold>Writes translated files using original files from "original" or "data" ("Data") folders of input directory and writes results to "output" folder of output directory.</bold>"#
             ),
+            watch_command_desc: cstr!(
+                r#"<bold>Watches "translation/maps", "translation/other" and "translation/plugins" folders and re-writes files, which translation has changed.</bold>"#
+            ),
+            verify_command_desc: cstr!(
+                r#"<bold>Reads the game and writes it back with empty translation to a temporary directory, and compares written files with original ones, reporting paths of all differing values.</bold>"#
+            ),
+            migrate_command_desc: cstr!(
+                r#"<bold>Migrates translation to a new version of the game. Translation of unchanged lines is carried over, translation of edited lines is proposed in the report, and translation of removed lines is moved to "_obsolete" files.</bold>"#
+            ),
+            merge_command_desc: cstr!(
+                r#"<bold>Merges "translation" folders of multiple translators, which started from the same base, into "translation" folder of output directory, and lists entries, which were translated differently, in "merge-conflicts.txt".</bold>"#
+            ),
+            check_command_desc: cstr!(
+                r#"<bold>Checks, that dialogue in "maps_trans.txt" and "commonevents_trans.txt" uses agreed renderings of terms from "translation/glossary.txt" and "translation/glossary_trans.txt", and lists lines, which original contains a term, but translation lacks its rendering. Also lists characters of "_trans.txt" files, which none of the fonts in "Fonts" or "fonts" folder of the game covers.</bold>"#
+            ),
+            pretranslate_command_desc: cstr!(
+                r#"<bold>Fills empty lines of "_trans.txt" files with machine translation from a server with LibreTranslate-compatible API. "\#" separators and escape codes are kept unchanged, and filled lines are recorded in ".rvpacker-txt-rs-machine-translation.json" as machine-generated.</bold>"#
+            ),
+            pack_command_desc: cstr!(
+                r#"<bold>Exports translations of all languages from "translation/<<language>" folders to language packs in "output/data/Languages", and generates "RvpackerLanguages.js" plugin in "output/js/plugins", which lets players switch the language in the options menu. Only MV and MZ are supported.</bold>"#
+            ),
+            tui_command_desc: cstr!(
+                r#"<bold>Opens a translation pair, like "maps.txt" and "maps_trans.txt", side by side in the terminal, and lets its translation be edited line by line. Translation is written back in the same format, and saving is refused, if it would change the line count.</bold>"#
+            ),
+            serve_command_desc: cstr!(
+                r#"<bold>Starts a server, which editors and other programs can drive without spawning a process per action. JSON-RPC 2.0 requests are read from stdin, or received over HTTP on localhost with --port. Translation is held in memory between requests, and writes skip files, which are unchanged since the previous write. Methods: read, write, stats, check, lookup_by_original and update_translation.</bold>"#
+            ),
 
             // Argument descriptions
             input_dir_arg_read_desc: r#"Input directory, containing folder "original" or "data" ("Data") with original game files."#,
@@ -193,9 +596,51 @@ impl<'a> ProgramLocalization<'a> {
 
             shuffle_level_arg_desc: "With value 1, shuffles all translation lines. With value 2, shuffles all words \
                                      in translation lines.",
+            pseudo_localize_arg_desc: "Replaces translation with accented, bracketed original text, which lines are \
+                                       expanded by the specified percent, to find truncated text and text, that \
+                                       isn't extracted, in-game. Escape codes are kept unchanged. Cannot be used \
+                                       with --shuffle-level.",
+            min_similarity_arg_desc: "Minimal similarity of edited line to the old one, from 0 to 1, to propose old \
+                                      line's translation for it.",
+            apply_proposals_arg_desc: "Writes proposed translation of edited lines to the translation files, instead \
+                                       of only listing it in the report. Translation of the old lines, that was used, \
+                                       isn't moved to \"_obsolete\" files.",
+            merge_base_arg_desc: r#"Path to the "translation" folder, from which all translators started."#,
+            merge_translations_arg_desc: r#"Paths to two or more "translation" folders of translators."#,
+            seed_glossary_arg_desc: "Before checking, adds names of actors, items, skills, weapons, armors and maps \
+                                     to the glossary. Their current translation is used as the rendering, if it's \
+                                     not set already.",
+            from_arg_desc: "Language code of the original text, like ja.",
+            to_arg_desc: "Language code of the translation, like en.",
+            url_arg_desc: "Base URL of the server with LibreTranslate-compatible API.",
+            api_key_arg_desc: "API key of the server, if it requires one.",
+            batch_size_arg_desc: "Count of lines, which are sent to the server in a single request.",
             disable_processing_arg_desc: "Skips processing specified files.",
+            only_arg_desc: "Processes only files, which names without extension match any of the patterns. * \
+                            matches any characters, and ? matches a single character. When reading, lines of \
+                            selected maps are appended to maps.txt, and lines of other maps are kept. System, Scripts \
+                            and plugins are matched by these names.",
+            exclude_arg_desc: "Skips files, which names without extension match any of the patterns. Patterns have \
+                               the same syntax as in --only.",
 
             romanize_desc: r#"If you parsing text from a Japanese game, that contains symbols like 「」, which are just the Japanese quotation marks, it automatically replaces these symbols by their roman equivalents (in this case, ''). This flag will automatically be used when writing if you parsed game text with it."#,
+            romanize_preset_arg_desc: "Sets the built-in romanization table for the source language of the game. \
+                                       Used with --romanize.",
+            romanize_table_arg_desc: "Path to the file with romanization table, which lines have \
+                                      <character>=<replacement> format, like 『=«. Its entries override entries of \
+                                      the preset. Used with --romanize. The table is recorded in the metadata, so \
+                                      writing uses the same table as reading.",
+            target_language_arg_desc: "Language of the translation. Translation is stored in translation/<language> \
+                                       directory with its own metadata, and written to output/<language>. Without \
+                                       this argument, read --append updates translations of all languages at once.",
+            config_arg_desc: "Path to the project configuration file. By default, rvpacker.toml from the input \
+                              directory, from the output directory, or from the current directory, is used, if it exists. Arguments of the \
+                              command line override its settings.",
+            no_romanize_desc: "Doesn't romanize text, even if romanize is enabled in the project configuration file.",
+            no_disable_custom_processing_desc: "Keeps built-in custom processing enabled, even if it's disabled in the project configuration file.",
+            no_disable_cache_desc: "Keeps the incremental cache enabled, even if it's disabled in the project configuration file.",
+            profile_arg_desc: "Name of the profile from [profiles.<name>] table of the project configuration file, \
+                               which settings override its top level settings.",
 
             force_arg_desc: "Force rewrite all files. Cannot be used with --append.",
             append_arg_desc: "When the game, which files you've parsed, or the rvpacker-txt-rs updates, you probably \
@@ -205,7 +650,28 @@ impl<'a> ProgramLocalization<'a> {
             disable_custom_processing_desc: "Disables built-in custom processing, implemented for some games. This \
                                              flag will automatically be used when writing if you parsed game text \
                                              with it.",
+            disable_cache_desc: "Disables the incremental cache. By default, when writing, files which original \
+                                 and translation text didn't change since the previous write are skipped, and when \
+                                 reading with --append, unchanged original files are skipped.",
+            in_game_arg_desc: r#"Writes files directly to the "data" ("Data") and "js" folders of input directory, so the running game picks them up. Requires original files to be in the "original" folder."#,
+            preserve_formatting_arg_desc: "Preserves formatting of original .json files (MV/MZ only). Only changed \
+                                           text is re-serialized, and all other parts of files remain byte-identical \
+                                           to the original.",
+            refuse_source_changes_arg_desc: "Refuses to write, if original files of the game changed since the \
+                                             translation was read, instead of only warning about them.",
+            protect_codes_arg_desc: "Replaces escape codes of event text, like \\C[2] or \\N[1], with numbered \
+                                     placeholders, like {1}, so they can't be broken by the translator. Placeholders \
+                                     are replaced back with codes when writing.",
+            note_tags_arg_desc: "Names of note tags, which contents are translatable, separated by commas. If \
+                                 set, only contents of these tags are extracted from notes of database entries, \
+                                 and writing replaces only them, keeping other tags unchanged. Both \
+                                 <Tag>...</Tag> and <Tag: ...> forms are matched, unless the name is followed by \
+                                 :block or :value. Tags are recorded in the \
+                                 metadata.",
             language_arg_desc: "Sets the localization of the tool to the selected language.",
+            output_format_arg_desc: "Format of the output. json prints newline-delimited JSON events instead of \
+                                     messages, for programs, which wrap the tool. Schema of the events is described \
+                                     in README.",
 
             log_arg_desc: "Enables logging.",
             help_arg_desc: "Prints the program's help message or for the entered subcommand.",
@@ -214,8 +680,22 @@ impl<'a> ProgramLocalization<'a> {
             input_dir_arg_type: "INPUT_PATH",
             output_dir_arg_type: "OUTPUT_PATH",
             disable_processing_arg_type: "FILENAMES",
+            patterns_arg_type: "PATTERNS",
             shuffle_arg_type: "NUMBER",
+            expansion_arg_type: "PERCENT",
+            min_similarity_arg_type: "SIMILARITY",
+            merge_base_arg_type: "BASE_PATH",
+            merge_translations_arg_type: "TRANSLATION_PATHS",
+            language_code_arg_type: "LANGUAGE_CODE",
+            url_arg_type: "URL",
+            api_key_arg_type: "API_KEY",
+            batch_size_arg_type: "COUNT",
+            romanize_table_arg_type: "TABLE_PATH",
+            note_tags_arg_type: "TAGS",
+            config_arg_type: "CONFIG_PATH",
+            profile_arg_type: "PROFILE",
             language_arg_type: "LANGUAGE",
+            output_format_arg_type: "FORMAT",
 
             // Messages and warnings
             input_dir_not_exist: "Input directory does not exist.",
@@ -223,11 +703,17 @@ impl<'a> ProgramLocalization<'a> {
             original_dir_missing: r#"The "original" or "data" ("Data") folder in the input directory does not exist."#,
             translation_dirs_missing: r#"The "translation/maps" and/or "translation/other" folders in the input directory do not exist."#,
             file_written_msg: "Wrote file",
+            updating_translation_msg: "Updating translation in",
             file_parsed_msg: "Parsed file",
             file_already_parsed_msg: "file already exists. If you want to forcefully re-read all files, use --force \
                                       flag, or --append if you want append new text to already existing files.",
             file_is_not_parsed_msg: "Files aren't already parsed. Continuing as if --append flag was omitted.",
             done_in_msg: "Done in:",
+            progress_flag_desc: "Shows progress of maps, other, system, scripts and plugins phases with the number of \
+                                 processed files and the estimated remaining time, and prints time of each phase at \
+                                 the end.",
+            eta_label: "remaining",
+            phase_timings_msg: "Time of the phases:",
             force_mode_warning: "WARNING! Force mode will forcefully rewrite all your translation files in the \
                                  folder, including _trans. Input 'Y' to continue.",
             custom_processing_enabled_msg: "Custom processing for this game will be used. Use \
@@ -235,6 +721,94 @@ impl<'a> ProgramLocalization<'a> {
             enabling_romanize_metadata_msg: "Enabling romanize according to the metadata from previous read.",
             disabling_custom_processing_metadata_msg: "Disabling custom processing according to the metadata from \
                                                        previous read.",
+            metadata_conflict_msg: "Translation was read with other settings, and appending with these would change \
+                                    the format of its lines. Pass the same settings or omit them, or read with \
+                                    --force. Setting:",
+            watching_msg: "Watching translation files for changes. Press Ctrl+C to stop.",
+            watch_write_failed_msg: "Failed to write files. Watching for further changes.",
+            in_game_requires_original_msg: r#"--in-game requires original files to be in the "original" folder, as they would be overwritten otherwise."#,
+            source_changed_msg: "Original files of the game changed since the translation was read, so their new \
+                                 lines will stay untranslated, and translation of their changed lines will be lost. \
+                                 Run read --append to update the translation. Files:",
+            source_changes_refused_msg: "Writing is refused, because original files of the game changed since the \
+                                         translation was read.",
+            source_changed_label: "changed",
+            source_added_label: "added",
+            source_removed_label: "removed",
+            verify_byte_identical_msg: "is byte-identical to the original.",
+            verify_structurally_identical_msg: "is structurally identical to the original.",
+            verify_different_msg: "differs from the original:",
+            verify_expected_label: "expected",
+            verify_found_label: "found",
+            verify_succeeded_msg: "All files were written without discrepancies.",
+            verify_failed_msg: "Some files were written with discrepancies.",
+            migrate_carried_label: "carried over",
+            migrate_proposed_label: "proposed",
+            migrate_untranslated_label: "need translation",
+            migrate_obsolete_label: "obsolete",
+            migrate_was_label: "was",
+            migrate_translation_label: "translation",
+            migrate_report_written_msg: "Migration report is written to",
+            migrate_proposals_applied_msg: "Proposed translation was applied, review it using the report:",
+            merge_base_label: "base",
+            merge_no_conflicts_msg: "Translations were merged without conflicts.",
+            merge_conflicts_msg: "Some entries were translated differently, so their base translation was kept. \
+                                  These entries are listed in",
+            glossary_seeded_msg: "Terms added to the glossary:",
+            glossary_missing_msg: "Glossary doesn't exist. Use --seed-glossary to create it from names of the game \
+                                   entries.",
+            glossary_inconsistent_msg: "translation lacks the rendering of",
+            glossary_check_passed_msg: "All translations use agreed renderings of glossary terms.",
+            glossary_check_failed_msg: "Some translations lack agreed renderings of glossary terms.",
+            fonts_missing_msg: r#"Game has no fonts in "Fonts" or "fonts" folder, so glyph coverage isn't checked."#,
+            glyphs_uncovered_msg: "no font of the game covers characters",
+            glyph_check_passed_msg: "Fonts of the game cover all characters of the translation.",
+            glyph_check_failed_msg: "Some characters of the translation aren't covered by fonts of the game, so \
+                                     they'll be rendered as boxes.",
+            pretranslate_filled_msg: "Lines filled with machine translation:",
+            pretranslate_skipped_msg: "Lines left empty, because machine translation lost their escape codes:",
+            pretranslate_failed_msg: "Translation server returned an error. Already received translations are \
+                                      cached, so they won't be requested again. Error:",
+            invalid_romanize_table_msg: "Romanization table has invalid line, which doesn't have \
+                                         <character>=<replacement> format. Line:",
+            placeholder_missing_msg: "translation lacks placeholder",
+            placeholder_duplicated_msg: "translation repeats placeholder",
+            placeholder_unknown_msg: "translation contains unknown placeholder",
+            placeholders_invalid_msg: "Placeholders of some translations don't match placeholders of originals. Every \
+                                       placeholder of the original must occur in the translation exactly once.",
+            pack_engine_unsupported_msg: "Language packs are supported only for MV and MZ games.",
+            pack_languages_missing_msg: "No \"translation/<language>\" folders exist. Read the translation with \
+                                          --target-language first.",
+            pack_metadata_unsupported_msg: "Language pack can't be exported from translation, that was read with \
+                                            --romanize or --protect-codes, as its text doesn't match the game text. \
+                                            Translation:",
+            config_missing_msg: "Project configuration file does not exist.",
+            config_parse_failed_msg: "Project configuration file isn't a valid TOML:",
+            config_unknown_key_msg: "Project configuration file contains unknown setting:",
+            config_invalid_value_msg: "Project configuration file contains invalid value of setting:",
+            config_unknown_profile_msg: "Project configuration file has no such profile:",
+            tui_file_arg_desc: r#"Name of the translation pair, like "maps", "names", "actors" or "system". Pair is looked up in "maps", "other" and "plugins" folders of the translation."#,
+            tui_file_arg_type: "NAME",
+            tui_file_missing_msg: "Translation pair with this name doesn't exist:",
+            tui_misaligned_msg: "Original and translation files have different line counts, so their lines can't be paired:",
+            tui_original_label: "Original",
+            tui_translation_label: "Translation",
+            tui_browse_help: "Enter: edit  n: next untranslated  /: search  f: find next  u: undo  s: save  q: quit",
+            tui_edit_help: "Enter: apply  Alt+Enter, Ctrl+N: line break  Esc: cancel",
+            tui_search_prompt: "Search:",
+            tui_saved_msg: "Saved",
+            tui_save_refused_msg: "Saving is refused, as it would change the line count. Original file was likely read again while editing.",
+            tui_unsaved_msg: "Translation has unsaved changes. Press q again to quit without saving.",
+            tui_no_untranslated_msg: "All lines are translated.",
+            tui_not_found_msg: "Nothing found:",
+            tui_nothing_to_undo_msg: "Nothing to undo.",
+            port_arg_desc: "Port on 127.0.0.1, which the server listens on for HTTP POST requests with JSON-RPC bodies. 0 picks any free port. Without it, requests are read from stdin, and responses are written to stdout, a single JSON per line.",
+            port_arg_type: "PORT",
+            serve_listening_msg: "Serving JSON-RPC requests over",
+            catalog_parse_failed_msg: "Failed to parse the message catalog:",
+            catalog_invalid_value_msg: "Message catalog contains an invalid value:",
+            catalog_unknown_key_msg: "Message catalog contains an unknown key, or its value isn't a string:",
+            language_unknown_msg: "Messages of the language are available neither built-in, nor in catalogs.",
 
             // Misc
             possible_values: "Allowed values:",
@@ -265,6 +839,33 @@ impl<'a> ProgramLocalization<'a> {
             write_command_desc: cstr!(
                 r#"<bold>Записывает переведенные файлы, используя исходные файлы из папки "original" или "data" ("Data") входной директории, применяя текст из .txt файлов папки "translation", выводя результаты в папку "output" выходной директории.</bold>"#
             ),
+            watch_command_desc: cstr!(
+                r#"<bold>Отслеживает папки "translation/maps", "translation/other" и "translation/plugins" и перезаписывает файлы, перевод которых изменился.</bold>"#
+            ),
+            verify_command_desc: cstr!(
+                r#"<bold>Читает игру и записывает её обратно с пустым переводом во временную директорию, а затем сравнивает записанные файлы с оригинальными, выводя пути всех различающихся значений.</bold>"#
+            ),
+            migrate_command_desc: cstr!(
+                r#"<bold>Переносит перевод на новую версию игры. Перевод неизменённых строк переносится, перевод отредактированных строк предлагается в отчёте, а перевод удалённых строк перемещается в файлы "_obsolete".</bold>"#
+            ),
+            merge_command_desc: cstr!(
+                r#"<bold>Объединяет папки "translation" нескольких переводчиков, начавших с одной базы, в папку "translation" выходной директории, и перечисляет записи, переведённые по-разному, в "merge-conflicts.txt".</bold>"#
+            ),
+            check_command_desc: cstr!(
+                r#"<bold>Проверяет, что в диалогах "maps_trans.txt" и "commonevents_trans.txt" используются согласованные переводы терминов из "translation/glossary.txt" и "translation/glossary_trans.txt", и перечисляет строки, оригинал которых содержит термин, а перевод не содержит его перевода. Также перечисляет символы файлов "_trans.txt", которых нет ни в одном из шрифтов папки "Fonts" или "fonts" игры.</bold>"#
+            ),
+            pretranslate_command_desc: cstr!(
+                r#"<bold>Заполняет пустые строки файлов "_trans.txt" машинным переводом с сервера с API, совместимым с LibreTranslate. Разделители "\#" и управляющие коды сохраняются без изменений, а заполненные строки записываются в ".rvpacker-txt-rs-machine-translation.json" как машинные.</bold>"#
+            ),
+            pack_command_desc: cstr!(
+                r#"<bold>Экспортирует переводы всех языков из папок "translation/<<язык>" в языковые пакеты в "output/data/Languages" и создаёт плагин "RvpackerLanguages.js" в "output/js/plugins", позволяющий игрокам переключать язык в меню настроек. Поддерживаются только MV и MZ.</bold>"#
+            ),
+            tui_command_desc: cstr!(
+                r#"<bold>Открывает пару перевода, например, "maps.txt" и "maps_trans.txt", рядом в терминале, и позволяет изменять перевод построчно. Перевод записывается обратно в том же формате, а сохранение отклоняется, если оно изменило бы количество строк.</bold>"#
+            ),
+            serve_command_desc: cstr!(
+                r#"<bold>Запускает сервер, которым редакторы и другие программы могут управлять, не запуская процесс на каждое действие. Запросы JSON-RPC 2.0 читаются из stdin, или принимаются по HTTP на localhost с --port. Перевод хранится в памяти между запросами, а запись пропускает файлы, которые не изменились с предыдущей записи. Методы: read, write, stats, check, lookup_by_original и update_translation.</bold>"#
+            ),
 
             input_dir_arg_read_desc: r#"Входная директория, содержащая папку "original" или "data" ("Data") с оригинальными файлами игры."#,
             input_dir_arg_write_desc: r#"Входная директория, содержащая папку "original" или "data" ("Data") с оригинальными файлами игры, а также папку "translation" с .txt файлами перевода."#,
@@ -274,9 +875,51 @@ impl<'a> ProgramLocalization<'a> {
 
             shuffle_level_arg_desc: "При значении 1, перемешивает все строки перевода. При значении 2, перемешивает \
                                      все слова в строках перевода.",
+            pseudo_localize_arg_desc: "Заменяет перевод оригинальным текстом с диакритикой в квадратных скобках, \
+                                       строки которого удлинены на указанный процент, чтобы найти в игре обрезанный \
+                                       и неизвлечённый текст. Управляющие коды сохраняются без изменений. Не может \
+                                       быть использован с --shuffle-level.",
+            min_similarity_arg_desc: "Минимальное сходство отредактированной строки со старой, от 0 до 1, чтобы \
+                                      предложить для неё перевод старой строки.",
+            apply_proposals_arg_desc: "Записывает предложенный перевод отредактированных строк в файлы перевода, а не \
+                                       только перечисляет его в отчёте. Использованный перевод старых строк не \
+                                       перемещается в файлы \"_obsolete\".",
+            merge_base_arg_desc: r#"Путь к папке "translation", с которой начали все переводчики."#,
+            merge_translations_arg_desc: r#"Пути к двум или более папкам "translation" переводчиков."#,
+            seed_glossary_arg_desc: "Перед проверкой добавляет в глоссарий имена персонажей, предметов, навыков, \
+                                     оружия, брони и карт. Если перевод термина ещё не задан, используется их \
+                                     текущий перевод.",
+            from_arg_desc: "Код языка оригинального текста, например ja.",
+            to_arg_desc: "Код языка перевода, например ru.",
+            url_arg_desc: "Базовый URL сервера с API, совместимым с LibreTranslate.",
+            api_key_arg_desc: "API-ключ сервера, если он требуется.",
+            batch_size_arg_desc: "Количество строк, отправляемых на сервер в одном запросе.",
             disable_processing_arg_desc: "Не обрабатывает указанные файлы.",
+            only_arg_desc: "Обрабатывает только файлы, имена которых без расширения соответствуют любому из \
+                            шаблонов. * соответствует любым символам, а ? соответствует одному символу. При чтении \
+                            строки выбранных карт добавляются в maps.txt, а строки других карт сохраняются. System, \
+                            Scripts и плагины сопоставляются по этим именам.",
+            exclude_arg_desc: "Пропускает файлы, имена которых без расширения соответствуют любому из шаблонов. \
+                               Шаблоны имеют тот же синтаксис, что и в --only.",
 
             romanize_desc: r#"Если вы парсите текст из японскной игры, содержащей символы вроде 「」, являющимися обычными японскими кавычками, программа автоматически заменяет эти символы на их европейские эквиваленты. (в данном случае, '')"#,
+            romanize_preset_arg_desc: "Устанавливает встроенную таблицу романизации для исходного языка игры. \
+                                       Используется с --romanize.",
+            romanize_table_arg_desc: "Путь к файлу с таблицей романизации, строки которого имеют формат \
+                                      <символ>=<замена>, например 『=«. Его записи переопределяют записи \
+                                      встроенной таблицы. Используется с --romanize. Таблица записывается в \
+                                      метаданные, поэтому при записи используется та же таблица, что и при чтении.",
+            target_language_arg_desc: "Язык перевода. Перевод хранится в директории translation/<язык> с \
+                                       собственными метаданными, и записывается в output/<язык>. Без этого \
+                                       аргумента read --append обновляет переводы всех языков сразу.",
+            config_arg_desc: "Путь к файлу конфигурации проекта. По умолчанию используется rvpacker.toml из \
+                              входной директории, из выходной директории или из текущей директории, если он существует. Аргументы командной \
+                              строки переопределяют его настройки.",
+            no_romanize_desc: "Не романизирует текст, даже если romanize включён в файле конфигурации проекта.",
+            no_disable_custom_processing_desc: "Оставляет встроенные способы обработки включёнными, даже если они отключены в файле конфигурации проекта.",
+            no_disable_cache_desc: "Оставляет инкрементальный кэш включённым, даже если он отключён в файле конфигурации проекта.",
+            profile_arg_desc: "Название профиля из таблицы [profiles.<название>] файла конфигурации проекта, \
+                               настройки которого переопределяют его настройки верхнего уровня.",
 
             force_arg_desc: "Принудительно перезаписать все файлы. Не может быть использован с --append.",
             append_arg_desc: "Когда игра, файлы которой вы распарсили, либо же rvpacker-txt-rs обновляется, вы, \
@@ -287,7 +930,28 @@ impl<'a> ProgramLocalization<'a> {
             disable_custom_processing_desc: "Отключает использование индивидуальных способов обработки текста, \
                                              имплементированных для некоторых игр. Этот флаг будет автоматически \
                                              применён при записи, если текст игры был прочитан с его использованием.",
+            disable_cache_desc: "Отключает инкрементальный кэш. По умолчанию, при записи пропускаются файлы, \
+                                 оригинальный текст и перевод которых не изменились с прошлой записи, а при чтении с \
+                                 флагом --append пропускаются неизменённые оригинальные файлы.",
+            in_game_arg_desc: r#"Записывает файлы напрямую в папки "data" ("Data") и "js" входной директории, чтобы запущенная игра подхватила их. Требует, чтобы оригинальные файлы находились в папке "original"."#,
+            preserve_formatting_arg_desc: "Сохраняет форматирование оригинальных .json файлов (только MV/MZ). \
+                                           Заново сериализуется только изменённый текст, а все остальные части \
+                                           файлов остаются идентичными оригиналу побайтово.",
+            refuse_source_changes_arg_desc: "Отказывается записывать, если оригинальные файлы игры изменились \
+                                             после чтения перевода, вместо того, чтобы только предупреждать о них.",
+            protect_codes_arg_desc: "Заменяет управляющие коды текста событий, такие как \\C[2] или \\N[1], \
+                                     нумерованными заполнителями, такими как {1}, чтобы переводчик не мог их \
+                                     повредить. При записи заполнители заменяются обратно на коды.",
+            note_tags_arg_desc: "Имена тегов заметок, содержимое которых переводимо, разделённые запятыми. Если \
+                                 установлено, из заметок записей базы данных извлекается только содержимое этих \
+                                 тегов, и при записи заменяется только оно, а остальные теги остаются без \
+                                 изменений. Распознаются обе формы: <Тег>...</Тег> и <Тег: ...>, если за именем \
+                                 не следует :block или :value. Теги \
+                                 записываются в метаданные.",
             language_arg_desc: "Устанавливает локализацию инструмента на выбранный язык.",
+            output_format_arg_desc: "Формат вывода. json выводит события в формате JSON, по одному на строку, \
+                                     вместо сообщений, для программ, которые используют инструмент. Схема событий \
+                                     описана в README.",
 
             log_arg_desc: "Включает логирование.",
             help_arg_desc: "Выводит справочную информацию по программе либо по введёной команде.",
@@ -295,20 +959,39 @@ impl<'a> ProgramLocalization<'a> {
             input_dir_arg_type: "ВХОДНОЙ_ПУТЬ",
             output_dir_arg_type: "ВЫХОДНОЙ_ПУТЬ",
             disable_processing_arg_type: "ИМЕНА_ФАЙЛОВ",
+            patterns_arg_type: "ШАБЛОНЫ",
             shuffle_arg_type: "ЦИФРА",
+            expansion_arg_type: "ПРОЦЕНТ",
+            min_similarity_arg_type: "СХОДСТВО",
+            merge_base_arg_type: "БАЗОВЫЙ_ПУТЬ",
+            merge_translations_arg_type: "ПУТИ_ПЕРЕВОДОВ",
+            language_code_arg_type: "КОД_ЯЗЫКА",
+            url_arg_type: "URL",
+            api_key_arg_type: "API_КЛЮЧ",
+            batch_size_arg_type: "КОЛИЧЕСТВО",
+            romanize_table_arg_type: "ПУТЬ_ТАБЛИЦЫ",
+            note_tags_arg_type: "ТЕГИ",
+            config_arg_type: "ПУТЬ_КОНФИГА",
+            profile_arg_type: "ПРОФИЛЬ",
             language_arg_type: "ЯЗЫК",
+            output_format_arg_type: "ФОРМАТ",
 
             input_dir_not_exist: "Входная директория не существует.",
             output_dir_not_exist: "Выходная директория не существует.",
             original_dir_missing: r#"Папка "original" или "data" ("Data") входной директории не существует."#,
             translation_dirs_missing: r#"Папки "translation/maps" и/или "translation/other" входной директории не существуют."#,
             file_written_msg: "Записан файл",
+            updating_translation_msg: "Обновление перевода в",
             file_parsed_msg: "Распарсен файл",
             file_already_parsed_msg: "уже существует. Если вы хотите принудительно перезаписать все файлы, \
                                       используйте флаг --force, или --append если вы хотите добавить новый текст в \
                                       файлы.",
             file_is_not_parsed_msg: "Файлы ещё не распарсены. Продолжаем в режиме с выключенным флагом --append.",
             done_in_msg: "Выполнено за:",
+            progress_flag_desc: "Показывает прогресс этапов maps, other, system, scripts и plugins с количеством \
+                                 обработанных файлов и оставшимся временем, и выводит время каждого этапа в конце.",
+            eta_label: "осталось",
+            phase_timings_msg: "Время этапов:",
             force_mode_warning: "ПРЕДУПРЕЖДЕНИЕ! Принудительный режим полностью перепишет все ваши файлы перевода, \
                                  включая _trans-файлы. Введите Y, чтобы продолжить.",
             custom_processing_enabled_msg: "Индивидуальная обработка текста будет использована для этой игры. \
@@ -317,6 +1000,94 @@ impl<'a> ProgramLocalization<'a> {
                                              будет использована.",
             disabling_custom_processing_metadata_msg: "В соответсвии с метаданными из прошлого чтения, индивидуальная \
                                                        обработка текста будет выключена.",
+            metadata_conflict_msg: "Перевод был прочитан с другими настройками, и добавление с этими изменило бы \
+                                    формат его строк. Передайте те же настройки или не указывайте их, или прочитайте \
+                                    с --force. Настройка:",
+            watching_msg: "Отслеживание изменений файлов перевода. Нажмите Ctrl+C, чтобы остановить.",
+            watch_write_failed_msg: "Не удалось записать файлы. Отслеживание изменений продолжается.",
+            in_game_requires_original_msg: r#"--in-game требует, чтобы оригинальные файлы находились в папке "original", так как иначе они будут перезаписаны."#,
+            source_changed_msg: "Оригинальные файлы игры изменились после чтения перевода, поэтому их новые строки \
+                                 останутся непереведёнными, а перевод их изменённых строк будет потерян. Запустите \
+                                 read --append, чтобы обновить перевод. Файлы:",
+            source_changes_refused_msg: "Запись отклонена, так как оригинальные файлы игры изменились после \
+                                         чтения перевода.",
+            source_changed_label: "изменён",
+            source_added_label: "добавлен",
+            source_removed_label: "удалён",
+            verify_byte_identical_msg: "побайтово идентичен оригиналу.",
+            verify_structurally_identical_msg: "структурно идентичен оригиналу.",
+            verify_different_msg: "отличается от оригинала:",
+            verify_expected_label: "ожидалось",
+            verify_found_label: "получено",
+            verify_succeeded_msg: "Все файлы были записаны без расхождений.",
+            verify_failed_msg: "Некоторые файлы были записаны с расхождениями.",
+            migrate_carried_label: "перенесено",
+            migrate_proposed_label: "предложено",
+            migrate_untranslated_label: "требуют перевода",
+            migrate_obsolete_label: "устарело",
+            migrate_was_label: "было",
+            migrate_translation_label: "перевод",
+            migrate_report_written_msg: "Отчёт о переносе записан в",
+            migrate_proposals_applied_msg: "Предложенный перевод применён, проверьте его по отчёту:",
+            merge_base_label: "база",
+            merge_no_conflicts_msg: "Переводы были объединены без конфликтов.",
+            merge_conflicts_msg: "Некоторые записи были переведены по-разному, поэтому для них был сохранён базовый \
+                                  перевод. Эти записи перечислены в",
+            glossary_seeded_msg: "Терминов добавлено в глоссарий:",
+            glossary_missing_msg: "Глоссарий не существует. Используйте --seed-glossary, чтобы создать его из имён \
+                                   записей игры.",
+            glossary_inconsistent_msg: "перевод не содержит перевода термина",
+            glossary_check_passed_msg: "Все переводы используют согласованные переводы терминов глоссария.",
+            glossary_check_failed_msg: "Некоторые переводы не содержат согласованных переводов терминов глоссария.",
+            fonts_missing_msg: r#"В папке "Fonts" или "fonts" игры нет шрифтов, поэтому покрытие глифов не проверяется."#,
+            glyphs_uncovered_msg: "ни один шрифт игры не содержит символы",
+            glyph_check_passed_msg: "Шрифты игры содержат все символы перевода.",
+            glyph_check_failed_msg: "Некоторые символы перевода отсутствуют в шрифтах игры, поэтому они будут \
+                                     отображаться квадратами.",
+            pretranslate_filled_msg: "Строк заполнено машинным переводом:",
+            pretranslate_skipped_msg: "Строк оставлено пустыми, так как машинный перевод потерял их управляющие коды:",
+            pretranslate_failed_msg: "Сервер перевода вернул ошибку. Уже полученные переводы кэшированы, поэтому \
+                                      они не будут запрошены повторно. Ошибка:",
+            invalid_romanize_table_msg: "Таблица романизации содержит некорректную строку, не имеющую формата \
+                                         <символ>=<замена>. Строка:",
+            placeholder_missing_msg: "перевод не содержит заполнитель",
+            placeholder_duplicated_msg: "перевод повторяет заполнитель",
+            placeholder_unknown_msg: "перевод содержит неизвестный заполнитель",
+            placeholders_invalid_msg: "Заполнители некоторых переводов не совпадают с заполнителями оригиналов. \
+                                       Каждый заполнитель оригинала должен встречаться в переводе ровно один раз.",
+            pack_engine_unsupported_msg: "Языковые пакеты поддерживаются только для игр на MV и MZ.",
+            pack_languages_missing_msg: "Папки \"translation/<язык>\" не существуют. Сначала прочитайте перевод с \
+                                          --target-language.",
+            pack_metadata_unsupported_msg: "Языковой пакет нельзя экспортировать из перевода, прочитанного с \
+                                            --romanize или --protect-codes, так как его текст не совпадает с текстом \
+                                            игры. Перевод:",
+            config_missing_msg: "Файл конфигурации проекта не существует.",
+            config_parse_failed_msg: "Файл конфигурации проекта не является корректным TOML:",
+            config_unknown_key_msg: "Файл конфигурации проекта содержит неизвестную настройку:",
+            config_invalid_value_msg: "Файл конфигурации проекта содержит некорректное значение настройки:",
+            config_unknown_profile_msg: "Файл конфигурации проекта не содержит профиль:",
+            tui_file_arg_desc: r#"Имя пары перевода, например, "maps", "names", "actors" или "system". Пара ищется в папках "maps", "other" и "plugins" перевода."#,
+            tui_file_arg_type: "ИМЯ",
+            tui_file_missing_msg: "Пара перевода с таким именем не существует:",
+            tui_misaligned_msg: "Файлы оригинала и перевода содержат разное количество строк, поэтому их строки нельзя сопоставить:",
+            tui_original_label: "Оригинал",
+            tui_translation_label: "Перевод",
+            tui_browse_help: "Enter: изменить  n: следующая непереведённая  /: поиск  f: найти далее  u: отменить  s: сохранить  q: выйти",
+            tui_edit_help: "Enter: применить  Alt+Enter, Ctrl+N: перенос строки  Esc: отменить",
+            tui_search_prompt: "Поиск:",
+            tui_saved_msg: "Сохранено",
+            tui_save_refused_msg: "Сохранение отклонено, так как оно изменило бы количество строк. Вероятно, файл оригинала был прочитан заново во время редактирования.",
+            tui_unsaved_msg: "Перевод содержит несохранённые изменения. Нажмите q ещё раз, чтобы выйти без сохранения.",
+            tui_no_untranslated_msg: "Все строки переведены.",
+            tui_not_found_msg: "Ничего не найдено:",
+            tui_nothing_to_undo_msg: "Нечего отменять.",
+            port_arg_desc: "Порт на 127.0.0.1, на котором сервер принимает HTTP POST запросы с телом JSON-RPC. 0 выбирает любой свободный порт. Без него запросы читаются из stdin, а ответы записываются в stdout, по одному JSON в строке.",
+            port_arg_type: "ПОРТ",
+            serve_listening_msg: "Обслуживаются запросы JSON-RPC через",
+            catalog_parse_failed_msg: "Не удалось разобрать каталог сообщений:",
+            catalog_invalid_value_msg: "Каталог сообщений содержит недопустимое значение:",
+            catalog_unknown_key_msg: "Каталог сообщений содержит неизвестный ключ, или его значение не строка:",
+            language_unknown_msg: "Сообщения этого языка недоступны ни встроенными, ни в каталогах.",
 
             possible_values: "Разрешённые значения:",
             example: "Пример:",
@@ -336,76 +1107,23 @@ pub static INVALID_MULTILINE_VARIABLE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(
 pub static INVALID_VARIABLE_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[+-]?[0-9]+$|^///|---|restrict eval").unwrap());
 pub static SELECT_WORDS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\S+").unwrap());
+/// Matches RPG Maker escape codes, like `\C[2]`, `\N[1]`, `\{`, `\.` or `\FS<24>` of plugins.
+pub static ESCAPE_CODE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\\(?:[A-Za-z]+(?:\[[^\]]*\]|<[^>]*>)?|[{}$.|!<>^\\])").unwrap());
+pub static HTML_TITLE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)(<title[^>]*>)(.*?)(</title>)").unwrap());
 
-pub fn romanize_string<T>(string: T) -> String
+pub fn romanize_string<T>(string: T, table: &RomanizationTable) -> String
 where
     T: AsRef<str>,
     String: From<T>,
 {
-    let actual_string: String = String::from(string);
     let mut result: String = String::new();
 
-    for char in actual_string.chars() {
-        let replacement: &str = match char {
-            '。' => ".",
-            '、' | '，' => ",",
-            '・' => "·",
-            '゠' => "–",
-            '＝' | 'ー' => "—",
-            '「' | '」' | '〈' | '〉' => "'",
-            '『' | '』' | '《' | '》' => "\"",
-            '（' | '〔' | '｟' | '〘' => "(",
-            '）' | '〕' | '｠' | '〙' => ")",
-            '｛' => "{",
-            '｝' => "}",
-            '［' | '【' | '〖' | '〚' => "[",
-            '］' | '】' | '〗' | '〛' => "]",
-            '〜' => "~",
-            '？' => "?",
-            '！' => "!",
-            '：' => ":",
-            '※' => "·",
-            '…' | '‥' => "...",
-            '　' => " ",
-            'Ⅰ' => "I",
-            'ⅰ' => "i",
-            'Ⅱ' => "II",
-            'ⅱ' => "ii",
-            'Ⅲ' => "III",
-            'ⅲ' => "iii",
-            'Ⅳ' => "IV",
-            'ⅳ' => "iv",
-            'Ⅴ' => "V",
-            'ⅴ' => "v",
-            'Ⅵ' => "VI",
-            'ⅵ' => "vi",
-            'Ⅶ' => "VII",
-            'ⅶ' => "vii",
-            'Ⅷ' => "VIII",
-            'ⅷ' => "viii",
-            'Ⅸ' => "IX",
-            'ⅸ' => "ix",
-            'Ⅹ' => "X",
-            'ⅹ' => "x",
-            'Ⅺ' => "XI",
-            'ⅺ' => "xi",
-            'Ⅻ' => "XII",
-            'ⅻ' => "xii",
-            'Ⅼ' => "L",
-            'ⅼ' => "l",
-            'Ⅽ' => "C",
-            'ⅽ' => "c",
-            'Ⅾ' => "D",
-            'ⅾ' => "d",
-            'Ⅿ' => "M",
-            'ⅿ' => "m",
-            _ => {
-                result.push(char);
-                continue;
-            }
-        };
-
-        result.push_str(replacement);
+    for char in String::from(string).chars() {
+        match table.get(&char) {
+            Some(replacement) => result.push_str(replacement),
+            None => result.push(char),
+        }
     }
 
     result
@@ -423,13 +1141,43 @@ fn get_game_type(game_title: String) -> Option<&'static GameType> {
     }
 }
 
+/// Returns whether the argument was passed on the command line, rather than taken from its default value.
+/// # Parameters
+/// * `matches` - arguments of the program or the subcommand
+/// * `id` - id of the argument
+fn is_from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+/// Returns whether the data file is a map.
+/// # Parameters
+/// * `name` - name of the file without extension
+fn is_map_name(name: &str) -> bool {
+    name.starts_with("Map") && name.as_bytes().get(3).is_some_and(u8::is_ascii_digit)
+}
+
 // this function probably should be replaced by some clap-native equivalent
-fn preparse_arguments() -> (Language, Option<String>) {
+/// Returns code of the language of messages, and the subcommand.
+fn preparse_arguments() -> (String, Option<String>) {
     let mut locale: String = get_locale().unwrap_or_else(|| String::from("en_US"));
 
     let args_vec: Vec<String> = args().collect();
 
-    let subcommand: Option<String> = if ["read", "write"].contains(&args_vec[1].as_str()) {
+    let subcommand: Option<String> = if [
+        "read",
+        "write",
+        "watch",
+        "verify",
+        "migrate",
+        "merge",
+        "check",
+        "pretranslate",
+        "pack",
+        "tui",
+        "serve",
+    ]
+    .contains(&args_vec[1].as_str())
+    {
         Some(args_vec[1].clone())
     } else {
         None
@@ -441,16 +1189,45 @@ fn preparse_arguments() -> (Language, Option<String>) {
         }
     }
 
-    if let Some((first, _)) = locale.split_once('_') {
+    if let Some((first, _)) = locale.split_once(['_', '-']) {
         locale = first.to_string()
     }
 
-    match locale.as_str() {
-        "ru" | "uk" | "be" => (Language::Russian, subcommand),
-        _ => (Language::English, subcommand),
+    // Without their own catalogs, languages, which speakers usually understand Russian, fall back to it
+    if list_languages().contains(&locale) {
+        (locale, subcommand)
+    } else if matches!(locale.as_str(), "uk" | "be") {
+        (String::from("ru"), subcommand)
+    } else {
+        (String::from("en"), subcommand)
     }
 }
 
+/// Returns paths of language directories of the translation directory, which contain `maps` or `other`
+/// subdirectory, sorted by language.
+/// # Parameters
+/// * `translation_root` - path to the translation directory
+fn get_language_paths(translation_root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = read_dir(translation_root) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path: &PathBuf| {
+            !matches!(
+                path.file_name().and_then(OsStr::to_str),
+                Some("maps" | "other" | "plugins")
+            )
+        })
+        .filter(|path: &PathBuf| path.join("maps").is_dir() || path.join("other").is_dir())
+        .collect();
+
+    paths.sort();
+    paths
+}
+
 fn detect_engine_type(original_path: &Path) -> (EngineType, PathBuf, PathBuf) {
     let engine_configs: [(EngineType, &str, &str); 4] = [
         (EngineType::New, "System.json", "Scripts.rvdata2"),
@@ -470,489 +1247,2567 @@ fn detect_engine_type(original_path: &Path) -> (EngineType, PathBuf, PathBuf) {
     panic!("Couldn't determine game engine");
 }
 
-fn main() {
-    let start_time: Instant = Instant::now();
+/// Settings of subcommands, which process the game, resolved from the command line, the project configuration and
+/// the metadata of the translation.
+struct Settings<'a> {
+    localization: &'a ProgramLocalization<'a>,
+    output: Output,
+    progress: Progress,
+    logging: bool,
+    file_filter: FileFilter,
+    input_dir: &'a Path,
+    original_path: &'a Path,
+    data_path: &'a Path,
+    translation_root: &'a Path,
+    translation_path: &'a Path,
+    output_root: &'a Path,
+    output_path: &'a Path,
+    target_language: Option<&'a String>,
+    maps_path: &'a Path,
+    other_path: &'a Path,
+    metadata_file_path: &'a Path,
+    cache_file_path: &'a Path,
+    engine_type: EngineType,
+    system_file_path: PathBuf,
+    scripts_file_path: PathBuf,
+    game_type: Option<&'a GameType>,
+    romanize: bool,
+    /// Table is recorded in the metadata even without --romanize, so it's used, when romanize is enabled on append
+    romanization_table: RomanizationTable,
+    protect_codes: bool,
+    event_codes: Option<Vec<u16>>,
+    note_tag_rules: Vec<NoteTagRule>,
+    recorded_source_hashes: Option<Value>,
+    disable_custom_processing: bool,
+    disable_cache: bool,
+    disable_maps_processing: bool,
+    disable_other_processing: bool,
+    disable_system_processing: bool,
+    disable_plugins_processing: bool,
+}
 
-    let (language, subcommand): (Language, Option<String>) = preparse_arguments();
-    let localization: ProgramLocalization = ProgramLocalization::new(language);
+impl Settings<'_> {
+    /// Returns the table to romanize text with, or None, if text isn't romanized.
+    fn romanization(&self) -> Option<&RomanizationTable> {
+        self.romanize.then_some(&self.romanization_table)
+    }
 
-    let (input_dir_arg_desc, output_dir_arg_desc) = if let Some(subcommand) = subcommand {
-        match subcommand.as_str() {
-            "read" => (
-                localization.input_dir_arg_read_desc.to_string(),
-                localization.output_dir_arg_read_desc.to_string(),
-            ),
-            "write" => (
-                localization.input_dir_arg_write_desc.to_string(),
-                localization.output_dir_arg_write_desc.to_string(),
-            ),
-            _ => unreachable!(),
+    /// Returns whether the data file is processed by readers and writers of other files.
+    /// # Parameters
+    /// * `name` - name of the file without extension
+    /// * `writing` - whether files are written, as scripts have their own writer
+    fn is_other_name(&self, name: &str, writing: bool) -> bool {
+        match name {
+            "Tilesets" | "Animations" | "System" => false,
+            "Scripts" => !writing,
+            "States" => !self
+                .game_type
+                .is_some_and(|game_type: &GameType| game_type == GameType::Termina),
+            _ => !name.starts_with("Map"),
         }
-    } else {
-        (
-            format!(
-                "{} {}\n{} {}",
-                localization.when_reading,
-                localization.input_dir_arg_read_desc,
-                localization.when_writing,
-                localization.input_dir_arg_write_desc
-            ),
-            format!(
-                "{} {}\n{} {}",
-                localization.when_reading,
-                localization.output_dir_arg_read_desc,
-                localization.when_writing,
-                localization.output_dir_arg_write_desc
-            ),
-        )
-    };
-
-    let input_dir_arg: Arg = Arg::new("input-dir")
-        .short('i')
-        .long("input-dir")
-        .global(true)
-        .help(input_dir_arg_desc)
-        .value_name(localization.input_dir_arg_type)
-        .value_parser(value_parser!(PathBuf))
-        .default_value("./")
-        .hide_default_value(true)
-        .display_order(0);
-
-    let output_dir_arg: Arg = Arg::new("output-dir")
-        .short('o')
-        .long("output-dir")
-        .global(true)
-        .help(output_dir_arg_desc)
-        .value_name(localization.output_dir_arg_type)
-        .value_parser(value_parser!(PathBuf))
-        .default_value("./")
-        .hide_default_value(true)
-        .display_order(1);
+    }
 
-    let shuffle_level_arg: Arg = Arg::new("shuffle-level")
-        .short('s')
-        .long("shuffle-level")
-        .action(ArgAction::Set)
-        .value_name(localization.shuffle_arg_type)
-        .default_value("0")
-        .value_parser(value_parser!(u8).range(0..=2))
-        .help(cformat!(
-            "{}\n{} --shuffle-level 1.<bold>\n[{} 0, 1, 2]\n[{} 0]</bold>",
-            localization.shuffle_level_arg_desc,
-            localization.example,
-            localization.possible_values,
-            localization.default_value,
-        ))
-        .hide_default_value(true)
-        .display_order(2);
+    /// Returns whether text of the source file is read into the translation, used to record hashes of the sources.
+    fn is_source_processed(&self, filename: &str) -> bool {
+        let name: &str = filename.split_once('.').map_or(filename, |(name, _)| name);
 
-    let disable_processing_arg: Arg = Arg::new("disable-processing")
-        .long("disable-processing")
-        .value_delimiter(',')
-        .value_name(localization.disable_processing_arg_type)
-        .help(cformat!(
-            "{}\n{} --disable-processing=maps,other,system.<bold>\n[{} maps, other, system, plugins]</bold>",
-            localization.disable_processing_arg_desc,
-            localization.example,
-            localization.possible_values,
-        ))
-        .global(true)
-        .value_parser(["maps", "other", "system", "plugins"])
-        .display_order(3);
+        let disabled: bool = match name {
+            "System" => self.disable_system_processing,
+            "Scripts" => self.disable_plugins_processing,
+            _ if is_map_name(name) => self.disable_maps_processing,
+            _ => self.disable_other_processing,
+        };
 
-    let romanize_arg: Arg = Arg::new("romanize")
-        .short('r')
-        .long("romanize")
-        .global(true)
-        .action(ArgAction::SetTrue)
-        .help(localization.romanize_desc)
-        .display_order(4);
+        !disabled && self.file_filter.matches(filename)
+    }
 
-    let force_flag: Arg = Arg::new("force")
-        .short('f')
-        .long("force")
-        .action(ArgAction::SetTrue)
-        .help(localization.force_arg_desc)
-        .display_order(95)
-        .conflicts_with("append");
+    /// Reads the game into the translation directory, and records settings of the reading in its metadata.
+    /// # Parameters
+    /// * `translation_path` - path to the translation directory
+    /// * `processing_type` - whether to read in default mode, force rewrite or append new text to existing files
+    /// * `source_hashes` - hashes of the original data files
+    fn read_translation(&self, translation_path: &Path, processing_type: &ProcessingMode, source_hashes: &Object) {
+        use read::*;
 
-    let append_flag: Arg = Arg::new("append")
-        .short('a')
-        .long("append")
-        .action(ArgAction::SetTrue)
-        .help(localization.append_arg_desc)
-        .display_order(96);
+        let &Settings {
+            localization,
+            output,
+            ref progress,
+            logging,
+            ref file_filter,
+            original_path,
+            ref engine_type,
+            ref system_file_path,
+            ref scripts_file_path,
+            game_type,
+            romanize,
+            ref romanization_table,
+            protect_codes,
+            ref note_tag_rules,
+            disable_custom_processing,
+            disable_cache,
+            disable_maps_processing,
+            disable_other_processing,
+            disable_system_processing,
+            disable_plugins_processing,
+            ..
+        } = self;
+        let romanization: Option<&RomanizationTable> = self.romanization();
+        let event_codes: Option<&[u16]> = self.event_codes.as_deref();
+
+        let (maps_path, other_path, metadata_file_path, cache_file_path) = (
+            &translation_path.join("maps"),
+            &translation_path.join("other"),
+            &translation_path.join(".rvpacker-txt-rs-metadata.json"),
+            &translation_path.join(".rvpacker-txt-rs-cache.json"),
+        );
 
-    let disable_custom_processing_flag: Arg = Arg::new("disable-custom-processing")
-        .long("disable-custom-processing")
-        .action(ArgAction::SetTrue)
-        .global(true)
-        .help(localization.disable_custom_processing_desc)
-        .display_order(97);
+        create_dir_all(maps_path).unwrap();
+        create_dir_all(other_path).unwrap();
 
-    let language_arg: Arg = Arg::new("language")
-        .short('l')
-        .long("language")
-        .value_name(localization.language_arg_type)
-        .global(true)
-        .help(cformat!(
-            "{}\n{} --language en.<bold>\n[{} en, ru]</bold>",
-            localization.language_arg_desc,
-            localization.example,
-            localization.possible_values,
-        ))
-        .value_parser(["en", "ru"])
-        .display_order(98);
+        let mut metadata: Object = Object::new();
+        metadata.insert("romanize", romanize);
+        metadata.insert("disableCustomProcessing", disable_custom_processing);
+        metadata.insert("romanizationTable", table_to_object(romanization_table));
+        metadata.insert("protectCodes", protect_codes);
+        metadata.insert(
+            "noteTags",
+            Array::from(
+                note_tag_rules
+                    .iter()
+                    .map(|rule: &NoteTagRule| rule.rule.as_str())
+                    .collect::<Vec<&str>>(),
+            ),
+        );
 
-    let log_flag: Arg = Arg::new("log")
-        .long("log")
-        .action(ArgAction::SetTrue)
-        .global(true)
-        .help(localization.log_arg_desc)
-        .display_order(99);
+        if let Some(event_codes) = event_codes {
+            metadata.insert("eventCodes", Array::from(event_codes.to_vec()));
+        }
 
-    let help_flag: Arg = Arg::new("help")
-        .short('h')
-        .long("help")
-        .help(localization.help_arg_desc)
-        .action(ArgAction::Help)
-        .display_order(100);
+        // Translation files of skipped files stay the same, so their hashes from the previous read are kept
+        let is_extracted = |filename: &str| -> bool {
+            let name: &str = filename.split_once('.').map_or(filename, |(name, _)| name);
+
+            // Scripts are always read again
+            let translation_file_path: Option<PathBuf> = if is_map_name(name) {
+                Some(maps_path.join("maps_trans.txt"))
+            } else if name == "Scripts" {
+                None
+            } else {
+                Some(other_path.join(name.to_lowercase() + "_trans.txt"))
+            };
+
+            self.is_source_processed(filename)
+                && (processing_type != ProcessingMode::Default
+                    || translation_file_path.is_none_or(|path: PathBuf| !path.exists()))
+        };
 
-    let silent_flag: Arg = Arg::new("silent").long("silent").hide(true).action(ArgAction::SetTrue);
+        let previous_hashes: Value = read_to_string(metadata_file_path)
+            .ok()
+            .and_then(|content: String| from_str::<Object>(&content).ok())
+            .and_then(|previous_metadata: Object| previous_metadata.get(&"sourceHashes").cloned())
+            .unwrap_or_default();
 
-    let read_subcommand: Command = Command::new("read")
-        .disable_help_flag(true)
-        .help_template(localization.subcommand_help_template)
-        .about(localization.read_command_desc)
-        .args([force_flag, append_flag, silent_flag])
-        .arg(&help_flag);
+        let mut recorded_hashes: Object = Object::new();
 
-    let write_subcommand: Command = Command::new("write")
-        .disable_help_flag(true)
-        .help_template(localization.subcommand_help_template)
-        .about(localization.write_command_desc)
-        .args([shuffle_level_arg])
-        .arg(&help_flag);
+        for (filename, hash) in source_hashes.iter() {
+            if is_extracted(filename) {
+                recorded_hashes.insert(&filename, hash.clone());
+            } else if let Some(previous_hash) = previous_hashes.get(filename) {
+                recorded_hashes.insert(&filename, previous_hash.clone());
+            }
+        }
 
-    let cli: Command = Command::new("")
-        .disable_version_flag(true)
-        .disable_help_subcommand(true)
-        .disable_help_flag(true)
-        .next_line_help(true)
-        .term_width(120)
-        .about(localization.about_msg)
-        .help_template(localization.help_template)
-        .subcommands([read_subcommand, write_subcommand])
-        .args([
-            input_dir_arg,
-            output_dir_arg,
-            disable_processing_arg,
-            romanize_arg,
-            language_arg,
-            disable_custom_processing_flag,
-            log_flag,
-            help_flag,
-        ])
-        .hide_possible_values(true);
+        metadata.insert("sourceHashes", recorded_hashes);
+
+        write(metadata_file_path, to_string(&metadata).unwrap()).unwrap();
+
+        let cache: Option<Cache> = (!disable_cache).then(|| {
+            Cache::load(
+                cache_file_path,
+                hash_settings(
+                    romanization,
+                    protect_codes,
+                    event_codes,
+                    note_tag_rules,
+                    game_type,
+                    engine_type,
+                    false,
+                    None,
+                    None,
+                ),
+            )
+        });
 
-    let matches: ArgMatches = cli.get_matches();
-    let (subcommand, subcommand_matches): (&str, &ArgMatches) = matches.subcommand().unwrap();
+        if !disable_maps_processing {
+            progress.run_phase(
+                "maps",
+                count_files(original_path, |name: &str| {
+                    is_map_name(name) && file_filter.matches(name)
+                }),
+                || {
+                    read_map(
+                        original_path,
+                        maps_path,
+                        romanization,
+                        protect_codes,
+                        event_codes,
+                        logging,
+                        output,
+                        progress,
+                        localization.file_parsed_msg,
+                        localization.file_already_parsed_msg,
+                        localization.file_is_not_parsed_msg,
+                        game_type,
+                        processing_type,
+                        engine_type,
+                        file_filter,
+                        cache.as_ref(),
+                    );
+                },
+            );
+        }
 
-    let (disable_maps_processing, disable_other_processing, disable_system_processing, disable_plugins_processing) =
-        matches
-            .get_many::<&str>("disable-processing")
-            .map(|disable_processing_args| {
-                let mut flags = (false, false, false, false);
-
-                for disable_processing_of in disable_processing_args {
-                    match *disable_processing_of {
-                        "maps" => flags.0 = true,
-                        "other" => flags.1 = true,
-                        "system" => flags.2 = true,
-                        "plugins" => flags.3 = true,
-                        _ => {}
-                    }
-                }
-                flags
-            })
-            .unwrap_or((false, false, false, false));
+        if !disable_other_processing {
+            progress.run_phase(
+                "other",
+                count_files(original_path, |name: &str| {
+                    self.is_other_name(name, false) && file_filter.matches(name)
+                }),
+                || {
+                    read_other(
+                        original_path,
+                        other_path,
+                        romanization,
+                        protect_codes,
+                        event_codes,
+                        note_tag_rules,
+                        logging,
+                        output,
+                        progress,
+                        localization.file_parsed_msg,
+                        localization.file_already_parsed_msg,
+                        localization.file_is_not_parsed_msg,
+                        game_type,
+                        processing_type,
+                        engine_type,
+                        file_filter,
+                        cache.as_ref(),
+                    );
+                },
+            );
+        }
 
-    let logging: bool = matches.get_flag("log");
-    let disable_custom_processing: bool = matches.get_flag("disable-custom-processing");
-    let mut romanize: bool = matches.get_flag("romanize");
+        if !disable_system_processing {
+            progress.run_phase("system", 1, || {
+                read_system(
+                    system_file_path,
+                    other_path,
+                    romanization,
+                    logging,
+                    output,
+                    progress,
+                    localization.file_parsed_msg,
+                    localization.file_already_parsed_msg,
+                    localization.file_is_not_parsed_msg,
+                    processing_type,
+                    engine_type,
+                    cache.as_ref(),
+                );
+            });
+        }
 
-    let input_dir: &Path = matches.get_one::<PathBuf>("input-dir").unwrap();
+        if !disable_plugins_processing && engine_type != EngineType::New {
+            progress.run_phase("scripts", 1, || {
+                read_scripts(
+                    scripts_file_path,
+                    other_path,
+                    romanization,
+                    logging,
+                    output,
+                    progress,
+                    localization.file_parsed_msg,
+                );
+            });
+        }
 
-    if !input_dir.exists() {
-        panic!("{}", localization.input_dir_not_exist);
+        if let Some(cache) = cache {
+            cache.save();
+        }
     }
+}
 
-    let output_dir: &Path = matches.get_one::<PathBuf>("output-dir").unwrap();
+/// Merges translation trees of multiple translators into the translation directory, and writes conflicting
+/// translations next to it.
+/// # Parameters
+/// * `subcommand_matches` - arguments of the subcommand
+/// * `translation_path` - path to the translation directory, which the merged translation is written to
+/// * `localization` - messages of the program
+/// * `output` - where and how messages are printed
+fn run_merge(
+    subcommand_matches: &ArgMatches,
+    translation_path: &Path,
+    localization: &ProgramLocalization,
+    output: Output,
+) {
+    let base_path: &Path = subcommand_matches.get_one::<PathBuf>("base").unwrap();
+    let translation_paths: Vec<PathBuf> = subcommand_matches
+        .get_many::<PathBuf>("translations")
+        .unwrap()
+        .cloned()
+        .collect();
+
+    create_dir_all(translation_path).unwrap();
+
+    let conflicts: Vec<Conflict> = merge(base_path, &translation_paths, translation_path);
+    let conflicts_path: &Path = &translation_path.join("merge-conflicts.txt");
+
+    if conflicts.is_empty() {
+        // Conflicts of the previous merge are resolved
+        if conflicts_path.exists() {
+            remove_file(conflicts_path).unwrap();
+        }
 
-    if !output_dir.exists() {
-        panic!("{}", localization.output_dir_not_exist)
-    }
+        output.print_message(localization.merge_no_conflicts_msg);
+    } else {
+        let mut conflicts_content: String = String::new();
 
-    let mut original_path: &Path = &input_dir.join("original");
-    let data_path: PathBuf = input_dir.join("data");
+        for conflict in conflicts {
+            conflicts_content.push_str(&format!(
+                "{}: {}\n  {}: {}\n",
+                conflict.filename, conflict.original, localization.merge_base_label, conflict.base
+            ));
 
-    if !original_path.exists() {
-        original_path = &data_path;
+            for (path, translation) in translation_paths.iter().zip(conflict.translations) {
+                if let Some(translation) = translation {
+                    conflicts_content.push_str(&format!("  {}: {translation}\n", path.display()));
+                }
+            }
 
-        if !original_path.exists() {
-            panic!("{}", localization.original_dir_missing)
+            conflicts_content.push('\n');
         }
+
+        write(conflicts_path, conflicts_content).unwrap();
+        output.print_message(&format!(
+            "{} {}",
+            localization.merge_conflicts_msg,
+            conflicts_path.display()
+        ));
     }
+}
 
-    let (maps_path, other_path, metadata_file_path) = if *output_dir.as_os_str() == *"./" {
-        (
-            &input_dir.join("translation/maps"),
-            &input_dir.join("translation/other"),
-            &input_dir.join("translation/.rvpacker-txt-rs-metadata.json"),
-        )
-    } else {
-        (
-            &output_dir.join("translation/maps"),
-            &output_dir.join("translation/other"),
-            &output_dir.join("translation/.rvpacker-txt-rs-metadata.json"),
-        )
+/// Opens the editor of the translation file.
+/// # Parameters
+/// * `subcommand_matches` - arguments of the subcommand
+/// * `translation_path` - path to the translation directory
+/// * `localization` - messages of the program
+fn run_tui(subcommand_matches: &ArgMatches, translation_path: &Path, localization: &ProgramLocalization) {
+    let name: &str = subcommand_matches.get_one::<String>("file").unwrap();
+
+    let labels: EditorLabels = EditorLabels {
+        original_label: localization.tui_original_label,
+        translation_label: localization.tui_translation_label,
+        browse_help: localization.tui_browse_help,
+        edit_help: localization.tui_edit_help,
+        search_prompt: localization.tui_search_prompt,
+        saved_msg: localization.tui_saved_msg,
+        save_refused_msg: localization.tui_save_refused_msg,
+        unsaved_msg: localization.tui_unsaved_msg,
+        no_untranslated_msg: localization.tui_no_untranslated_msg,
+        not_found_msg: localization.tui_not_found_msg,
+        nothing_to_undo_msg: localization.tui_nothing_to_undo_msg,
     };
 
-    let (engine_type, system_file_path, scripts_file_path) = detect_engine_type(original_path);
+    match run_editor(translation_path, name, &labels) {
+        Ok(()) => {}
+        Err(EditorError::Missing) => panic!("{} {name}", localization.tui_file_missing_msg),
+        Err(EditorError::Misaligned) => panic!("{} {name}", localization.tui_misaligned_msg),
+    }
+}
 
-    let mut game_type: Option<&GameType> = if disable_custom_processing {
-        None
-    } else {
-        let game_title: String = if engine_type == EngineType::New {
-            let system_obj: Object = from_str::<Object>(&read_to_string(&system_file_path).unwrap()).unwrap();
-            system_obj["gameTitle"].as_str().unwrap().to_string()
-        } else {
-            let ini_file_path: &Path = &input_dir.join("Game.ini");
-            let ini_file_content: String = read_to_string(ini_file_path).unwrap();
+/// Reads the game into the translation directory, or into translation directories of all languages, when appending
+/// without target language. Returns time, which was spent waiting for confirmation of the user.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+fn run_read(settings: &Settings, subcommand_matches: &ArgMatches) -> f64 {
+    let &Settings {
+        localization,
+        output,
+        original_path,
+        translation_root,
+        translation_path,
+        target_language,
+        ..
+    } = settings;
+
+    let force: bool = subcommand_matches.get_flag("force");
+    let append: bool = subcommand_matches.get_flag("append");
+    let silent: bool = subcommand_matches.get_flag("silent");
+    let mut wait_time: f64 = 0f64;
 
-            let mut game_title: Option<String> = None;
+    let processing_type: &ProcessingMode = &if force {
+        if !silent {
+            let start_time: Instant = Instant::now();
+            output.print_warning("force_mode", localization.force_mode_warning);
 
-            for line in ini_file_content.lines() {
-                if line.to_lowercase().starts_with("title") {
-                    game_title = Some(line.splitn(2, "=").last().unwrap().trim().to_string());
-                }
+            let mut buf: String = String::new();
+            stdin().read_line(&mut buf).unwrap();
+
+            if buf.trim_end() != "Y" {
+                exit(0);
             }
 
-            game_title.unwrap()
-        };
+            wait_time += start_time.elapsed().as_secs_f64();
+        }
 
-        get_game_type(game_title)
+        ProcessingMode::Force
+    } else if append {
+        ProcessingMode::Append
+    } else {
+        ProcessingMode::Default
     };
 
-    if game_type.is_some() {
-        println!("{}", localization.custom_processing_enabled_msg);
+    let source_hashes: Object = hash_sources(original_path);
+
+    // Appending without target language updates translations of all languages at once
+    let read_translation_paths: Vec<PathBuf> = if append && target_language.is_none() {
+        let mut paths: Vec<PathBuf> = get_language_paths(translation_root);
+
+        if paths.is_empty() || translation_root.join("maps").exists() {
+            paths.insert(0, translation_root.to_path_buf());
+        }
+
+        paths
+    } else {
+        vec![translation_path.to_path_buf()]
+    };
+
+    for translation_path in read_translation_paths.iter() {
+        if read_translation_paths.len() > 1 {
+            output.print_message(&format!(
+                "{} {}",
+                localization.updating_translation_msg,
+                translation_path.display()
+            ));
+        }
+
+        settings.read_translation(translation_path, processing_type, &source_hashes);
     }
 
-    let mut wait_time: f64 = 0f64;
+    wait_time
+}
 
-    if subcommand == "read" {
-        use read::*;
+/// Reads the game and writes it back to a temporary directory, and compares written files with the original ones.
+/// Returns whether any file differs.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+fn run_verify(settings: &Settings, subcommand_matches: &ArgMatches) -> bool {
+    use read::*;
+    use write::*;
+
+    let &Settings {
+        localization,
+        output,
+        ref progress,
+        logging,
+        ref file_filter,
+        original_path,
+        ref engine_type,
+        ref system_file_path,
+        ref scripts_file_path,
+        game_type,
+        disable_maps_processing,
+        disable_other_processing,
+        disable_system_processing,
+        disable_plugins_processing,
+        ..
+    } = settings;
+    let romanization: Option<&RomanizationTable> = settings.romanization();
+    let event_codes: Option<&[u16]> = settings.event_codes.as_deref();
+
+    let mut failed: bool = false;
+
+    // Files are written the same way, as by write with the same flags
+    let preserve_formatting: bool =
+        subcommand_matches.get_flag("preserve-formatting") && engine_type == EngineType::New;
+
+    // Translation and output are written to a temporary directory, so existing translation isn't touched
+    let verify_dir: TempDir = TempDir::new(temp_dir().join(format!("rvpacker-txt-rs-verify-{}", std::process::id())));
+    let verify_path: &Path = verify_dir.path();
+    let verify_maps_path: &Path = &verify_path.join("translation/maps");
+    let verify_other_path: &Path = &verify_path.join("translation/other");
+    let verify_output_path: &Path = &verify_path.join("output");
+
+    create_dir_all(verify_maps_path).unwrap();
+    create_dir_all(verify_other_path).unwrap();
+    create_dir_all(verify_output_path).unwrap();
+
+    if !disable_maps_processing {
+        read_map(
+            original_path,
+            verify_maps_path,
+            romanization,
+            false,
+            event_codes,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            game_type,
+            &ProcessingMode::Force,
+            engine_type,
+            file_filter,
+            None,
+        );
+
+        write_maps(
+            verify_maps_path,
+            original_path,
+            verify_output_path,
+            romanization,
+            false,
+            event_codes,
+            0,
+            None,
+            false,
+            output,
+            progress,
+            localization.file_written_msg,
+            game_type,
+            engine_type,
+            file_filter,
+            None,
+            None,
+            preserve_formatting,
+        );
+    }
 
-        let force: bool = subcommand_matches.get_flag("force");
-        let append: bool = subcommand_matches.get_flag("append");
-        let silent: bool = subcommand_matches.get_flag("silent");
+    if !disable_other_processing {
+        read_other(
+            original_path,
+            verify_other_path,
+            romanization,
+            false,
+            event_codes,
+            &[],
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            game_type,
+            &ProcessingMode::Force,
+            engine_type,
+            file_filter,
+            None,
+        );
+
+        write_other(
+            verify_other_path,
+            original_path,
+            verify_output_path,
+            romanization,
+            false,
+            event_codes,
+            &[],
+            0,
+            None,
+            false,
+            output,
+            progress,
+            localization.file_written_msg,
+            game_type,
+            engine_type,
+            file_filter,
+            None,
+            None,
+            preserve_formatting,
+        );
+    }
 
-        let processing_type: &ProcessingMode = &if force {
-            if !silent {
-                let start_time: Instant = Instant::now();
-                println!("{}", localization.force_mode_warning);
+    if !disable_system_processing {
+        read_system(
+            system_file_path,
+            verify_other_path,
+            romanization,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            &ProcessingMode::Force,
+            engine_type,
+            None,
+        );
+
+        write_system(
+            system_file_path,
+            verify_other_path,
+            verify_output_path,
+            romanization,
+            0,
+            None,
+            false,
+            output,
+            progress,
+            localization.file_written_msg,
+            engine_type,
+            None,
+            None,
+            preserve_formatting,
+        );
+    }
 
-                let mut buf: String = String::new();
-                stdin().read_line(&mut buf).unwrap();
+    if !disable_plugins_processing && engine_type != EngineType::New {
+        read_scripts(
+            scripts_file_path,
+            verify_other_path,
+            romanization,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+        );
+
+        write_scripts(
+            scripts_file_path,
+            verify_other_path,
+            verify_output_path,
+            romanization,
+            None,
+            false,
+            output,
+            progress,
+            engine_type,
+            localization.file_written_msg,
+            None,
+        );
+    }
 
-                if buf.trim_end() != "Y" {
-                    exit(0);
+    for (filename, verification) in verify(original_path, verify_output_path) {
+        match verification {
+            Verification::ByteIdentical => {
+                if logging {
+                    output.print_message(&format!("{filename} {}", localization.verify_byte_identical_msg));
+                }
+            }
+            Verification::StructurallyIdentical => {
+                if logging {
+                    output.print_message(&format!(
+                        "{filename} {}",
+                        localization.verify_structurally_identical_msg
+                    ));
                 }
-
-                wait_time += start_time.elapsed().as_secs_f64();
             }
+            Verification::Different(discrepancies) => {
+                failed = true;
+                output.print_message(&format!("{filename} {}", localization.verify_different_msg));
+
+                for discrepancy in discrepancies {
+                    output.print_message(&format!(
+                        "  {}: {} {}, {} {}",
+                        discrepancy.path,
+                        localization.verify_expected_label,
+                        discrepancy.expected,
+                        localization.verify_found_label,
+                        discrepancy.found
+                    ));
+                }
+            }
+        }
+    }
+
+    if failed {
+        output.print_message(localization.verify_failed_msg);
+    } else {
+        output.print_message(localization.verify_succeeded_msg);
+    }
+
+    failed
+}
+
+/// Optionally seeds the glossary, and checks consistency of the glossary terms and coverage of the translation by
+/// glyphs of the game fonts. Returns whether any check failed.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+fn run_check(settings: &Settings, subcommand_matches: &ArgMatches) -> bool {
+    let &Settings {
+        localization,
+        output,
+        input_dir,
+        original_path,
+        maps_path,
+        other_path,
+        ref engine_type,
+        ref system_file_path,
+        ..
+    } = settings;
+    let romanization: Option<&RomanizationTable> = settings.romanization();
+
+    let mut failed: bool = false;
+    let glossary_path: &Path = &maps_path.with_file_name("glossary.txt");
+
+    if subcommand_matches.get_flag("seed-glossary") {
+        if !maps_path.exists() || !other_path.exists() {
+            panic!("{}", localization.translation_dirs_missing);
+        }
+
+        let added: usize = seed_glossary(
+            glossary_path,
+            original_path,
+            maps_path,
+            other_path,
+            romanization,
+            engine_type,
+        );
+        output.print_message(&format!("{} {added}", localization.glossary_seeded_msg));
+    }
+
+    if glossary_path.exists() {
+        let issues: Vec<GlossaryIssue> = check_glossary(glossary_path, maps_path.parent().unwrap());
 
-            ProcessingMode::Force
-        } else if append {
-            ProcessingMode::Append
+        for issue in issues.iter() {
+            output.print_message(&format!(
+                "{}:{}: {} \"{}\" - \"{}\"",
+                issue.filename, issue.line, localization.glossary_inconsistent_msg, issue.term, issue.rendering
+            ));
+        }
+
+        if issues.is_empty() {
+            output.print_message(localization.glossary_check_passed_msg);
         } else {
-            ProcessingMode::Default
-        };
+            failed = true;
+            output.print_message(localization.glossary_check_failed_msg);
+        }
+    } else {
+        output.print_message(localization.glossary_missing_msg);
+    }
 
-        create_dir_all(maps_path).unwrap();
-        create_dir_all(other_path).unwrap();
+    let font_paths: Vec<PathBuf> = find_fonts(input_dir, system_file_path, engine_type);
 
-        write(
-            metadata_file_path,
-            format!(r#"{{"romanize":{romanize},"disableCustomProcessing":{disable_custom_processing}}}"#),
-        )
-        .unwrap();
+    if font_paths.is_empty() {
+        output.print_message(localization.fonts_missing_msg);
+    } else {
+        let issues: Vec<GlyphIssue> = check_glyphs(&font_paths, maps_path.parent().unwrap());
+
+        for issue in issues.iter() {
+            output.print_message(&format!(
+                "{}:{}: {} {}",
+                issue.filename,
+                issue.line,
+                localization.glyphs_uncovered_msg,
+                issue.chars.iter().collect::<String>()
+            ));
+        }
 
-        if !disable_maps_processing {
-            read_map(
-                original_path,
-                maps_path,
-                romanize,
-                logging,
-                localization.file_parsed_msg,
-                localization.file_already_parsed_msg,
-                localization.file_is_not_parsed_msg,
-                game_type,
-                processing_type,
-                &engine_type,
-            );
+        if issues.is_empty() {
+            output.print_message(localization.glyph_check_passed_msg);
+        } else {
+            failed = true;
+            output.print_message(localization.glyph_check_failed_msg);
         }
+    }
 
-        if !disable_other_processing {
-            read_other(
-                original_path,
-                other_path,
-                romanize,
-                logging,
-                localization.file_parsed_msg,
-                localization.file_already_parsed_msg,
-                localization.file_is_not_parsed_msg,
-                game_type,
-                processing_type,
-                &engine_type,
-            );
+    failed
+}
+
+/// Fills empty translation lines with machine translation. Returns whether the translation failed.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+fn run_pretranslate(settings: &Settings, subcommand_matches: &ArgMatches) -> bool {
+    let &Settings {
+        localization,
+        output,
+        logging,
+        maps_path,
+        other_path,
+        disable_maps_processing,
+        disable_other_processing,
+        disable_system_processing,
+        disable_plugins_processing,
+        ..
+    } = settings;
+
+    if !maps_path.exists() || !other_path.exists() {
+        panic!("{}", localization.translation_dirs_missing);
+    }
+
+    let backend: LibreTranslate = LibreTranslate::new(
+        subcommand_matches.get_one::<String>("url").unwrap(),
+        subcommand_matches.get_one::<String>("api-key").map(String::as_str),
+        subcommand_matches.get_one::<String>("from").unwrap(),
+        subcommand_matches.get_one::<String>("to").unwrap(),
+    );
+
+    let result: Result<(usize, usize), String> = pretranslate(
+        maps_path.parent().unwrap(),
+        &backend,
+        *subcommand_matches.get_one::<usize>("batch-size").unwrap(),
+        |subdir: &str, name: &str| match (subdir, name) {
+            ("maps", _) => !disable_maps_processing,
+            ("plugins", _) | ("other", "scripts") => !disable_plugins_processing,
+            ("other", "system") => !disable_system_processing,
+            _ => !disable_other_processing,
+        },
+        logging,
+        output,
+        localization.file_written_msg,
+    );
+
+    match result {
+        Ok((filled, skipped)) => {
+            output.print_message(&format!("{} {filled}", localization.pretranslate_filled_msg));
+
+            if skipped > 0 {
+                output.print_message(&format!("{} {skipped}", localization.pretranslate_skipped_msg));
+            }
+
+            false
+        }
+        Err(err) => {
+            output.print_message(&format!("{} {err}", localization.pretranslate_failed_msg));
+            true
         }
+    }
+}
 
-        if !disable_system_processing {
-            read_system(
-                &system_file_path,
-                other_path,
-                romanize,
-                logging,
-                localization.file_parsed_msg,
-                localization.file_already_parsed_msg,
-                localization.file_is_not_parsed_msg,
-                processing_type,
-                &engine_type,
-            );
+/// Exports translations of the target language, or of all languages, as a language pack, which the game loads at
+/// runtime.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+fn run_pack(settings: &Settings) {
+    let &Settings {
+        localization,
+        output,
+        logging,
+        input_dir,
+        translation_root,
+        translation_path,
+        output_root,
+        target_language,
+        ref engine_type,
+        ..
+    } = settings;
+
+    if engine_type != EngineType::New {
+        panic!("{}", localization.pack_engine_unsupported_msg);
+    }
+
+    let language_paths: Vec<PathBuf> = match target_language {
+        Some(_) => vec![translation_path.to_path_buf()],
+        None => get_language_paths(translation_root),
+    };
+
+    if language_paths.is_empty() || !language_paths.iter().all(|path: &PathBuf| path.exists()) {
+        panic!("{}", localization.pack_languages_missing_msg);
+    }
+
+    let mut languages: Vec<(String, PathBuf)> = Vec::with_capacity(language_paths.len());
+
+    for path in language_paths {
+        let metadata: Object = read_to_string(path.join(".rvpacker-txt-rs-metadata.json"))
+            .ok()
+            .and_then(|content: String| from_str(&content).ok())
+            .unwrap_or_default();
+
+        // Romanized or protected text differs from the text, which the plugin sees at runtime
+        if ["romanize", "protectCodes"]
+            .into_iter()
+            .any(|key: &str| metadata.get(&key).and_then(|value| value.as_bool()) == Some(true))
+        {
+            panic!("{} {}", localization.pack_metadata_unsupported_msg, path.display());
         }
 
-        if !disable_plugins_processing && engine_type != EngineType::New {
-            read_scripts(
-                &scripts_file_path,
-                other_path,
-                romanize,
-                logging,
-                localization.file_parsed_msg,
-            );
+        let language: String = path.file_name().unwrap().to_string_lossy().into_owned();
+        languages.push((language, path));
+    }
+
+    export_language_pack(
+        &languages,
+        input_dir,
+        output_root,
+        logging,
+        output,
+        localization.file_written_msg,
+    );
+}
+
+/// Migrates the translation to a new version of the game: translation of unchanged lines is carried over, and
+/// translation of edited lines is proposed in the report, or applied.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+fn run_migrate(settings: &Settings, subcommand_matches: &ArgMatches) {
+    use read::*;
+
+    let &Settings {
+        localization,
+        output,
+        ref progress,
+        ref file_filter,
+        input_dir,
+        original_path,
+        translation_path,
+        maps_path,
+        other_path,
+        metadata_file_path,
+        ref engine_type,
+        ref system_file_path,
+        ref scripts_file_path,
+        game_type,
+        protect_codes,
+        ref note_tag_rules,
+        disable_maps_processing,
+        disable_other_processing,
+        disable_system_processing,
+        disable_plugins_processing,
+        ..
+    } = settings;
+    let romanization: Option<&RomanizationTable> = settings.romanization();
+    let event_codes: Option<&[u16]> = settings.event_codes.as_deref();
+
+    if !maps_path.exists() || !other_path.exists() {
+        panic!("{}", localization.translation_dirs_missing);
+    }
+
+    // New version of the game is read to a temporary directory, and then compared with existing translation
+    let migrate_path: &Path = &temp_dir().join(format!("rvpacker-txt-rs-migrate-{}", std::process::id()));
+    let new_maps_path: &Path = &migrate_path.join("maps");
+    let new_other_path: &Path = &migrate_path.join("other");
+
+    create_dir_all(new_maps_path).unwrap();
+    create_dir_all(new_other_path).unwrap();
+
+    if !disable_maps_processing {
+        read_map(
+            original_path,
+            new_maps_path,
+            romanization,
+            protect_codes,
+            event_codes,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            game_type,
+            &ProcessingMode::Force,
+            engine_type,
+            file_filter,
+            None,
+        );
+    }
+
+    if !disable_other_processing {
+        read_other(
+            original_path,
+            new_other_path,
+            romanization,
+            protect_codes,
+            event_codes,
+            note_tag_rules,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            game_type,
+            &ProcessingMode::Force,
+            engine_type,
+            file_filter,
+            None,
+        );
+    }
+
+    if !disable_system_processing {
+        read_system(
+            system_file_path,
+            new_other_path,
+            romanization,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+            localization.file_already_parsed_msg,
+            localization.file_is_not_parsed_msg,
+            &ProcessingMode::Force,
+            engine_type,
+            None,
+        );
+    }
+
+    if !disable_plugins_processing && engine_type != EngineType::New {
+        read_scripts(
+            scripts_file_path,
+            new_other_path,
+            romanization,
+            false,
+            output,
+            progress,
+            localization.file_parsed_msg,
+        );
+    }
+
+    let min_similarity: f64 = *subcommand_matches.get_one("min-similarity").unwrap();
+    let apply_proposals: bool = subcommand_matches.get_flag("apply-proposals");
+
+    let mut migrations: Vec<Migration> = Vec::new();
+
+    if !disable_maps_processing {
+        migrations.extend(migrate(
+            maps_path,
+            new_maps_path,
+            min_similarity,
+            apply_proposals,
+            |_| true,
+        ));
+    }
+
+    // Files of disabled categories don't exist in the new extraction, so they mustn't be migrated
+    migrations.extend(migrate(
+        other_path,
+        new_other_path,
+        min_similarity,
+        apply_proposals,
+        |name: &str| match name {
+            "system" => !disable_system_processing,
+            "scripts" => !disable_plugins_processing,
+            _ => !disable_other_processing,
+        },
+    ));
+
+    let plugins_path: &Path = &translation_path.join("plugins");
+
+    if !disable_plugins_processing && engine_type == EngineType::New && plugins_path.exists() {
+        let new_plugins_path: &Path = &migrate_path.join("plugins");
+        create_dir_all(new_plugins_path).unwrap();
+
+        if let Some(plugins) = extract_plugins(&input_dir.join("js/plugins.js"), plugins_path, new_plugins_path) {
+            migrations.extend(migrate(
+                plugins_path,
+                new_plugins_path,
+                min_similarity,
+                apply_proposals,
+                |name: &str| name == "plugins",
+            ));
+
+            write(plugins_path.join("plugins.json"), plugins).unwrap();
         }
-    } else {
-        use write::*;
+    }
 
-        if !maps_path.exists() || !other_path.exists() {
-            panic!("{}", localization.translation_dirs_missing);
+    remove_dir_all(migrate_path).unwrap();
+
+    // Migrated translation matches the current originals, so it mustn't be reported as outdated when writing
+    if metadata_file_path.exists() {
+        let mut metadata: Object = from_str(&read_to_string(metadata_file_path).unwrap()).unwrap();
+        let mut source_hashes: Object = Object::new();
+
+        if let Some(previous_hashes) = metadata.get(&"sourceHashes").and_then(|value| value.as_object()) {
+            for (filename, hash) in previous_hashes.iter() {
+                if !settings.is_source_processed(filename) {
+                    source_hashes.insert(&filename, hash.clone());
+                }
+            }
         }
 
-        let plugins_path: &Path = &input_dir.join("translation/plugins");
+        for (filename, hash) in hash_sources(original_path).iter() {
+            if settings.is_source_processed(filename) {
+                source_hashes.insert(&filename, hash.clone());
+            }
+        }
 
-        let (data_output_path, plugins_output_path) = if *output_dir.as_os_str() == *"./" {
-            (&input_dir.join("output/data"), &input_dir.join("output/js"))
-        } else {
-            (&output_dir.join("output/data"), &output_dir.join("output/js"))
-        };
+        metadata.insert("sourceHashes", source_hashes);
+        write(metadata_file_path, to_string(&metadata).unwrap()).unwrap();
+    }
 
-        if engine_type == EngineType::New {
-            create_dir_all(data_output_path).unwrap();
-            create_dir_all(plugins_output_path).unwrap();
-        } else {
-            create_dir_all(output_dir.join("output/Data")).unwrap();
+    let mut report: String = String::new();
+
+    for migration in migrations {
+        let summary: String = format!(
+            "{}: {} {}, {} {}, {} {}, {} {}",
+            migration.filename,
+            localization.migrate_carried_label,
+            migration.carried,
+            localization.migrate_proposed_label,
+            migration.proposals.len(),
+            localization.migrate_untranslated_label,
+            migration.untranslated.len(),
+            localization.migrate_obsolete_label,
+            migration.obsolete.len()
+        );
+
+        output.print_message(&summary);
+        report.push_str(&summary);
+        report.push('\n');
+
+        for proposal in migration.proposals {
+            report.push_str(&format!(
+                "\n[{:.0}%] {}\n  {}: {}\n  {}: {}\n",
+                proposal.similarity * 100.0,
+                proposal.original,
+                localization.migrate_was_label,
+                proposal.old_original,
+                localization.migrate_translation_label,
+                proposal.translation
+            ));
         }
 
-        let shuffle_level: u8 = *subcommand_matches.get_one("shuffle-level").unwrap();
+        for original in migration.untranslated {
+            report.push_str(&format!("\n[+] {original}\n"));
+        }
+
+        report.push('\n');
+    }
+
+    let report_path: &Path = &maps_path.parent().unwrap().join("migration-report.txt");
+    write(report_path, report).unwrap();
+
+    output.print_message(&format!(
+        "{} {}",
+        if apply_proposals {
+            localization.migrate_proposals_applied_msg
+        } else {
+            localization.migrate_report_written_msg
+        },
+        report_path.display()
+    ));
+}
 
-        if metadata_file_path.exists() {
-            let metadata: Object = from_str(&read_to_string(metadata_file_path).unwrap()).unwrap();
+/// Writes the translation to the output directory once, or whenever it changes, or on requests of the server.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand` - name of the subcommand: write, watch or serve
+/// * `subcommand_matches` - arguments of the subcommand
+/// * `config` - project configuration, which provides the shuffle level
+fn run_write(settings: &Settings, subcommand: &str, subcommand_matches: &ArgMatches, config: &ProjectConfig) {
+    use write::*;
+
+    let &Settings {
+        localization,
+        output,
+        ref progress,
+        logging,
+        ref file_filter,
+        input_dir,
+        original_path,
+        data_path,
+        translation_path,
+        output_path,
+        maps_path,
+        other_path,
+        cache_file_path,
+        ref engine_type,
+        ref system_file_path,
+        ref scripts_file_path,
+        game_type,
+        protect_codes,
+        ref note_tag_rules,
+        ref recorded_source_hashes,
+        disable_custom_processing,
+        disable_cache,
+        disable_maps_processing,
+        disable_other_processing,
+        disable_system_processing,
+        disable_plugins_processing,
+        ..
+    } = settings;
+    let romanization: Option<&RomanizationTable> = settings.romanization();
+    let event_codes: Option<&[u16]> = settings.event_codes.as_deref();
+
+    // Server can read the game first
+    if subcommand != "serve" && (!maps_path.exists() || !other_path.exists()) {
+        panic!("{}", localization.translation_dirs_missing);
+    }
+
+    // Writing changed originals would silently leave their new lines untranslated
+    if let Some(recorded_source_hashes) = recorded_source_hashes {
+        let changes: Vec<SourceChange> = compare_sources(recorded_source_hashes, &hash_sources(original_path))
+            .into_iter()
+            .filter(|change: &SourceChange| file_filter.matches(&change.filename))
+            .collect();
+
+        if !changes.is_empty() {
+            // Events are self-contained, and text lists the files under a single heading
+            if !output.is_json() {
+                output.print_message(localization.source_changed_msg);
+            }
+
+            for change in changes.iter() {
+                let label: &str = match change.kind {
+                    SourceChangeKind::Changed => localization.source_changed_label,
+                    SourceChangeKind::Added => localization.source_added_label,
+                    SourceChangeKind::Removed => localization.source_removed_label,
+                };
 
-            let romanize_bool: bool = metadata["romanize"].as_bool().unwrap();
-            let disable_custom_processing_bool: bool = metadata["disableCustomProcessing"].as_bool().unwrap();
+                let message: String = if output.is_json() {
+                    format!("{} {} ({label})", localization.source_changed_msg, change.filename)
+                } else {
+                    format!("  {} ({label})", change.filename)
+                };
 
-            if romanize_bool {
-                println!("{}", localization.enabling_romanize_metadata_msg);
-                romanize = romanize_bool;
+                output.print_warning("source_changed", &message);
             }
 
-            if disable_custom_processing_bool && game_type.is_some() {
-                println!("{}", localization.disabling_custom_processing_metadata_msg);
-                game_type = None;
+            if subcommand_matches.get_flag("refuse-source-changes") {
+                panic!("{}", localization.source_changes_refused_msg);
             }
         }
+    }
 
-        if !disable_maps_processing {
-            write_maps(
-                maps_path,
-                original_path,
-                data_output_path,
-                romanize,
-                shuffle_level,
-                logging,
-                localization.file_written_msg,
+    let plugins_path: &Path = &translation_path.join("plugins");
+
+    let in_game: bool = subcommand == "watch" && subcommand_matches.get_flag("in-game");
+
+    if in_game && !input_dir.join("original").exists() {
+        panic!("{}", localization.in_game_requires_original_msg);
+    }
+
+    let (data_output_path, plugins_output_path): (&Path, &Path) = if in_game {
+        (data_path, &input_dir.join("js"))
+    } else if engine_type == EngineType::New {
+        (&output_path.join("data"), &output_path.join("js"))
+    } else {
+        (&output_path.join("Data"), &output_path.join("js"))
+    };
+
+    if engine_type == EngineType::New {
+        create_dir_all(data_output_path).unwrap();
+        create_dir_all(plugins_output_path).unwrap();
+    } else if !in_game {
+        create_dir_all(data_output_path).unwrap();
+    }
+
+    let shuffle_level: u8 = if subcommand == "write" {
+        match config.shuffle_level {
+            Some(shuffle_level) if !is_from_command_line(subcommand_matches, "shuffle-level") => shuffle_level,
+            _ => *subcommand_matches.get_one("shuffle-level").unwrap(),
+        }
+    } else {
+        0
+    };
+
+    let pseudo_expansion: Option<u16> = if subcommand == "write" {
+        subcommand_matches.get_one::<u16>("pseudo-localize").copied()
+    } else {
+        None
+    };
+
+    // Marshal files have no formatting to preserve
+    let preserve_formatting: bool =
+        subcommand_matches.get_flag("preserve-formatting") && engine_type == EngineType::New;
+
+    // Shuffled output differs on every write, so it can't be cached
+    let cache: Option<Cache> = (!disable_cache && shuffle_level == 0).then(|| {
+        Cache::load(
+            cache_file_path,
+            hash_settings(
+                romanization,
+                protect_codes,
+                event_codes,
+                note_tag_rules,
                 game_type,
-                &engine_type,
+                engine_type,
+                preserve_formatting,
+                pseudo_expansion,
+                Some(data_output_path),
+            ),
+        )
+    });
+
+    // Only the server keeps parsed original data files between writes
+    let sources: Sources = Sources::default();
+    let kept_sources: Option<&Sources> = (subcommand == "serve").then_some(&sources);
+
+    let run_writers = |writers: &[Writer], file_filter: &FileFilter| {
+        // Output with broken placeholders would lose or corrupt escape codes, so nothing is written
+        if protect_codes
+            && pseudo_expansion.is_none()
+            && (writers.contains(&Writer::Maps) || writers.contains(&Writer::Other))
+        {
+            let issues: Vec<PlaceholderIssue> = check_placeholders(maps_path.parent().unwrap());
+
+            for issue in issues.iter() {
+                let message: &str = match issue.kind {
+                    PlaceholderIssueKind::Missing => localization.placeholder_missing_msg,
+                    PlaceholderIssueKind::Duplicated => localization.placeholder_duplicated_msg,
+                    PlaceholderIssueKind::Unknown => localization.placeholder_unknown_msg,
+                };
+
+                output.print_message(&format!(
+                    "{}:{}: {message} {}",
+                    issue.filename, issue.line, issue.placeholder
+                ));
+            }
+
+            if !issues.is_empty() {
+                panic!("{}", localization.placeholders_invalid_msg);
+            }
+        }
+
+        if !disable_maps_processing && writers.contains(&Writer::Maps) {
+            progress.run_phase(
+                "maps",
+                count_files(original_path, |name: &str| {
+                    is_map_name(name) && file_filter.matches(name)
+                }),
+                || {
+                    write_maps(
+                        maps_path,
+                        original_path,
+                        data_output_path,
+                        romanization,
+                        protect_codes,
+                        event_codes,
+                        shuffle_level,
+                        pseudo_expansion,
+                        logging,
+                        output,
+                        progress,
+                        localization.file_written_msg,
+                        game_type,
+                        engine_type,
+                        file_filter,
+                        cache.as_ref(),
+                        kept_sources,
+                        preserve_formatting,
+                    );
+                },
             );
         }
 
-        if !disable_other_processing {
-            write_other(
-                other_path,
-                original_path,
-                data_output_path,
-                romanize,
-                shuffle_level,
-                logging,
-                localization.file_written_msg,
-                game_type,
-                &engine_type,
+        if !disable_other_processing && writers.contains(&Writer::Other) {
+            progress.run_phase(
+                "other",
+                count_files(original_path, |name: &str| {
+                    settings.is_other_name(name, true) && file_filter.matches(name)
+                }),
+                || {
+                    write_other(
+                        other_path,
+                        original_path,
+                        data_output_path,
+                        romanization,
+                        protect_codes,
+                        event_codes,
+                        note_tag_rules,
+                        shuffle_level,
+                        pseudo_expansion,
+                        logging,
+                        output,
+                        progress,
+                        localization.file_written_msg,
+                        game_type,
+                        engine_type,
+                        file_filter,
+                        cache.as_ref(),
+                        kept_sources,
+                        preserve_formatting,
+                    );
+                },
             );
         }
 
-        if !disable_system_processing {
-            write_system(
-                &system_file_path,
+        if !disable_system_processing && writers.contains(&Writer::System) {
+            progress.run_phase("system", 1, || {
+                write_system(
+                    system_file_path,
+                    other_path,
+                    data_output_path,
+                    romanization,
+                    shuffle_level,
+                    pseudo_expansion,
+                    logging,
+                    output,
+                    progress,
+                    localization.file_written_msg,
+                    engine_type,
+                    cache.as_ref(),
+                    kept_sources,
+                    preserve_formatting,
+                );
+            });
+        }
+
+        // Root files of the game have no copies in the "original" folder, so they aren't overwritten in-game
+        if !disable_system_processing && writers.contains(&Writer::System) && !in_game {
+            write_window_title(
+                input_dir,
+                system_file_path,
                 other_path,
-                data_output_path,
-                romanize,
-                shuffle_level,
+                data_output_path.parent().unwrap(),
+                romanization,
+                pseudo_expansion,
                 logging,
+                output,
                 localization.file_written_msg,
-                &engine_type,
+                engine_type,
+                kept_sources,
             );
         }
 
         if !disable_plugins_processing
+            && writers.contains(&Writer::Plugins)
             && plugins_path.exists()
             && game_type.is_some_and(|game_type: &GameType| game_type == GameType::Termina)
         {
-            write_plugins(
-                &plugins_path.join("plugins.json"),
-                plugins_path,
-                plugins_output_path,
-                shuffle_level,
-                logging,
-                localization.file_written_msg,
-            );
+            progress.run_phase("plugins", 1, || {
+                write_plugins(
+                    &plugins_path.join("plugins.json"),
+                    plugins_path,
+                    plugins_output_path,
+                    shuffle_level,
+                    pseudo_expansion,
+                    logging,
+                    output,
+                    progress,
+                    localization.file_written_msg,
+                );
+            });
         }
 
-        if !disable_custom_processing && engine_type != EngineType::New {
-            write_scripts(
-                &scripts_file_path,
-                other_path,
-                data_output_path,
-                romanize,
-                logging,
-                &engine_type,
-                localization.file_written_msg,
-            )
+        if !disable_custom_processing
+            && !disable_plugins_processing
+            && writers.contains(&Writer::Scripts)
+            && engine_type != EngineType::New
+        {
+            progress.run_phase("scripts", 1, || {
+                write_scripts(
+                    scripts_file_path,
+                    other_path,
+                    data_output_path,
+                    romanization,
+                    pseudo_expansion,
+                    logging,
+                    output,
+                    progress,
+                    engine_type,
+                    localization.file_written_msg,
+                    kept_sources,
+                );
+            });
         }
+
+        if let Some(cache) = &cache {
+            cache.save();
+        }
+    };
+
+    if subcommand == "watch" {
+        watch(
+            maps_path,
+            other_path,
+            plugins_path,
+            Duration::from_millis(300),
+            localization.watching_msg,
+            localization.watch_write_failed_msg,
+            output,
+            |writers: &[Writer]| run_writers(writers, file_filter),
+        );
+    } else if subcommand == "serve" {
+        run_serve(settings, subcommand_matches, &sources, run_writers);
+    } else {
+        run_writers(&Writer::ALL, file_filter);
     }
+}
 
-    println!(
-        "{} {}",
-        localization.done_in_msg,
-        start_time.elapsed().as_secs_f64() - wait_time
+/// Serves JSON-RPC requests over stdio, or over HTTP on localhost, if the port is passed.
+/// # Parameters
+/// * `settings` - resolved settings of the subcommand
+/// * `subcommand_matches` - arguments of the subcommand
+/// * `sources` - parsed original data files, which are kept between writes
+/// * `run_writers` - function that runs the passed writers on files, which the filter selects
+fn run_serve(
+    settings: &Settings,
+    subcommand_matches: &ArgMatches,
+    sources: &Sources,
+    run_writers: impl FnMut(&[Writer], &FileFilter),
+) {
+    let &Settings {
+        localization,
+        output,
+        ref file_filter,
+        input_dir,
+        original_path,
+        translation_path,
+        ref engine_type,
+        ref system_file_path,
+        ..
+    } = settings;
+
+    let transport: Transport = match subcommand_matches.get_one::<u16>("port") {
+        Some(port) => Transport::Http(*port),
+        None => Transport::Stdio,
+    };
+
+    serve(
+        translation_path,
+        transport,
+        file_filter,
+        sources,
+        localization.serve_listening_msg,
+        output,
+        |processing_type: &ProcessingMode| {
+            settings.read_translation(translation_path, processing_type, &hash_sources(original_path))
+        },
+        run_writers,
+        || -> CheckResult {
+            let glossary_path: &Path = &translation_path.join("glossary.txt");
+            let font_paths: Vec<PathBuf> = find_fonts(input_dir, system_file_path, engine_type);
+
+            (
+                glossary_path
+                    .exists()
+                    .then(|| check_glossary(glossary_path, translation_path)),
+                (!font_paths.is_empty()).then(|| check_glyphs(&font_paths, translation_path)),
+            )
+        },
     );
 }
+
+fn main() {
+    let start_time: Instant = Instant::now();
+
+    let (language, subcommand): (String, Option<String>) = preparse_arguments();
+    let get_builtin_language = |language: &str| match language {
+        "ru" => Language::Russian,
+        _ => Language::English,
+    };
+
+    // Messages are borrowed by clap for the whole run, so the catalog is leaked, as built-in messages live for the
+    // whole run too
+    let catalog: Option<&'static Catalog> = match load_catalog(&language) {
+        Ok(catalog) => catalog.map(|catalog: Catalog| &*Box::leak(Box::new(catalog))),
+        Err(err) => {
+            let localization: ProgramLocalization = ProgramLocalization::new(get_builtin_language(&language));
+
+            match err {
+                CatalogError::Parse(message) => panic!("{} {message}", localization.catalog_parse_failed_msg),
+                CatalogError::InvalidValue(key) => panic!("{} {key}", localization.catalog_invalid_value_msg),
+            }
+        }
+    };
+
+    let mut localization: ProgramLocalization = ProgramLocalization::new(get_builtin_language(
+        catalog.map_or(&language, |catalog: &Catalog| &catalog.fallback),
+    ));
+
+    if let Some(catalog) = catalog {
+        for key in localization.apply_catalog(catalog) {
+            eprintln!("{} {key}", localization.catalog_unknown_key_msg);
+        }
+    }
+
+    let (input_dir_arg_desc, output_dir_arg_desc) = if let Some(subcommand) = subcommand {
+        match subcommand.as_str() {
+            "read" | "verify" => (
+                localization.input_dir_arg_read_desc.to_string(),
+                localization.output_dir_arg_read_desc.to_string(),
+            ),
+            "write" | "watch" | "migrate" | "merge" | "check" | "pretranslate" | "pack" | "tui" | "serve" => (
+                localization.input_dir_arg_write_desc.to_string(),
+                localization.output_dir_arg_write_desc.to_string(),
+            ),
+            _ => unreachable!(),
+        }
+    } else {
+        (
+            format!(
+                "{} {}\n{} {}",
+                localization.when_reading,
+                localization.input_dir_arg_read_desc,
+                localization.when_writing,
+                localization.input_dir_arg_write_desc
+            ),
+            format!(
+                "{} {}\n{} {}",
+                localization.when_reading,
+                localization.output_dir_arg_read_desc,
+                localization.when_writing,
+                localization.output_dir_arg_write_desc
+            ),
+        )
+    };
+
+    let input_dir_arg: Arg = Arg::new("input-dir")
+        .short('i')
+        .long("input-dir")
+        .global(true)
+        .help(input_dir_arg_desc)
+        .value_name(localization.input_dir_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .default_value("./")
+        .hide_default_value(true)
+        .display_order(0);
+
+    let output_dir_arg: Arg = Arg::new("output-dir")
+        .short('o')
+        .long("output-dir")
+        .global(true)
+        .help(output_dir_arg_desc)
+        .value_name(localization.output_dir_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .default_value("./")
+        .hide_default_value(true)
+        .display_order(1);
+
+    let shuffle_level_arg: Arg = Arg::new("shuffle-level")
+        .short('s')
+        .long("shuffle-level")
+        .action(ArgAction::Set)
+        .value_name(localization.shuffle_arg_type)
+        .default_value("0")
+        .value_parser(value_parser!(u8).range(0..=2))
+        .help(cformat!(
+            "{}\n{} --shuffle-level 1.<bold>\n[{} 0, 1, 2]\n[{} 0]</bold>",
+            localization.shuffle_level_arg_desc,
+            localization.example,
+            localization.possible_values,
+            localization.default_value,
+        ))
+        .hide_default_value(true)
+        .display_order(2);
+
+    let disable_processing_arg: Arg = Arg::new("disable-processing")
+        .long("disable-processing")
+        .value_delimiter(',')
+        .value_name(localization.disable_processing_arg_type)
+        .help(cformat!(
+            "{}\n{} --disable-processing=maps,other,system.<bold>\n[{} maps, other, system, plugins]</bold>",
+            localization.disable_processing_arg_desc,
+            localization.example,
+            localization.possible_values,
+        ))
+        .global(true)
+        .value_parser(["maps", "other", "system", "plugins"])
+        .display_order(3);
+
+    let only_arg: Arg = Arg::new("only")
+        .long("only")
+        .global(true)
+        .value_delimiter(',')
+        .value_name(localization.patterns_arg_type)
+        .help(cformat!(
+            "{}\n{} --only Map012,Map013,CommonEvents.",
+            localization.only_arg_desc,
+            localization.example,
+        ))
+        .display_order(3);
+
+    let exclude_arg: Arg = Arg::new("exclude")
+        .long("exclude")
+        .global(true)
+        .value_delimiter(',')
+        .value_name(localization.patterns_arg_type)
+        .help(cformat!(
+            "{}\n{} --exclude \"Map0*\".",
+            localization.exclude_arg_desc,
+            localization.example,
+        ))
+        .display_order(3);
+
+    let romanize_arg: Arg = Arg::new("romanize")
+        .short('r')
+        .long("romanize")
+        .global(true)
+        .action(ArgAction::SetTrue)
+        .help(localization.romanize_desc)
+        .display_order(4);
+
+    let no_romanize_flag: Arg = Arg::new("no-romanize")
+        .long("no-romanize")
+        .global(true)
+        .action(ArgAction::SetTrue)
+        .help(localization.no_romanize_desc)
+        .conflicts_with("romanize")
+        .display_order(4);
+
+    let romanize_preset_arg: Arg = Arg::new("romanize-preset")
+        .long("romanize-preset")
+        .global(true)
+        .value_name(localization.language_code_arg_type)
+        .value_parser(PRESETS)
+        .default_value("ja")
+        .help(cformat!(
+            "{}\n{} --romanize-preset zh.<bold>\n[{} ja, zh, ko]\n[{} ja]</bold>",
+            localization.romanize_preset_arg_desc,
+            localization.example,
+            localization.possible_values,
+            localization.default_value,
+        ))
+        .hide_default_value(true)
+        .display_order(5);
+
+    let romanize_table_arg: Arg = Arg::new("romanize-table")
+        .long("romanize-table")
+        .global(true)
+        .value_name(localization.romanize_table_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .help(localization.romanize_table_arg_desc)
+        .display_order(6);
+
+    let target_language_arg: Arg = Arg::new("target-language")
+        .long("target-language")
+        .global(true)
+        .value_name(localization.language_code_arg_type)
+        .help(cformat!(
+            "{}\n{} --target-language ru.",
+            localization.target_language_arg_desc,
+            localization.example,
+        ))
+        .display_order(7);
+
+    let config_arg: Arg = Arg::new("config")
+        .long("config")
+        .global(true)
+        .value_name(localization.config_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .help(localization.config_arg_desc)
+        .display_order(8);
+
+    let profile_arg: Arg = Arg::new("profile")
+        .long("profile")
+        .global(true)
+        .value_name(localization.profile_arg_type)
+        .help(cformat!(
+            "{}\n{} --profile release.",
+            localization.profile_arg_desc,
+            localization.example,
+        ))
+        .display_order(9);
+
+    let force_flag: Arg = Arg::new("force")
+        .short('f')
+        .long("force")
+        .action(ArgAction::SetTrue)
+        .help(localization.force_arg_desc)
+        .display_order(95)
+        .conflicts_with("append");
+
+    let append_flag: Arg = Arg::new("append")
+        .short('a')
+        .long("append")
+        .action(ArgAction::SetTrue)
+        .help(localization.append_arg_desc)
+        .display_order(96);
+
+    let disable_custom_processing_flag: Arg = Arg::new("disable-custom-processing")
+        .long("disable-custom-processing")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.disable_custom_processing_desc)
+        .display_order(97);
+
+    let no_disable_custom_processing_flag: Arg = Arg::new("no-disable-custom-processing")
+        .long("no-disable-custom-processing")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.no_disable_custom_processing_desc)
+        .conflicts_with("disable-custom-processing")
+        .display_order(97);
+
+    let disable_cache_flag: Arg = Arg::new("disable-cache")
+        .long("disable-cache")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.disable_cache_desc)
+        .display_order(97);
+
+    let no_disable_cache_flag: Arg = Arg::new("no-disable-cache")
+        .long("no-disable-cache")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.no_disable_cache_desc)
+        .conflicts_with("disable-cache")
+        .display_order(97);
+
+    let languages: Vec<String> = list_languages();
+    let language_arg: Arg = Arg::new("language")
+        .short('l')
+        .long("language")
+        .value_name(localization.language_arg_type)
+        .global(true)
+        .help(cformat!(
+            "{}\n{} --language en.<bold>\n[{} {}]</bold>",
+            localization.language_arg_desc,
+            localization.example,
+            localization.possible_values,
+            languages.join(", "),
+        ))
+        .value_parser(move |language: &str| {
+            if languages.iter().any(|available: &String| available == language) {
+                Ok(language.to_string())
+            } else {
+                Err(localization.language_unknown_msg)
+            }
+        })
+        .display_order(98);
+
+    let output_format_arg: Arg = Arg::new("output-format")
+        .long("output-format")
+        .value_name(localization.output_format_arg_type)
+        .global(true)
+        .help(cformat!(
+            "{}\n{} --output-format json.<bold>\n[{} text, json]</bold>",
+            localization.output_format_arg_desc,
+            localization.example,
+            localization.possible_values,
+        ))
+        .value_parser(["text", "json"])
+        .default_value("text")
+        .display_order(98);
+
+    let log_flag: Arg = Arg::new("log")
+        .long("log")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.log_arg_desc)
+        .display_order(99);
+
+    let progress_flag: Arg = Arg::new("progress")
+        .long("progress")
+        .action(ArgAction::SetTrue)
+        .global(true)
+        .help(localization.progress_flag_desc)
+        .display_order(99);
+
+    let help_flag: Arg = Arg::new("help")
+        .short('h')
+        .long("help")
+        .help(localization.help_arg_desc)
+        .action(ArgAction::Help)
+        .display_order(100);
+
+    let in_game_flag: Arg = Arg::new("in-game")
+        .long("in-game")
+        .action(ArgAction::SetTrue)
+        .help(localization.in_game_arg_desc)
+        .display_order(95);
+
+    let preserve_formatting_flag: Arg = Arg::new("preserve-formatting")
+        .long("preserve-formatting")
+        .action(ArgAction::SetTrue)
+        .help(localization.preserve_formatting_arg_desc)
+        .display_order(94);
+
+    let refuse_source_changes_flag: Arg = Arg::new("refuse-source-changes")
+        .long("refuse-source-changes")
+        .action(ArgAction::SetTrue)
+        .help(localization.refuse_source_changes_arg_desc)
+        .display_order(94);
+
+    let pseudo_localize_arg: Arg = Arg::new("pseudo-localize")
+        .long("pseudo-localize")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("30")
+        .value_name(localization.expansion_arg_type)
+        .value_parser(value_parser!(u16))
+        .help(cformat!(
+            "{}\n{} --pseudo-localize=50.<bold>\n[{} 30]</bold>",
+            localization.pseudo_localize_arg_desc,
+            localization.example,
+            localization.default_value,
+        ))
+        .conflicts_with("shuffle-level")
+        .display_order(3);
+
+    let protect_codes_flag: Arg = Arg::new("protect-codes")
+        .long("protect-codes")
+        .action(ArgAction::SetTrue)
+        .help(localization.protect_codes_arg_desc)
+        .display_order(94);
+
+    let note_tags_arg: Arg = Arg::new("note-tags")
+        .long("note-tags")
+        .value_delimiter(',')
+        .value_name(localization.note_tags_arg_type)
+        .help(cformat!(
+            "{}\n{} --note-tags=\"Help Description,Quest Text:value\".",
+            localization.note_tags_arg_desc,
+            localization.example,
+        ))
+        .display_order(93);
+
+    let silent_flag: Arg = Arg::new("silent").long("silent").hide(true).action(ArgAction::SetTrue);
+
+    let read_subcommand: Command = Command::new("read")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.read_command_desc)
+        .args([force_flag, append_flag, protect_codes_flag, note_tags_arg, silent_flag])
+        .arg(&help_flag);
+
+    let write_subcommand: Command = Command::new("write")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.write_command_desc)
+        .args([shuffle_level_arg, pseudo_localize_arg])
+        .arg(&preserve_formatting_flag)
+        .arg(&refuse_source_changes_flag)
+        .arg(&help_flag);
+
+    let watch_subcommand: Command = Command::new("watch")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.watch_command_desc)
+        .args([in_game_flag])
+        .arg(&preserve_formatting_flag)
+        .arg(&refuse_source_changes_flag)
+        .arg(&help_flag);
+
+    let verify_subcommand: Command = Command::new("verify")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.verify_command_desc)
+        .arg(&preserve_formatting_flag)
+        .arg(&help_flag);
+
+    let min_similarity_arg: Arg = Arg::new("min-similarity")
+        .long("min-similarity")
+        .value_name(localization.min_similarity_arg_type)
+        .default_value("0.75")
+        .value_parser(value_parser!(f64))
+        .help(cformat!(
+            "{}\n{} --min-similarity 0.9.<bold>\n[{} 0.75]</bold>",
+            localization.min_similarity_arg_desc,
+            localization.example,
+            localization.default_value,
+        ))
+        .hide_default_value(true)
+        .display_order(2);
+
+    let apply_proposals_flag: Arg = Arg::new("apply-proposals")
+        .long("apply-proposals")
+        .action(ArgAction::SetTrue)
+        .help(localization.apply_proposals_arg_desc)
+        .display_order(3);
+
+    let migrate_subcommand: Command = Command::new("migrate")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.migrate_command_desc)
+        .args([min_similarity_arg, apply_proposals_flag])
+        .arg(&help_flag);
+
+    let merge_base_arg: Arg = Arg::new("base")
+        .long("base")
+        .required(true)
+        .value_name(localization.merge_base_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .help(localization.merge_base_arg_desc)
+        .display_order(2);
+
+    let merge_translations_arg: Arg = Arg::new("translations")
+        .required(true)
+        .num_args(2..)
+        .value_name(localization.merge_translations_arg_type)
+        .value_parser(value_parser!(PathBuf))
+        .help(localization.merge_translations_arg_desc)
+        .display_order(3);
+
+    let merge_subcommand: Command = Command::new("merge")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.merge_command_desc)
+        .args([merge_base_arg, merge_translations_arg])
+        .arg(&help_flag);
+
+    let seed_glossary_flag: Arg = Arg::new("seed-glossary")
+        .long("seed-glossary")
+        .action(ArgAction::SetTrue)
+        .help(localization.seed_glossary_arg_desc)
+        .display_order(2);
+
+    let check_subcommand: Command = Command::new("check")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.check_command_desc)
+        .args([seed_glossary_flag])
+        .arg(&help_flag);
+
+    let from_arg: Arg = Arg::new("from")
+        .long("from")
+        .required(true)
+        .value_name(localization.language_code_arg_type)
+        .help(localization.from_arg_desc)
+        .display_order(2);
+
+    let to_arg: Arg = Arg::new("to")
+        .long("to")
+        .required(true)
+        .value_name(localization.language_code_arg_type)
+        .help(localization.to_arg_desc)
+        .display_order(3);
+
+    let url_arg: Arg = Arg::new("url")
+        .long("url")
+        .value_name(localization.url_arg_type)
+        .default_value("http://localhost:5000")
+        .help(cformat!(
+            "{}\n{} --url https://libretranslate.com.<bold>\n[{} http://localhost:5000]</bold>",
+            localization.url_arg_desc,
+            localization.example,
+            localization.default_value,
+        ))
+        .hide_default_value(true)
+        .display_order(4);
+
+    let api_key_arg: Arg = Arg::new("api-key")
+        .long("api-key")
+        .value_name(localization.api_key_arg_type)
+        .help(localization.api_key_arg_desc)
+        .display_order(5);
+
+    let batch_size_arg: Arg = Arg::new("batch-size")
+        .long("batch-size")
+        .value_name(localization.batch_size_arg_type)
+        .default_value("50")
+        .value_parser(value_parser!(usize))
+        .help(cformat!(
+            "{}\n{} --batch-size 100.<bold>\n[{} 50]</bold>",
+            localization.batch_size_arg_desc,
+            localization.example,
+            localization.default_value,
+        ))
+        .hide_default_value(true)
+        .display_order(6);
+
+    let pretranslate_subcommand: Command = Command::new("pretranslate")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.pretranslate_command_desc)
+        .args([from_arg, to_arg, url_arg, api_key_arg, batch_size_arg])
+        .arg(&help_flag);
+
+    let pack_subcommand: Command = Command::new("pack")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.pack_command_desc)
+        .arg(&help_flag);
+
+    let tui_file_arg: Arg = Arg::new("file")
+        .required(true)
+        .value_name(localization.tui_file_arg_type)
+        .help(localization.tui_file_arg_desc)
+        .display_order(2);
+
+    let tui_subcommand: Command = Command::new("tui")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.tui_command_desc)
+        .arg(tui_file_arg)
+        .arg(&help_flag);
+
+    let port_arg: Arg = Arg::new("port")
+        .long("port")
+        .value_name(localization.port_arg_type)
+        .value_parser(value_parser!(u16))
+        .help(cformat!(
+            "{}\n{} --port 8080.",
+            localization.port_arg_desc,
+            localization.example,
+        ))
+        .display_order(2);
+
+    let serve_subcommand: Command = Command::new("serve")
+        .disable_help_flag(true)
+        .help_template(localization.subcommand_help_template)
+        .about(localization.serve_command_desc)
+        .args([port_arg])
+        .arg(&preserve_formatting_flag)
+        .arg(&refuse_source_changes_flag)
+        .arg(&help_flag);
+
+    let cli: Command = Command::new("")
+        .disable_version_flag(true)
+        .disable_help_subcommand(true)
+        .disable_help_flag(true)
+        .next_line_help(true)
+        .term_width(120)
+        .about(localization.about_msg)
+        .help_template(localization.help_template)
+        .subcommands([
+            read_subcommand,
+            write_subcommand,
+            watch_subcommand,
+            verify_subcommand,
+            migrate_subcommand,
+            merge_subcommand,
+            check_subcommand,
+            pretranslate_subcommand,
+            pack_subcommand,
+            tui_subcommand,
+            serve_subcommand,
+        ])
+        .args([
+            input_dir_arg,
+            output_dir_arg,
+            disable_processing_arg,
+            only_arg,
+            exclude_arg,
+            romanize_arg,
+            no_romanize_flag,
+            romanize_preset_arg,
+            romanize_table_arg,
+            target_language_arg,
+            config_arg,
+            profile_arg,
+            language_arg,
+            output_format_arg,
+            disable_custom_processing_flag,
+            no_disable_custom_processing_flag,
+            disable_cache_flag,
+            no_disable_cache_flag,
+            log_flag,
+            progress_flag,
+            help_flag,
+        ])
+        .hide_possible_values(true);
+
+    let matches: ArgMatches = cli.get_matches();
+    let (subcommand, subcommand_matches): (&str, &ArgMatches) = matches.subcommand().unwrap();
+
+    let output_format: OutputFormat = match matches.get_one::<String>("output-format").unwrap().as_str() {
+        "json" => OutputFormat::Json,
+        _ => OutputFormat::Text,
+    };
+
+    // Responses of the server over stdio are written to stdout, so nothing else may be printed there
+    let serving_stdio: bool = subcommand == "serve" && subcommand_matches.get_one::<u16>("port").is_none();
+    let output: Output = Output {
+        format: output_format,
+        reserve_stdout: serving_stdio,
+    };
+
+    if output.is_json() {
+        // Errors are reported as events too, so wrappers don't have to parse panic messages from stderr
+        set_hook(Box::new(move |info: &PanicHookInfo| {
+            let message: &str = info
+                .payload()
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| info.payload().downcast_ref::<&str>().copied())
+                .unwrap_or_default();
+
+            output.emit(Event::Error { message });
+        }));
+
+        output.emit(Event::Started { command: subcommand });
+    }
+
+    // Settings of the project configuration file apply, unless the same arguments are passed on the command line.
+    // Configuration is looked up next to the game and the translation first, so the program can be run from any
+    // directory.
+    let config_path: PathBuf = matches.get_one::<PathBuf>("config").cloned().unwrap_or_else(|| {
+        [
+            matches.get_one::<PathBuf>("input-dir").unwrap(),
+            matches.get_one::<PathBuf>("output-dir").unwrap(),
+        ]
+        .into_iter()
+        .map(|dir: &PathBuf| dir.join(CONFIG_FILENAME))
+        .find(|config_path: &PathBuf| config_path.exists())
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILENAME))
+    });
+    let profile: Option<&str> = matches.get_one::<String>("profile").map(String::as_str);
+
+    let config: ProjectConfig = if config_path.exists() {
+        match load_config(&config_path, profile) {
+            Ok(config) => config,
+            Err(ConfigError::Parse(message)) => panic!("{} {message}", localization.config_parse_failed_msg),
+            Err(ConfigError::UnknownKey(key)) => panic!("{} {key}", localization.config_unknown_key_msg),
+            Err(ConfigError::InvalidValue(key)) => panic!("{} {key}", localization.config_invalid_value_msg),
+            Err(ConfigError::UnknownProfile(profile)) => {
+                panic!("{} {profile}", localization.config_unknown_profile_msg)
+            }
+        }
+    } else if is_from_command_line(&matches, "config") || profile.is_some() {
+        panic!("{}", localization.config_missing_msg);
+    } else {
+        ProjectConfig::default()
+    };
+
+    let disable_processing: Vec<String> = match &config.disable_processing {
+        Some(disable_processing) if !is_from_command_line(&matches, "disable-processing") => disable_processing.clone(),
+        _ => matches
+            .get_many::<String>("disable-processing")
+            .map(|disable_processing_args| disable_processing_args.cloned().collect())
+            .unwrap_or_default(),
+    };
+
+    // Filter affects only commands, which process the translation in place, as others compare all files
+    let file_filter: FileFilter = if matches!(subcommand, "read" | "write" | "watch" | "serve") {
+        let get_patterns = |id: &str| -> Vec<String> {
+            matches
+                .get_many::<String>(id)
+                .map(|patterns| patterns.cloned().collect())
+                .unwrap_or_default()
+        };
+
+        FileFilter::new(&get_patterns("only"), &get_patterns("exclude"))
+    } else {
+        FileFilter::default()
+    };
+
+    let (disable_maps_processing, disable_other_processing, disable_system_processing, disable_plugins_processing) = {
+        let mut flags = (
+            false,
+            false,
+            !file_filter.matches("System"),
+            !file_filter.matches("plugins") && !file_filter.matches("Scripts"),
+        );
+
+        for disable_processing_of in disable_processing.iter() {
+            match disable_processing_of.as_str() {
+                "maps" => flags.0 = true,
+                "other" => flags.1 = true,
+                "system" => flags.2 = true,
+                "plugins" => flags.3 = true,
+                _ => {}
+            }
+        }
+        flags
+    };
+
+    // Readers and writers log to stdout directly
+    let logging: bool = matches.get_flag("log") && !serving_stdio;
+
+    let progress: Progress = Progress::new(
+        output,
+        matches.get_flag("progress"),
+        localization.eta_label,
+        localization.phase_timings_msg,
+    );
+
+    // Switches of the command line override the configuration in both directions
+    let get_switch = |id: &str, negated_id: &str, config_value: Option<bool>| -> bool {
+        if matches.get_flag(id) {
+            true
+        } else if matches.get_flag(negated_id) {
+            false
+        } else {
+            config_value.unwrap_or(false)
+        }
+    };
+
+    let disable_custom_processing: bool = get_switch(
+        "disable-custom-processing",
+        "no-disable-custom-processing",
+        config.disable_custom_processing,
+    );
+    let disable_cache: bool = get_switch("disable-cache", "no-disable-cache", config.disable_cache);
+    let mut romanize: bool = get_switch("romanize", "no-romanize", config.romanize);
+
+    let input_dir: &Path = match &config.input_dir {
+        Some(input_dir) if !is_from_command_line(&matches, "input-dir") => input_dir,
+        _ => matches.get_one::<PathBuf>("input-dir").unwrap(),
+    };
+
+    if !input_dir.exists() {
+        panic!("{}", localization.input_dir_not_exist);
+    }
+
+    let output_dir: &Path = match &config.output_dir {
+        Some(output_dir) if !is_from_command_line(&matches, "output-dir") => output_dir,
+        _ => matches.get_one::<PathBuf>("output-dir").unwrap(),
+    };
+
+    if !output_dir.exists() {
+        panic!("{}", localization.output_dir_not_exist)
+    }
+
+    let (translation_root, output_root): (PathBuf, PathBuf) = if *output_dir.as_os_str() == *"./" {
+        (input_dir.join("translation"), input_dir.join("output"))
+    } else {
+        (output_dir.join("translation"), output_dir.join("output"))
+    };
+
+    // Each target language has its own translation folder with its own metadata, and its own output folder
+    let target_language: Option<&String> = matches
+        .get_one::<String>("target-language")
+        .or(config.target_language.as_ref());
+    let (translation_path, output_path): (&Path, &Path) = match target_language {
+        Some(target_language) => (
+            &translation_root.join(target_language),
+            &output_root.join(target_language),
+        ),
+        None => (&translation_root, &output_root),
+    };
+
+    // Merging works only with translation folders, so it doesn't require game files
+    if subcommand == "merge" {
+        run_merge(subcommand_matches, translation_path, &localization, output);
+        output.print_finished(subcommand, start_time.elapsed().as_secs_f64(), localization.done_in_msg);
+        return;
+    }
+
+    // Editing works only with translation folders too
+    if subcommand == "tui" {
+        run_tui(subcommand_matches, translation_path, &localization);
+        return;
+    }
+
+    let mut original_path: &Path = &input_dir.join("original");
+    // Older engines name the folder "Data", which differs from "data" on case-sensitive filesystems
+    let data_path: PathBuf = ["data", "Data"]
+        .into_iter()
+        .map(|dirname: &str| input_dir.join(dirname))
+        .find(|path: &PathBuf| path.is_dir())
+        .unwrap_or_else(|| input_dir.join("data"));
+
+    if !original_path.exists() {
+        original_path = &data_path;
+
+        if !original_path.exists() {
+            panic!("{}", localization.original_dir_missing)
+        }
+    }
+
+    let (maps_path, other_path, metadata_file_path, cache_file_path) = (
+        &translation_path.join("maps"),
+        &translation_path.join("other"),
+        &translation_path.join(".rvpacker-txt-rs-metadata.json"),
+        &translation_path.join(".rvpacker-txt-rs-cache.json"),
+    );
+
+    let (engine_type, system_file_path, scripts_file_path) = detect_engine_type(original_path);
+
+    let mut game_type: Option<&GameType> = if disable_custom_processing {
+        None
+    } else {
+        let game_title: String = if engine_type == EngineType::New {
+            let system_obj: Object = from_str::<Object>(&read_to_string(&system_file_path).unwrap()).unwrap();
+            system_obj["gameTitle"].as_str().unwrap().to_string()
+        } else {
+            let ini_file_path: &Path = &input_dir.join("Game.ini");
+            // Legacy Game.ini files are usually stored in the system code page
+            let (ini_file_content, _) = decode_ini(&read(ini_file_path).unwrap());
+
+            let mut game_title: Option<String> = None;
+
+            for line in ini_file_content.lines() {
+                if line.to_lowercase().starts_with("title") {
+                    game_title = Some(line.splitn(2, "=").last().unwrap().trim().to_string());
+                }
+            }
+
+            game_title.unwrap()
+        };
+
+        get_game_type(game_title)
+    };
+
+    if game_type.is_some() {
+        output.print_message(localization.custom_processing_enabled_msg);
+    }
+
+    let mut romanization_table: Option<RomanizationTable> = None;
+    let mut protect_codes: bool = subcommand == "read" && subcommand_matches.get_flag("protect-codes");
+    let mut event_codes: Option<Vec<u16>> = config.event_codes.clone();
+    let mut recorded_source_hashes: Option<Value> = None;
+    let mut note_tags: Option<Vec<String>> = if subcommand == "read" {
+        subcommand_matches
+            .get_many::<String>("note-tags")
+            .map(|tags| tags.cloned().collect())
+            .or_else(|| config.note_tags.clone())
+    } else {
+        config.note_tags.clone()
+    };
+
+    let get_command_line_table = || -> RomanizationTable {
+        let mut table: RomanizationTable = get_preset(matches.get_one::<String>("romanize-preset").unwrap());
+
+        if let Some(table_path) = matches.get_one::<PathBuf>("romanize-table") {
+            match parse_table(&read_to_string(table_path).unwrap()) {
+                Ok(overrides) => table.extend(overrides),
+                Err(line) => panic!("{} {line}", localization.invalid_romanize_table_msg),
+            }
+        }
+
+        table
+    };
+
+    // Appended lines must have the same format, as the lines of the existing translation
+    let appending: bool = subcommand == "read" && subcommand_matches.get_flag("append");
+
+    // Settings, that were used when reading, must be used when processing the same translation
+    if (appending || matches!(subcommand, "write" | "watch" | "migrate" | "check" | "serve"))
+        && metadata_file_path.exists()
+    {
+        let metadata: Object = from_str(&read_to_string(metadata_file_path).unwrap()).unwrap();
+
+        // Metadata of older versions has no table, and Japanese table was used for them
+        let recorded_table: RomanizationTable = metadata
+            .get(&"romanizationTable")
+            .and_then(table_from_object)
+            .unwrap_or_else(|| get_preset("ja"));
+
+        if appending
+            && (is_from_command_line(&matches, "romanize-preset") || is_from_command_line(&matches, "romanize-table"))
+            && get_command_line_table() != recorded_table
+        {
+            panic!(
+                "{} --romanize-preset, --romanize-table",
+                localization.metadata_conflict_msg
+            );
+        }
+
+        romanization_table = Some(recorded_table);
+
+        // Metadata of older versions has no such field, and codes were never protected in them
+        let recorded_protect_codes: bool = metadata
+            .get(&"protectCodes")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+
+        if appending && protect_codes && !recorded_protect_codes {
+            panic!("{} --protect-codes", localization.metadata_conflict_msg);
+        }
+
+        protect_codes = recorded_protect_codes;
+
+        let recorded_note_tags: Vec<String> = metadata
+            .get(&"noteTags")
+            .and_then(|value| value.as_array())
+            .map(|tags| tags.iter().filter_map(|tag| tag.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let get_rules = |tags: &[String]| -> Vec<String> {
+            parse_note_tag_rules(tags)
+                .into_iter()
+                .map(|rule: NoteTagRule| rule.rule)
+                .collect()
+        };
+
+        if appending
+            && note_tags
+                .as_ref()
+                .is_some_and(|tags: &Vec<String>| get_rules(tags) != get_rules(&recorded_note_tags))
+        {
+            panic!("{} --note-tags", localization.metadata_conflict_msg);
+        }
+
+        note_tags = Some(recorded_note_tags);
+        // Metadata of older versions has no hashes, so changes of originals can't be detected for them
+        recorded_source_hashes = metadata.get(&"sourceHashes").cloned();
+        // Metadata without the field means, that text of all event codes was extracted
+        let recorded_event_codes: Option<Vec<u16>> = metadata
+            .get(&"eventCodes")
+            .and_then(|value| value.as_array())
+            .map(|codes| {
+                codes
+                    .iter()
+                    .filter_map(|code| code.as_u64().map(|code: u64| code as u16))
+                    .collect()
+            });
+
+        let get_enabled_codes = |codes: Option<&Vec<u16>>| -> Vec<u16> {
+            let mut codes: Vec<u16> = codes.map_or(EVENT_CODES.to_vec(), Vec::clone);
+            codes.sort_unstable();
+            codes.dedup();
+            codes
+        };
+
+        if appending
+            && event_codes.is_some()
+            && get_enabled_codes(event_codes.as_ref()) != get_enabled_codes(recorded_event_codes.as_ref())
+        {
+            panic!("{} event-codes", localization.metadata_conflict_msg);
+        }
+
+        event_codes = recorded_event_codes;
+
+        let romanize_bool: bool = metadata["romanize"].as_bool().unwrap();
+        let disable_custom_processing_bool: bool = metadata["disableCustomProcessing"].as_bool().unwrap();
+
+        if appending && romanize && !romanize_bool {
+            panic!("{} --romanize", localization.metadata_conflict_msg);
+        }
+
+        if romanize_bool {
+            output.print_message(localization.enabling_romanize_metadata_msg);
+            romanize = romanize_bool;
+        }
+
+        if disable_custom_processing_bool && game_type.is_some() {
+            output.print_message(localization.disabling_custom_processing_metadata_msg);
+            game_type = None;
+        }
+    }
+
+    let settings: Settings = Settings {
+        localization: &localization,
+        output,
+        progress,
+        logging,
+        file_filter,
+        input_dir,
+        original_path,
+        data_path: &data_path,
+        translation_root: &translation_root,
+        translation_path,
+        output_root: &output_root,
+        output_path,
+        target_language,
+        maps_path,
+        other_path,
+        metadata_file_path,
+        cache_file_path,
+        engine_type,
+        system_file_path,
+        scripts_file_path,
+        game_type,
+        romanize,
+        romanization_table: romanization_table.unwrap_or_else(get_command_line_table),
+        protect_codes,
+        event_codes,
+        note_tag_rules: parse_note_tag_rules(note_tags.as_deref().unwrap_or_default()),
+        recorded_source_hashes,
+        disable_custom_processing,
+        disable_cache,
+        disable_maps_processing,
+        disable_other_processing,
+        disable_system_processing,
+        disable_plugins_processing,
+    };
+
+    let mut wait_time: f64 = 0f64;
+    // Set by checking subcommands, so the program exits with non-zero code
+    let mut failed: bool = false;
+
+    if subcommand == "read" {
+        wait_time = run_read(&settings, subcommand_matches);
+    } else if subcommand == "verify" {
+        failed = run_verify(&settings, subcommand_matches);
+    } else if subcommand == "check" {
+        failed = run_check(&settings, subcommand_matches);
+    } else if subcommand == "pretranslate" {
+        failed = run_pretranslate(&settings, subcommand_matches);
+    } else if subcommand == "pack" {
+        run_pack(&settings);
+    } else if subcommand == "migrate" {
+        run_migrate(&settings, subcommand_matches);
+    } else {
+        run_write(&settings, subcommand, subcommand_matches, &config);
+    }
+
+    settings.progress.print_phase_timings();
+    output.print_finished(
+        subcommand,
+        start_time.elapsed().as_secs_f64() - wait_time,
+        localization.done_in_msg,
+    );
+
+    if failed {
+        exit(1);
+    }
+}
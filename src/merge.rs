@@ -0,0 +1,229 @@
+use crate::migrate::read_lines;
+use indexmap::{IndexMap, IndexSet};
+use std::{
+    ffi::OsString,
+    fs::{copy, create_dir_all, read_dir, write},
+    hash::BuildHasherDefault,
+    path::{Path, PathBuf},
+};
+use xxhash_rust::xxh3::Xxh3;
+
+const METADATA_FILENAME: &str = ".rvpacker-txt-rs-metadata.json";
+
+/// Subdirectories of the translation directory, which contain translation files.
+const TRANSLATION_SUBDIRS: [&str; 3] = ["maps", "other", "plugins"];
+
+/// Entry, which was translated differently by multiple translators.
+pub struct Conflict {
+    /// Path to the translation file relative to the translation directory, like `maps/maps.txt`
+    pub filename: String,
+    pub original: String,
+    pub base: String,
+    /// Translation from each of the merged directories, if it was changed there
+    pub translations: Vec<Option<String>>,
+}
+
+fn read_pairs(dir: &Path, name: &str) -> IndexMap<String, String, BuildHasherDefault<Xxh3>> {
+    read_lines(&dir.join(format!("{name}.txt")))
+        .into_iter()
+        .zip(read_lines(&dir.join(format!("{name}_trans.txt"))))
+        .collect()
+}
+
+/// Merges a single translation file, using originals as keys.
+///
+/// Translation, that was changed in a single directory, is taken from it. If it was changed differently in
+/// multiple directories, base translation is kept and the entry is returned as a conflict.
+/// # Parameters
+/// * `base_dir` - path to the base translation subdirectory
+/// * `translation_dirs` - paths to the translated subdirectories
+/// * `output_dir` - path to the output subdirectory
+/// * `subdir` - name of the subdirectory
+/// * `name` - name of the translation file without extension
+fn merge_file(
+    base_dir: &Path,
+    translation_dirs: &[PathBuf],
+    output_dir: &Path,
+    subdir: &str,
+    name: &str,
+) -> Vec<Conflict> {
+    let base_pairs: IndexMap<String, String, BuildHasherDefault<Xxh3>> = read_pairs(base_dir, name);
+    let translated_pairs: Vec<IndexMap<String, String, BuildHasherDefault<Xxh3>>> = translation_dirs
+        .iter()
+        .map(|dir: &PathBuf| read_pairs(dir, name))
+        .collect();
+
+    let mut originals: IndexSet<&String, BuildHasherDefault<Xxh3>> = base_pairs.keys().collect();
+
+    // Originals, that were added in translated directories, are placed right after the original, which precedes
+    // them there, so they stay in their map or event
+    for pairs in translated_pairs.iter() {
+        let mut anchor: Option<usize> = None;
+
+        for original in pairs.keys() {
+            match originals.get_index_of(original) {
+                Some(index) => anchor = Some(index),
+                None => {
+                    let index: usize = anchor.map_or(0, |anchor: usize| anchor + 1);
+                    originals.shift_insert(index, original);
+                    anchor = Some(index);
+                }
+            }
+        }
+    }
+
+    let mut translations: Vec<String> = Vec::with_capacity(originals.len());
+    let mut conflicts: Vec<Conflict> = Vec::new();
+
+    for original in originals.iter() {
+        let base: &str = base_pairs.get(*original).map_or("", String::as_str);
+
+        let changed: Vec<Option<&String>> = translated_pairs
+            .iter()
+            .map(|pairs| {
+                pairs
+                    .get(*original)
+                    .filter(|translation: &&String| *translation != base)
+            })
+            .collect();
+
+        let mut distinct: Vec<&String> = changed.iter().flatten().copied().collect();
+        distinct.sort();
+        distinct.dedup();
+
+        match distinct.as_slice() {
+            [] => translations.push(base.to_string()),
+            [translation] => translations.push(translation.to_string()),
+            _ => {
+                translations.push(base.to_string());
+                conflicts.push(Conflict {
+                    filename: format!("{subdir}/{name}.txt"),
+                    original: original.to_string(),
+                    base: base.to_string(),
+                    translations: changed
+                        .into_iter()
+                        .map(|translation: Option<&String>| translation.cloned())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    write(
+        output_dir.join(format!("{name}.txt")),
+        originals.into_iter().map(String::as_str).collect::<Vec<_>>().join("\n"),
+    )
+    .unwrap();
+    write(output_dir.join(format!("{name}_trans.txt")), translations.join("\n")).unwrap();
+
+    conflicts
+}
+
+/// Merges translation directories of multiple translators, and writes the result to the output directory.
+/// Returns entries, which were translated differently.
+/// # Parameters
+/// * `base_path` - path to the translation directory, from which all translators started
+/// * `translation_paths` - paths to the translated translation directories
+/// * `output_path` - path to the output translation directory
+pub fn merge(base_path: &Path, translation_paths: &[PathBuf], output_path: &Path) -> Vec<Conflict> {
+    let mut conflicts: Vec<Conflict> = Vec::new();
+
+    for subdir in TRANSLATION_SUBDIRS {
+        let base_dir: &Path = &base_path.join(subdir);
+        let translation_dirs: Vec<PathBuf> = translation_paths.iter().map(|path| path.join(subdir)).collect();
+        let output_dir: &Path = &output_path.join(subdir);
+
+        let mut names: Vec<String> = Vec::new();
+        let mut other_files: Vec<OsString> = Vec::new();
+
+        for dir in translation_dirs.iter().map(PathBuf::as_path).chain([base_dir]) {
+            let Ok(entries) = read_dir(dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let filename: OsString = entry.file_name();
+
+                match filename
+                    .to_str()
+                    .and_then(|filename: &str| filename.strip_suffix(".txt"))
+                {
+                    Some(name) => {
+                        if !name.ends_with("_trans") && !name.ends_with("_obsolete") {
+                            names.push(name.to_string());
+                        }
+                    }
+                    None => {
+                        if dir == base_dir {
+                            other_files.push(entry.file_name());
+                        }
+                    }
+                }
+            }
+        }
+
+        if names.is_empty() {
+            continue;
+        }
+
+        names.sort();
+        names.dedup();
+
+        create_dir_all(output_dir).unwrap();
+
+        for name in names {
+            conflicts.extend(merge_file(base_dir, &translation_dirs, output_dir, subdir, &name));
+        }
+
+        // Files, which aren't translation files, like plugins.json, are taken from the base
+        for filename in other_files {
+            if !output_dir.join(&filename).exists() {
+                copy(base_dir.join(&filename), output_dir.join(&filename)).unwrap();
+            }
+        }
+    }
+
+    // Metadata contains settings, which were used when reading, and is the same for all translators
+    let metadata_path: &Path = &base_path.join(METADATA_FILENAME);
+
+    if metadata_path.exists() && !output_path.join(METADATA_FILENAME).exists() {
+        copy(metadata_path, output_path.join(METADATA_FILENAME)).unwrap();
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::TempDir;
+    use std::{env::temp_dir, fs::read_to_string};
+
+    #[test]
+    fn added_originals_keep_their_position() {
+        let dir: TempDir = TempDir::new(temp_dir().join(format!("rvpacker-txt-rs-merge-test-{}", std::process::id())));
+        let [base_dir, translated_dir, output_dir]: [PathBuf; 3] =
+            ["base", "translated", "output"].map(|name: &str| dir.path().join(name));
+
+        for path in [&base_dir, &translated_dir, &output_dir] {
+            create_dir_all(path).unwrap();
+        }
+
+        write(base_dir.join("maps.txt"), "<!-- Map1 -->\nA\n<!-- Map2 -->\nC").unwrap();
+        write(base_dir.join("maps_trans.txt"), "\n\n\n").unwrap();
+        write(translated_dir.join("maps.txt"), "<!-- Map1 -->\nA\nB\n<!-- Map2 -->\nC").unwrap();
+        write(translated_dir.join("maps_trans.txt"), "\nА\nБ\n\nВ").unwrap();
+
+        let conflicts: Vec<Conflict> = merge_file(&base_dir, &[translated_dir], &output_dir, "maps", "maps");
+
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            read_to_string(output_dir.join("maps.txt")).unwrap(),
+            "<!-- Map1 -->\nA\nB\n<!-- Map2 -->\nC"
+        );
+        assert_eq!(
+            read_to_string(output_dir.join("maps_trans.txt")).unwrap(),
+            "\nА\nБ\n\nВ"
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use indexmap::{IndexMap, IndexSet};
+use rayon::prelude::*;
+use sonic_rs::{from_str, prelude::*, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    fs::{read_dir, read_to_string, remove_file, write, DirEntry},
+    hash::BuildHasherDefault,
+    path::Path,
+};
+use strsim::normalized_levenshtein;
+use xxhash_rust::xxh3::Xxh3;
+
+/// Separator between original and translation in `_obsolete.txt` files.
+pub const OBSOLETE_SEPARATOR: &str = "<#>";
+
+/// Translation of the old original, that's proposed for the edited new original.
+pub struct Proposal {
+    pub original: String,
+    pub old_original: String,
+    pub translation: String,
+    pub similarity: f64,
+}
+
+/// Result of migration of a single translation file.
+pub struct Migration {
+    pub filename: String,
+    /// Count of translations, which originals matched exactly
+    pub carried: usize,
+    pub proposals: Vec<Proposal>,
+    /// New originals, which didn't exist in the old version and have no proposed translation
+    pub untranslated: Vec<String>,
+    /// Translated old originals, which don't exist in the new version
+    pub obsolete: Vec<(String, String)>,
+}
+
+/// Reads lines of the file, or returns an empty vector, if file doesn't exist.
+pub fn read_lines(path: &Path) -> Vec<String> {
+    read_to_string(path)
+        .map(|content: String| content.split('\n').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Collects string values of JSON value recursively. Plugin parameters are often JSON encoded into strings, so
+/// such strings are parsed and collected too.
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    if let Some(string) = value.as_str() {
+        if let Ok(nested) = from_str::<Value>(string) {
+            if nested.is_array() || nested.is_object() {
+                collect_strings(&nested, strings);
+            }
+        }
+
+        strings.push(string.to_string());
+    } else if let Some(array) = value.as_array() {
+        for value in array.iter() {
+            collect_strings(value, strings);
+        }
+    } else if let Some(object) = value.as_object() {
+        for (_, value) in object.iter() {
+            collect_strings(value, strings);
+        }
+    }
+}
+
+/// Extracts the new version of `plugins.txt` for migration. Plugins text isn't read by the program, so old originals,
+/// which still occur in the new `plugins.js`, are kept. Returns the new plugins array for `plugins.json`, or `None`,
+/// if `plugins.js` doesn't exist or can't be parsed.
+/// # Parameters
+/// * `plugins_file_path` - path to the new `plugins.js`
+/// * `old_path` - path to the translation/plugins directory
+/// * `new_path` - path to the directory with the new extraction
+pub fn extract_plugins(plugins_file_path: &Path, old_path: &Path, new_path: &Path) -> Option<String> {
+    let content: String = read_to_string(plugins_file_path).ok()?;
+    let array: &str = &content[content.find('[')?..=content.rfind(']')?];
+    let value: Value = from_str(array).ok()?;
+
+    let mut strings: Vec<String> = Vec::new();
+    collect_strings(&value, &mut strings);
+
+    let new_originals: Vec<String> = read_lines(&old_path.join("plugins.txt"))
+        .into_iter()
+        .filter(|original: &String| strings.iter().any(|string: &String| string.contains(original.as_str())))
+        .collect();
+
+    write(new_path.join("plugins.txt"), new_originals.join("\n")).unwrap();
+    Some(array.to_string())
+}
+
+/// Migrates translation of a single file from the old extraction to the new one.
+///
+/// Exactly matching originals keep their translation, edited originals get fuzzy proposals, and translated
+/// originals, which were removed, are appended to `<name>_obsolete.txt`.
+/// # Parameters
+/// * `old_path` - path to the directory with old translation files, which are overwritten
+/// * `new_path` - path to the directory with the new extraction
+/// * `name` - name of the translation file without extension
+/// * `min_similarity` - minimal normalized similarity for fuzzy proposals, from 0 to 1
+/// * `apply_proposals` - whether proposed translations are written to the translation file, so translation of their
+///   old originals isn't obsolete
+fn migrate_file(old_path: &Path, new_path: &Path, name: &str, min_similarity: f64, apply_proposals: bool) -> Migration {
+    let old_original_path: &Path = &old_path.join(format!("{name}.txt"));
+    let old_translation_path: &Path = &old_path.join(format!("{name}_trans.txt"));
+    let new_original_path: &Path = &new_path.join(format!("{name}.txt"));
+
+    let old_pairs: IndexMap<String, String, BuildHasherDefault<Xxh3>> = read_lines(old_original_path)
+        .into_iter()
+        .zip(read_lines(old_translation_path))
+        .collect();
+
+    let new_originals: Vec<String> = read_lines(new_original_path);
+
+    let mut translations: Vec<String> = Vec::with_capacity(new_originals.len());
+    let mut matched: HashSet<&str, BuildHasherDefault<Xxh3>> = HashSet::default();
+    let mut carried: usize = 0;
+
+    for original in &new_originals {
+        match old_pairs.get(original) {
+            Some(translation) => {
+                if !translation.is_empty() {
+                    carried += 1;
+                }
+
+                matched.insert(original.as_str());
+                translations.push(translation.to_string());
+            }
+            None => translations.push(String::new()),
+        }
+    }
+
+    let mut obsolete: Vec<(String, String)> = old_pairs
+        .iter()
+        .filter(|(original, translation)| !translation.is_empty() && !matched.contains(original.as_str()))
+        .map(|(original, translation)| (original.to_string(), translation.to_string()))
+        .collect();
+
+    let obsolete_lengths: Vec<usize> = obsolete.iter().map(|(original, _)| original.chars().count()).collect();
+
+    // Edited lines are new originals, which are similar to one of the obsolete originals
+    let (proposals, untranslated): (Vec<Option<Proposal>>, Vec<Option<String>>) = new_originals
+        .par_iter()
+        .filter(|original: &&String| !original.is_empty() && !old_pairs.contains_key(*original))
+        .map(|original: &String| {
+            let length: usize = original.chars().count();
+
+            // Edit distance is at least the difference of lengths, so similarity can't exceed the ratio of lengths,
+            // and originals of too different lengths aren't compared
+            let best: Option<(f64, &(String, String))> = obsolete
+                .iter()
+                .zip(obsolete_lengths.iter())
+                .filter(|(_, &old_length)| {
+                    length.min(old_length) as f64 >= min_similarity * length.max(old_length) as f64
+                })
+                .map(|(pair, _)| (normalized_levenshtein(original, &pair.0), pair))
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+
+            match best {
+                Some((similarity, (old_original, translation))) if similarity >= min_similarity => (
+                    Some(Proposal {
+                        original: original.to_string(),
+                        old_original: old_original.to_string(),
+                        translation: translation.to_string(),
+                        similarity,
+                    }),
+                    None,
+                ),
+                _ => (None, Some(original.to_string())),
+            }
+        })
+        .unzip();
+
+    let proposals: Vec<Proposal> = proposals.into_iter().flatten().collect();
+
+    if apply_proposals {
+        let proposed: HashMap<&str, &str, BuildHasherDefault<Xxh3>> = proposals
+            .iter()
+            .map(|proposal: &Proposal| (proposal.original.as_str(), proposal.translation.as_str()))
+            .collect();
+
+        for (original, translation) in new_originals.iter().zip(translations.iter_mut()) {
+            if let Some(proposed) = proposed.get(original.as_str()) {
+                *translation = proposed.to_string();
+            }
+        }
+
+        let used: HashSet<&str, BuildHasherDefault<Xxh3>> = proposals
+            .iter()
+            .map(|proposal: &Proposal| proposal.old_original.as_str())
+            .collect();
+
+        obsolete.retain(|(original, _)| !used.contains(original.as_str()));
+    }
+
+    if new_original_path.exists() {
+        write(old_original_path, new_originals.join("\n")).unwrap();
+        write(old_translation_path, translations.join("\n")).unwrap();
+    } else {
+        // File doesn't exist in the new version, so all of its translation is obsolete
+        let _ = remove_file(old_original_path);
+        let _ = remove_file(old_translation_path);
+    }
+
+    if !obsolete.is_empty() {
+        let obsolete_path: &Path = &old_path.join(format!("{name}_obsolete.txt"));
+
+        let mut obsolete_lines: IndexSet<String, BuildHasherDefault<Xxh3>> = read_lines(obsolete_path)
+            .into_iter()
+            .filter(|line: &String| !line.is_empty())
+            .collect();
+
+        obsolete_lines.extend(
+            obsolete
+                .iter()
+                .map(|(original, translation)| format!("{original}{OBSOLETE_SEPARATOR}{translation}")),
+        );
+
+        write(obsolete_path, obsolete_lines.into_iter().collect::<Vec<_>>().join("\n")).unwrap();
+    }
+
+    Migration {
+        filename: format!("{name}.txt"),
+        carried,
+        proposals,
+        untranslated: untranslated.into_iter().flatten().collect(),
+        obsolete,
+    }
+}
+
+/// Migrates all translation files of the old extraction to the new one. Returns results sorted by filename.
+/// # Parameters
+/// * `old_path` - path to the directory with old translation files, which are overwritten
+/// * `new_path` - path to the directory with the new extraction
+/// * `min_similarity` - minimal normalized similarity for fuzzy proposals, from 0 to 1
+/// * `apply_proposals` - whether proposed translations are written to the translation files
+/// * `is_processed` - whether the file with the name (without extension) should be migrated
+pub fn migrate(
+    old_path: &Path,
+    new_path: &Path,
+    min_similarity: f64,
+    apply_proposals: bool,
+    is_processed: impl Fn(&str) -> bool,
+) -> Vec<Migration> {
+    let mut names: Vec<String> = [old_path, new_path]
+        .into_iter()
+        .flat_map(|path: &Path| read_dir(path).unwrap().flatten())
+        .filter_map(|entry: DirEntry| {
+            let filename: OsString = entry.file_name();
+            let name: &str = filename.to_str()?.strip_suffix(".txt")?;
+
+            if name.ends_with("_trans") || name.ends_with("_obsolete") || !is_processed(name) {
+                None
+            } else {
+                Some(name.to_string())
+            }
+        })
+        .collect();
+
+    names.sort();
+    names.dedup();
+
+    names
+        .iter()
+        .map(|name: &String| migrate_file(old_path, new_path, name, min_similarity, apply_proposals))
+        .collect()
+}
@@ -0,0 +1,151 @@
+use regex::{escape, Regex};
+use std::ops::Range;
+
+/// Form of the tag, which contents are translatable.
+#[derive(Clone, Copy, PartialEq)]
+enum NoteTagForm {
+    /// Both `<Tag>...</Tag>` and `<Tag: ...>`
+    Any,
+    /// Only `<Tag>...</Tag>`
+    Block,
+    /// Only `<Tag: ...>`
+    Value,
+}
+
+/// Rule, which marks contents of a note tag as translatable. Rule is written as the tag name, like `Help Description`,
+/// which matches both block tags, like `<Help Description>...</Help Description>`, and value tags, like
+/// `<Help Description: ...>`. `:block` or `:value` suffix, like `Quest Text:value`, restricts it to one of the forms.
+pub struct NoteTagRule {
+    /// Rule as it's written, which is recorded in the metadata
+    pub rule: String,
+    block: Option<Regex>,
+    value: Option<Regex>,
+}
+
+impl NoteTagRule {
+    /// Returns None, if the rule has an empty tag name.
+    /// # Parameters
+    /// * `rule` - tag name, which is matched case-insensitively, optionally followed by `:block` or `:value`
+    pub fn parse(rule: &str) -> Option<Self> {
+        let rule: &str = rule.trim();
+
+        let (tag, form): (&str, NoteTagForm) = match rule.rsplit_once(':') {
+            Some((tag, suffix)) if suffix.trim().eq_ignore_ascii_case("block") => (tag.trim(), NoteTagForm::Block),
+            Some((tag, suffix)) if suffix.trim().eq_ignore_ascii_case("value") => (tag.trim(), NoteTagForm::Value),
+            _ => (rule, NoteTagForm::Any),
+        };
+
+        if tag.is_empty() {
+            return None;
+        }
+
+        let escaped: String = escape(tag);
+
+        Some(Self {
+            rule: rule.to_string(),
+            block: (form != NoteTagForm::Value)
+                .then(|| Regex::new(&format!(r"(?is)<{escaped}>(.*?)</{escaped}>")).unwrap()),
+            value: (form != NoteTagForm::Block).then(|| Regex::new(&format!(r"(?i)<{escaped}:([^>]*)>")).unwrap()),
+        })
+    }
+}
+
+/// Parses rules, which are used when reading and writing notes. Empty list keeps extraction of whole notes.
+pub fn parse_note_tag_rules(rules: &[String]) -> Vec<NoteTagRule> {
+    rules
+        .iter()
+        .filter_map(|rule: &String| NoteTagRule::parse(rule))
+        .collect()
+}
+
+/// Returns byte ranges of translatable fragments of the note in order of their occurrence. Surrounding whitespace
+/// isn't included, and empty fragments and fragments inside of already matched ones are skipped.
+pub fn find_note_fragments(note: &str, rules: &[NoteTagRule]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = rules
+        .iter()
+        .flat_map(|rule: &NoteTagRule| rule.block.iter().chain(rule.value.iter()))
+        .flat_map(|regex: &Regex| regex.captures_iter(note))
+        .filter_map(|captures| {
+            let content = captures.get(1)?;
+            let trimmed: &str = content.as_str().trim();
+
+            if trimmed.is_empty() {
+                return None;
+            }
+
+            let start: usize = content.start() + content.as_str().len() - content.as_str().trim_start().len();
+            Some(start..start + trimmed.len())
+        })
+        .collect();
+
+    ranges.sort_by_key(|range: &Range<usize>| (range.start, usize::MAX - range.end));
+
+    let mut fragments: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        if fragments
+            .last()
+            .is_none_or(|last: &Range<usize>| range.start >= last.end)
+        {
+            fragments.push(range);
+        }
+    }
+
+    fragments
+}
+
+/// Replaces translatable fragments of the note with their translations, keeping the rest of the note unchanged.
+/// Returns None, if none of the fragments has translation.
+/// # Parameters
+/// * `note` - original note
+/// * `rules` - note tag rules
+/// * `translate` - returns translation of the fragment, if any
+pub fn splice_note_fragments(
+    note: &str,
+    rules: &[NoteTagRule],
+    translate: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let mut result: String = String::with_capacity(note.len());
+    let mut last_end: usize = 0;
+    let mut translated_any: bool = false;
+
+    for range in find_note_fragments(note, rules) {
+        if let Some(translated) = translate(&note[range.clone()]) {
+            result.push_str(&note[last_end..range.start]);
+            result.push_str(&translated);
+            last_end = range.end;
+            translated_any = true;
+        }
+    }
+
+    if !translated_any {
+        return None;
+    }
+
+    result.push_str(&note[last_end..]);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_suffix_restricts_matched_tags() {
+        let note: &str = "<Quest Text>Block</Quest Text>\n<Quest Text: Value>\n<Price: 100>";
+
+        let fragments = |rules: &[&str]| -> Vec<&str> {
+            let rules: Vec<NoteTagRule> =
+                parse_note_tag_rules(&rules.iter().map(|rule: &&str| rule.to_string()).collect::<Vec<_>>());
+            find_note_fragments(note, &rules)
+                .into_iter()
+                .map(|range: Range<usize>| &note[range])
+                .collect()
+        };
+
+        assert_eq!(fragments(&["Quest Text"]), ["Block", "Value"]);
+        assert_eq!(fragments(&["Quest Text:block"]), ["Block"]);
+        assert_eq!(fragments(&["quest text : VALUE"]), ["Value"]);
+        assert!(fragments(&[":value", " "]).is_empty());
+    }
+}
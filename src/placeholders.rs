@@ -0,0 +1,190 @@
+use crate::{migrate::read_lines, ESCAPE_CODE_RE};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::path::Path;
+
+/// Matches numbered placeholders, like `{1}`, which replace escape codes in exported text, and doubled braces, which
+/// literal braces of the text are escaped with.
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{|\}\}|\{([0-9]+)\}").unwrap());
+
+/// Translation files, which text is extracted from event commands and may contain placeholders.
+const PROTECTED_FILES: [(&str, &str); 3] = [("maps", "maps"), ("other", "commonevents"), ("other", "troops")];
+
+pub enum PlaceholderIssueKind {
+    Missing,
+    Duplicated,
+    Unknown,
+}
+
+/// Placeholder, which translation dropped, duplicated or invented.
+pub struct PlaceholderIssue {
+    /// Name of the translation file, like `maps_trans.txt`
+    pub filename: String,
+    /// 1-based line number in the translation file
+    pub line: usize,
+    pub placeholder: String,
+    pub kind: PlaceholderIssueKind,
+}
+
+/// Replaces escape codes of the string with numbered placeholders, starting from `{1}`. Literal braces of the text
+/// are doubled, so they can't be taken for placeholders. Returns the protected string and the replaced codes.
+pub fn protect_escape_codes(string: &str) -> (String, Vec<String>) {
+    let mut codes: Vec<String> = Vec::new();
+    let mut protected: String = String::with_capacity(string.len());
+    let mut last_end: usize = 0;
+
+    for code in ESCAPE_CODE_RE.find_iter(string) {
+        protected.push_str(&string[last_end..code.start()].replace('{', "{{").replace('}', "}}"));
+        codes.push(code.as_str().to_string());
+        protected.push_str(&format!("{{{}}}", codes.len()));
+        last_end = code.end();
+    }
+
+    protected.push_str(&string[last_end..].replace('{', "{{").replace('}', "}}"));
+    (protected, codes)
+}
+
+/// Replaces numbered placeholders of the translation with the escape codes, and doubled braces with single ones.
+/// Placeholders without a code are kept.
+pub fn restore_escape_codes(string: &str, codes: &[String]) -> String {
+    PLACEHOLDER_RE
+        .replace_all(string, |captures: &Captures| match captures.get(1) {
+            Some(number) => number
+                .as_str()
+                .parse::<usize>()
+                .ok()
+                .and_then(|number: usize| codes.get(number.wrapping_sub(1)))
+                .map_or_else(|| captures[0].to_string(), String::to_string),
+            None => captures[0][..1].to_string(),
+        })
+        .into_owned()
+}
+
+/// Returns numbered placeholders of the string, like `{1}`, skipping doubled braces.
+pub fn find_placeholders(string: &str) -> Vec<&str> {
+    PLACEHOLDER_RE
+        .captures_iter(string)
+        .filter(|captures: &Captures| captures.get(1).is_some())
+        .map(|captures: Captures| captures.get(0).unwrap().as_str())
+        .collect()
+}
+
+/// Compares placeholders of originals and translations. Every placeholder of the original must occur in the
+/// translation exactly once, and translation must not contain other placeholders. Untranslated lines are skipped.
+/// # Parameters
+/// * `translation_path` - path to the translation directory
+pub fn check_placeholders(translation_path: &Path) -> Vec<PlaceholderIssue> {
+    let mut issues: Vec<PlaceholderIssue> = Vec::new();
+
+    for (subdir, name) in PROTECTED_FILES {
+        let originals: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}.txt")));
+        let translations: Vec<String> = read_lines(&translation_path.join(format!("{subdir}/{name}_trans.txt")));
+
+        for (i, (original, translation)) in originals.iter().zip(translations.iter()).enumerate() {
+            if translation.is_empty() {
+                continue;
+            }
+
+            let expected: Vec<&str> = find_placeholders(original);
+            let found: Vec<&str> = find_placeholders(translation);
+
+            let mut push = |placeholder: &str, kind: PlaceholderIssueKind| {
+                issues.push(PlaceholderIssue {
+                    filename: format!("{name}_trans.txt"),
+                    line: i + 1,
+                    placeholder: placeholder.to_string(),
+                    kind,
+                })
+            };
+
+            for placeholder in expected.iter() {
+                match found.iter().filter(|found| *found == placeholder).count() {
+                    0 => push(placeholder, PlaceholderIssueKind::Missing),
+                    1 => {}
+                    _ => push(placeholder, PlaceholderIssueKind::Duplicated),
+                }
+            }
+
+            let mut unknown: Vec<&str> = found
+                .into_iter()
+                .filter(|placeholder| !expected.contains(placeholder))
+                .collect();
+            unknown.sort();
+            unknown.dedup();
+
+            for placeholder in unknown {
+                push(placeholder, PlaceholderIssueKind::Unknown);
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        env::temp_dir,
+        fs::{create_dir_all, remove_dir_all, write},
+        path::PathBuf,
+    };
+
+    #[test]
+    fn protect_and_restore_round_trip() {
+        let original: &str = r"Hello, \C[2]\N[1]\C[0]! {0} costs {price}\{";
+        let (protected, codes): (String, Vec<String>) = protect_escape_codes(original);
+
+        assert_eq!(protected, "Hello, {1}{2}{3}! {{0}} costs {{price}}{4}");
+        assert_eq!(codes, [r"\C[2]", r"\N[1]", r"\C[0]", r"\{"]);
+        assert_eq!(find_placeholders(&protected), ["{1}", "{2}", "{3}", "{4}"]);
+        assert_eq!(restore_escape_codes(&protected, &codes), original);
+        assert_eq!(
+            restore_escape_codes("{4}{2}{{0}} {9}, {1}{3}", &codes),
+            r"\{\N[1]{0} {9}, \C[2]\C[0]"
+        );
+    }
+
+    #[test]
+    fn check_placeholders_reports_issues() {
+        let translation_path: PathBuf = temp_dir().join(format!("rvpacker-txt-rs-placeholders-{}", std::process::id()));
+        create_dir_all(translation_path.join("maps")).unwrap();
+
+        write(
+            translation_path.join("maps/maps.txt"),
+            "{1} and {2}\n{1}\n{1}\n{{1}}\n{1} {2}\nUntranslated {1}",
+        )
+        .unwrap();
+        write(
+            translation_path.join("maps/maps_trans.txt"),
+            "{2} и {1}\n{1}{1}\n{1} {3}\n{1}\nтолько {1}\n",
+        )
+        .unwrap();
+
+        let issues: Vec<PlaceholderIssue> = check_placeholders(&translation_path);
+        remove_dir_all(&translation_path).unwrap();
+
+        let issues: Vec<(usize, &str, &str)> = issues
+            .iter()
+            .map(|issue: &PlaceholderIssue| {
+                let kind: &str = match issue.kind {
+                    PlaceholderIssueKind::Missing => "missing",
+                    PlaceholderIssueKind::Duplicated => "duplicated",
+                    PlaceholderIssueKind::Unknown => "unknown",
+                };
+
+                (issue.line, issue.placeholder.as_str(), kind)
+            })
+            .collect();
+
+        assert_eq!(
+            issues,
+            [
+                (2, "{1}", "duplicated"),
+                (3, "{3}", "unknown"),
+                (4, "{1}", "unknown"),
+                (5, "{2}", "missing")
+            ]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{
+    events::{Event, Output},
+    migrate::read_lines,
+    placeholders::{find_placeholders, protect_escape_codes, restore_escape_codes},
+};
+use sonic_rs::{from_str, prelude::*, to_string, Array, Object, Value};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs::{read_dir, read_to_string, write},
+    hash::BuildHasherDefault,
+    path::Path,
+    time::Duration,
+};
+use ureq::{Agent, AgentBuilder, Error};
+use xxhash_rust::xxh3::Xxh3;
+
+/// File in the translation directory, which stores responses of translation backends.
+pub const PRETRANSLATE_CACHE_FILENAME: &str = ".rvpacker-txt-rs-pretranslate-cache.json";
+
+/// File in the translation directory, which stores machine-generated translation lines. Translation, that still
+/// equals the stored one, wasn't reviewed by a human.
+pub const MACHINE_TRANSLATION_FILENAME: &str = ".rvpacker-txt-rs-machine-translation.json";
+
+/// Subdirectories of the translation directory, which contain translation files.
+const TRANSLATION_SUBDIRS: [&str; 3] = ["maps", "other", "plugins"];
+
+/// Separator of lines inside of a single translation entry.
+const LINE_SEPARATOR: &str = r"\#";
+
+type Responses = HashMap<String, String, BuildHasherDefault<Xxh3>>;
+
+/// Machine translation service, that translates batches of texts.
+pub trait TranslationBackend {
+    /// Returns identifier of the backend and its languages, under which responses are cached.
+    fn id(&self) -> String;
+
+    /// Translates texts, returning translations in the same order.
+    fn translate(&self, texts: &[String]) -> Result<Vec<String>, String>;
+}
+
+/// Backend for servers with LibreTranslate-compatible API, including self-hosted ones.
+pub struct LibreTranslate {
+    url: String,
+    api_key: Option<String>,
+    source: String,
+    target: String,
+    agent: Agent,
+}
+
+impl LibreTranslate {
+    /// # Parameters
+    /// * `url` - base URL of the server, like `http://localhost:5000`
+    /// * `api_key` - API key, if server requires it
+    /// * `source` - language code of the original text
+    /// * `target` - language code of the translation
+    pub fn new(url: &str, api_key: Option<&str>, source: &str, target: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.map(str::to_string),
+            source: source.to_string(),
+            target: target.to_string(),
+            agent: AgentBuilder::new().timeout(Duration::from_secs(300)).build(),
+        }
+    }
+}
+
+impl TranslationBackend for LibreTranslate {
+    fn id(&self) -> String {
+        format!("libretranslate:{}:{}:{}", self.url, self.source, self.target)
+    }
+
+    fn translate(&self, texts: &[String]) -> Result<Vec<String>, String> {
+        let mut body: Object = Object::new();
+        body.insert(
+            "q",
+            Array::from(texts.iter().map(String::as_str).collect::<Vec<&str>>()),
+        );
+        body.insert("source", self.source.as_str());
+        body.insert("target", self.target.as_str());
+        body.insert("format", "text");
+
+        if let Some(api_key) = &self.api_key {
+            body.insert("api_key", api_key.as_str());
+        }
+
+        let response: String = self
+            .agent
+            .post(&format!("{}/translate", self.url))
+            .set("Content-Type", "application/json")
+            .send_string(&to_string(&body).unwrap())
+            .map_err(|err| match err {
+                Error::Status(code, response) => format!("{code} {}", response.into_string().unwrap_or_default()),
+                err => err.to_string(),
+            })?
+            .into_string()
+            .map_err(|err| err.to_string())?;
+
+        // Unexpected response is returned as is, as it usually contains the error message of the server
+        let translations: Option<Vec<String>> = from_str::<Value>(&response).ok().and_then(|response_value: Value| {
+            response_value["translatedText"]
+                .as_array()?
+                .iter()
+                .map(|translation: &Value| translation.as_str().map(str::to_string))
+                .collect()
+        });
+
+        match translations {
+            Some(translations) if translations.len() == texts.len() => Ok(translations),
+            _ => Err(response),
+        }
+    }
+}
+
+/// Part of the original entry between `\#` separators, which escape codes are replaced with `{N}` placeholders,
+/// like with `--protect-codes`, and surrounding whitespace is cut off.
+struct Segment {
+    prefix: String,
+    text: String,
+    suffix: String,
+    codes: Vec<String>,
+}
+
+impl Segment {
+    fn new(segment: &str) -> Self {
+        let trimmed: &str = segment.trim();
+        let start: usize = segment.len() - segment.trim_start().len();
+
+        let (text, codes): (String, Vec<String>) = protect_escape_codes(trimmed);
+
+        Self {
+            prefix: segment[..start].to_string(),
+            text,
+            suffix: segment[start + trimmed.len()..].to_string(),
+            codes,
+        }
+    }
+
+    /// Segments without letters, like ones, that consist only of escape codes or numbers, are kept as is.
+    fn needs_translation(&self) -> bool {
+        self.text.chars().any(char::is_alphabetic)
+    }
+
+    /// Puts escape codes back to the translated text. Returns None, if backend lost any of the placeholders.
+    fn restore(&self, translation: &str) -> Option<String> {
+        let translation: &str = translation.trim();
+        let placeholders: Vec<&str> = find_placeholders(translation);
+
+        for number in 1..=self.codes.len() {
+            if !placeholders.contains(&format!("{{{number}}}").as_str()) {
+                return None;
+            }
+        }
+
+        Some(format!(
+            "{}{}{}",
+            self.prefix,
+            restore_escape_codes(translation, &self.codes),
+            self.suffix
+        ))
+    }
+}
+
+fn load_object(path: &Path) -> Object {
+    read_to_string(path)
+        .ok()
+        .and_then(|content: String| from_str::<Object>(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Translates all texts, which aren't in responses yet, in batches, and saves responses after each batch, so
+/// interrupted pretranslation doesn't lose them.
+/// # Parameters
+/// * `texts` - texts to translate
+/// * `responses` - cached responses of the backend
+/// * `cache_obj` - object of the cache file, to which responses are saved
+/// * `cache_path` - path to the cache file
+/// * `backend` - translation backend
+/// * `batch_size` - count of texts in a single request
+fn translate_texts(
+    texts: Vec<&String>,
+    responses: &mut Responses,
+    cache_obj: &mut Object,
+    cache_path: &Path,
+    backend: &dyn TranslationBackend,
+    batch_size: usize,
+) -> Result<(), String> {
+    let mut pending: Vec<String> = texts
+        .into_iter()
+        .filter(|text: &&String| !responses.contains_key(*text))
+        .cloned()
+        .collect();
+
+    pending.sort();
+    pending.dedup();
+
+    for batch in pending.chunks(batch_size.max(1)) {
+        let translations: Vec<String> = backend.translate(batch)?;
+        responses.extend(batch.iter().cloned().zip(translations));
+
+        let mut responses_obj: Object = Object::new();
+
+        for (text, translation) in responses.iter() {
+            responses_obj.insert(text, translation.as_str());
+        }
+
+        cache_obj.insert(&backend.id(), responses_obj);
+        write(cache_path, to_string(&cache_obj).unwrap()).unwrap();
+    }
+
+    Ok(())
+}
+
+/// Fills empty lines of translation files with machine translation. `\#` separators and escape codes are kept
+/// unchanged, and lines, in which backend lost any of the escape codes, are left empty. Filled lines are recorded
+/// in the machine translation file. Returns count of filled and skipped lines.
+/// # Parameters
+/// * `translation_path` - path to the translation directory
+/// * `backend` - translation backend
+/// * `batch_size` - count of texts in a single request
+/// * `is_processed` - whether the file with the name (without extension) in the subdirectory should be pretranslated
+/// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `file_written_msg` - message to log when file is written
+pub fn pretranslate(
+    translation_path: &Path,
+    backend: &dyn TranslationBackend,
+    batch_size: usize,
+    is_processed: impl Fn(&str, &str) -> bool,
+    logging: bool,
+    output: Output,
+    file_written_msg: &str,
+) -> Result<(usize, usize), String> {
+    let cache_path: &Path = &translation_path.join(PRETRANSLATE_CACHE_FILENAME);
+    let machine_translation_path: &Path = &translation_path.join(MACHINE_TRANSLATION_FILENAME);
+
+    let mut cache_obj: Object = load_object(cache_path);
+    let mut responses: Responses = cache_obj
+        .get(&backend.id())
+        .and_then(|value: &Value| value.as_object())
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(text, translation)| Some((text.to_string(), translation.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut machine_translation_obj: Object = load_object(machine_translation_path);
+    let (mut filled, mut skipped): (usize, usize) = (0, 0);
+
+    for subdir in TRANSLATION_SUBDIRS {
+        let Ok(entries) = read_dir(translation_path.join(subdir)) else {
+            continue;
+        };
+
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let filename: OsString = entry.file_name();
+                let name: &str = filename.to_str()?.strip_suffix(".txt")?;
+
+                if name.ends_with("_trans") || name.ends_with("_obsolete") || !is_processed(subdir, name) {
+                    None
+                } else {
+                    Some(name.to_string())
+                }
+            })
+            .collect();
+
+        names.sort();
+
+        for name in names {
+            let original_file_path: &Path = &translation_path.join(format!("{subdir}/{name}.txt"));
+            let translation_file_path: &Path = &translation_path.join(format!("{subdir}/{name}_trans.txt"));
+
+            let originals: Vec<String> = read_lines(original_file_path);
+            let mut translations: Vec<String> = read_lines(translation_file_path);
+            translations.resize(originals.len(), String::new());
+
+            // Indices of untranslated entries and their segments. Entries without any text, like ones, that consist
+            // only of escape codes, are skipped
+            let entries: Vec<(usize, Vec<Segment>)> = originals
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| translations[*i].is_empty())
+                .map(|(i, original)| (i, original.split(LINE_SEPARATOR).map(Segment::new).collect::<Vec<_>>()))
+                .filter(|(_, segments)| segments.iter().any(Segment::needs_translation))
+                .collect();
+
+            if entries.is_empty() {
+                continue;
+            }
+
+            let texts: Vec<&String> = entries
+                .iter()
+                .flat_map(|(_, segments)| segments.iter())
+                .filter(|segment: &&Segment| segment.needs_translation())
+                .map(|segment: &Segment| &segment.text)
+                .collect();
+
+            translate_texts(texts, &mut responses, &mut cache_obj, cache_path, backend, batch_size)?;
+
+            let relative_path: String = format!("{subdir}/{name}_trans.txt");
+            let mut file_machine_translation: Object = machine_translation_obj
+                .get(&relative_path)
+                .and_then(|value: &Value| value.as_object())
+                .cloned()
+                .unwrap_or_default();
+
+            for (i, segments) in entries {
+                let translated: Option<Vec<String>> = segments
+                    .iter()
+                    .map(|segment: &Segment| {
+                        let translation: &str = if segment.needs_translation() {
+                            &responses[&segment.text]
+                        } else {
+                            &segment.text
+                        };
+
+                        segment.restore(translation)
+                    })
+                    .collect();
+
+                match translated {
+                    Some(translated) => {
+                        translations[i] = translated.join(LINE_SEPARATOR);
+                        file_machine_translation.insert(&originals[i], translations[i].as_str());
+                        filled += 1;
+                    }
+                    None => skipped += 1,
+                }
+            }
+
+            let translation_content: String = translations.join("\n");
+            write(translation_file_path, &translation_content).unwrap();
+            machine_translation_obj.insert(&relative_path, file_machine_translation);
+            write(machine_translation_path, to_string(&machine_translation_obj).unwrap()).unwrap();
+
+            if output.is_json() {
+                output.emit(Event::FileWritten {
+                    file: &relative_path,
+                    bytes: translation_content.len(),
+                });
+            } else if logging {
+                println!("{file_written_msg} {}", translation_file_path.display());
+            }
+        }
+    }
+
+    Ok((filled, skipped))
+}
@@ -0,0 +1,184 @@
+use crate::events::{Event, Output};
+use std::{
+    ffi::OsString,
+    fs::read_dir,
+    io::{stderr, Write},
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Minimal interval between redraws of the progress line, so parallel readers don't flood the terminal.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Phase, which is running now.
+struct Phase {
+    name: &'static str,
+    total: usize,
+    done: usize,
+    start: Instant,
+    last_redraw: Option<Instant>,
+}
+
+/// Labels of the progress line and summary, which are set from the localization.
+struct ProgressLabels {
+    eta_label: String,
+    phase_timings_msg: String,
+}
+
+/// Tracks phases of readers and writers, draws the progress line and records durations of the phases.
+pub struct Progress {
+    output: Output,
+    labels: Option<ProgressLabels>,
+    current_phase: Mutex<Option<Phase>>,
+    phase_timings: Mutex<Vec<(&'static str, f64)>>,
+}
+
+/// Counts data files of the directory, which readers and writers of the phase process.
+/// # Parameters
+/// * `path` - path to the directory with data files
+/// * `predicate` - returns whether the name of the file without extension belongs to the phase
+pub fn count_files(path: &Path, predicate: impl Fn(&str) -> bool) -> usize {
+    let Ok(entries) = read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            let filename: OsString = entry.file_name();
+
+            filename.to_str().is_some_and(|filename: &str| {
+                filename.split_once('.').is_some_and(|(name, extension)| {
+                    ["json", "rvdata2", "rvdata", "rxdata"].contains(&extension) && predicate(name)
+                })
+            })
+        })
+        .count()
+}
+
+fn redraw(phase: &Phase, eta_label: &str) {
+    let elapsed: f64 = phase.start.elapsed().as_secs_f64();
+    let done: usize = phase.done.min(phase.total);
+
+    let mut line: String = format!("\r{}: {done}/{}", phase.name, phase.total);
+
+    if done > 0 && done < phase.total {
+        let eta: f64 = elapsed / done as f64 * (phase.total - done) as f64;
+        line.push_str(&format!(", {eta_label} {eta:.1}s"));
+    }
+
+    // Trailing spaces erase the rest of the previous, longer line
+    let _ = write!(stderr().lock(), "{line:<48}");
+}
+
+impl Progress {
+    /// Creates the progress of readers and writers.
+    /// # Parameters
+    /// * `output` - where and how events and messages are printed
+    /// * `enabled` - whether to draw the progress line to stderr and print the summary of phase timings
+    /// * `eta_label` - label of the estimated remaining time
+    /// * `phase_timings_msg` - message to print before the summary
+    pub fn new(output: Output, enabled: bool, eta_label: &str, phase_timings_msg: &str) -> Self {
+        Self {
+            output,
+            labels: enabled.then(|| ProgressLabels {
+                eta_label: eta_label.to_string(),
+                phase_timings_msg: phase_timings_msg.to_string(),
+            }),
+            current_phase: Mutex::new(None),
+            phase_timings: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Marks a file of the current phase as processed, or skipped, and redraws the progress line.
+    pub fn advance(&self) {
+        let Some(labels) = &self.labels else {
+            return;
+        };
+
+        let mut current_phase = self.current_phase.lock().unwrap();
+
+        let Some(phase) = current_phase.as_mut() else {
+            return;
+        };
+
+        phase.done += 1;
+
+        if phase
+            .last_redraw
+            .is_none_or(|last_redraw: Instant| last_redraw.elapsed() >= REDRAW_INTERVAL)
+            || phase.done >= phase.total
+        {
+            phase.last_redraw = Some(Instant::now());
+            redraw(phase, &labels.eta_label);
+        }
+    }
+
+    /// Runs the phase and records its duration. Durations of the phases, that run multiple times, like when reading
+    /// multiple languages, are summed.
+    /// # Parameters
+    /// * `name` - name of the phase, the same as of `--disable-processing` values
+    /// * `total` - number of files, which the phase processes
+    /// * `run` - function, that runs readers or writers of the phase
+    pub fn run_phase<T>(&self, name: &'static str, total: usize, run: impl FnOnce() -> T) -> T {
+        let start: Instant = Instant::now();
+
+        *self.current_phase.lock().unwrap() = Some(Phase {
+            name,
+            total,
+            done: 0,
+            start,
+            last_redraw: None,
+        });
+
+        if let Some(labels) = &self.labels {
+            redraw(self.current_phase.lock().unwrap().as_ref().unwrap(), &labels.eta_label);
+        }
+
+        let result: T = run();
+        let duration: f64 = start.elapsed().as_secs_f64();
+
+        if self.labels.is_some() {
+            let _ = write!(stderr().lock(), "\r{:<48}\r", "");
+        }
+
+        *self.current_phase.lock().unwrap() = None;
+
+        if self.output.is_json() {
+            self.output.emit(Event::PhaseFinished {
+                phase: name,
+                files: total,
+                duration,
+            });
+        }
+
+        let mut phase_timings = self.phase_timings.lock().unwrap();
+
+        match phase_timings.iter_mut().find(|(phase, _)| *phase == name) {
+            Some((_, total_duration)) => *total_duration += duration,
+            None => phase_timings.push((name, duration)),
+        }
+
+        result
+    }
+
+    /// Prints durations of the phases, if the progress is enabled and any phase ran.
+    pub fn print_phase_timings(&self) {
+        let Some(labels) = &self.labels else {
+            return;
+        };
+
+        let phase_timings = self.phase_timings.lock().unwrap();
+
+        if phase_timings.is_empty() || self.output.is_json() {
+            return;
+        }
+
+        self.output.print_message(&labels.phase_timings_msg);
+
+        for (name, duration) in phase_timings.iter() {
+            self.output.print_message(&format!("  {name:<8} {duration:.3}s"));
+        }
+    }
+}
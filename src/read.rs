@@ -1,7 +1,17 @@
 #![allow(clippy::too_many_arguments)]
 use crate::{
-    romanize_string, write::extract_strings, Code, EngineType, GameType, ProcessingMode, Variable, ENDS_WITH_IF_RE,
-    INVALID_MULTILINE_VARIABLE_RE, INVALID_VARIABLE_RE, LISA_PREFIX_RE, STRING_IS_ONLY_SYMBOLS_RE,
+    cache::{Cache, CacheEntry, NAMES_SEED},
+    config::filter_event_codes,
+    events::{Event, Output, SkipReason},
+    file_filter::FileFilter,
+    note_tags::{find_note_fragments, NoteTagRule},
+    placeholders::protect_escape_codes,
+    progress::Progress,
+    romanization::RomanizationTable,
+    romanize_string,
+    write::extract_strings,
+    Code, EngineType, GameType, ProcessingMode, Variable, ENDS_WITH_IF_RE, INVALID_MULTILINE_VARIABLE_RE,
+    INVALID_VARIABLE_RE, LISA_PREFIX_RE, STRING_IS_ONLY_SYMBOLS_RE,
 };
 use encoding_rs::{CoderResult, Encoding};
 use flate2::read::ZlibDecoder;
@@ -9,8 +19,9 @@ use indexmap::{IndexMap, IndexSet};
 use marshal_rs::load::{load, StringMode};
 use rayon::prelude::*;
 use regex::Regex;
-use sonic_rs::{from_str, from_value, prelude::*, Array, Value};
+use sonic_rs::{from_slice, from_value, prelude::*, Array, Value};
 use std::{
+    collections::{HashMap, HashSet},
     ffi::OsString,
     fs::{read, read_dir, read_to_string, write, DirEntry},
     hash::{BuildHasher, BuildHasherDefault},
@@ -18,7 +29,7 @@ use std::{
     path::Path,
     str::{from_utf8_unchecked, Chars},
 };
-use xxhash_rust::xxh3::Xxh3;
+use xxhash_rust::xxh3::{xxh3_64_with_seed, Xxh3};
 
 trait Join {
     fn join(&self, delimiter: &str) -> String;
@@ -47,6 +58,7 @@ fn parse_parameter(
     mut parameter: &str,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
+    protect_codes: bool,
 ) -> Option<String> {
     if STRING_IS_ONLY_SYMBOLS_RE.is_match(parameter) {
         return None;
@@ -94,6 +106,10 @@ fn parse_parameter(
         }
     }
 
+    if protect_codes {
+        return Some(protect_escape_codes(parameter).0);
+    }
+
     Some(parameter.to_string())
 }
 
@@ -281,10 +297,30 @@ fn parse_variable(
     Some((variable_text, is_continuation_of_description))
 }
 
-fn parse_list<T: BuildHasher>(
+/// Map filename, its display name and lines, extracted from it, and whether lines of the map are only kept, as the map
+/// is unchanged or isn't selected.
+type ExtractedMap = (String, Option<String>, IndexSet<String, BuildHasherDefault<Xxh3>>, bool);
+
+/// Inserts line into the set, and in append mode, inserts it into the translation map at the position
+/// of the line in the set, if map doesn't contain it yet.
+fn insert_line<T: BuildHasher>(
+    line: String,
+    processing_mode: &ProcessingMode,
+    set: &mut IndexSet<String, T>,
+    map: &mut IndexMap<String, String, T>,
+) {
+    if processing_mode == ProcessingMode::Append && !map.contains_key(&line) {
+        map.shift_insert(set.len(), line.clone(), String::new());
+    }
+
+    set.insert(line);
+}
+
+pub fn parse_list<T: BuildHasher>(
     list: &Array,
     allowed_codes: &[u16],
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
     processing_mode: &ProcessingMode,
@@ -302,14 +338,15 @@ fn parse_list<T: BuildHasher>(
             if !line.is_empty() {
                 let mut joined: String = line.join("\n").trim().replace('\n', r"\#");
 
-                if romanize {
-                    joined = romanize_string(joined);
+                if let Some(table) = romanization_table {
+                    joined = romanize_string(joined, table);
                 }
 
-                let parsed: Option<String> = parse_parameter(Code::Dialogue, &joined, game_type, engine_type);
+                let parsed: Option<String> =
+                    parse_parameter(Code::Dialogue, &joined, game_type, engine_type, protect_codes);
 
                 if let Some(parsed) = parsed {
-                    if processing_mode == ProcessingMode::Append && !map.contains_key(&joined) {
+                    if processing_mode == ProcessingMode::Append && !map.contains_key(&parsed) {
                         map.shift_insert(set.len(), parsed.clone(), String::new());
                     }
 
@@ -386,12 +423,17 @@ fn parse_list<T: BuildHasher>(
                         .to_string();
 
                     if !subparameter_string.is_empty() {
-                        let parsed: Option<String> =
-                            parse_parameter(Code::Choice, &subparameter_string, game_type, engine_type);
+                        let parsed: Option<String> = parse_parameter(
+                            Code::Choice,
+                            &subparameter_string,
+                            game_type,
+                            engine_type,
+                            protect_codes,
+                        );
 
                         if let Some(mut parsed) = parsed {
-                            if romanize {
-                                parsed = romanize_string(parsed);
+                            if let Some(table) = romanization_table {
+                                parsed = romanize_string(parsed, table);
                             }
 
                             if processing_mode == ProcessingMode::Append && !map.contains_key(&parsed) {
@@ -430,11 +472,11 @@ fn parse_list<T: BuildHasher>(
 
                 if !parameter_string.is_empty() {
                     let parsed: Option<String> =
-                        parse_parameter(Code::System, &parameter_string, game_type, engine_type);
+                        parse_parameter(Code::System, &parameter_string, game_type, engine_type, protect_codes);
 
                     if let Some(mut parsed) = parsed {
-                        if romanize {
-                            parsed = romanize_string(parsed);
+                        if let Some(table) = romanization_table {
+                            parsed = romanize_string(parsed, table);
                         }
 
                         if processing_mode == ProcessingMode::Append && !map.contains_key(&parsed) {
@@ -472,11 +514,11 @@ fn parse_list<T: BuildHasher>(
 
                 if !parameter_string.is_empty() {
                     let parsed: Option<String> =
-                        parse_parameter(Code::Unknown, &parameter_string, game_type, engine_type);
+                        parse_parameter(Code::Unknown, &parameter_string, game_type, engine_type, protect_codes);
 
                     if let Some(mut parsed) = parsed {
-                        if romanize {
-                            parsed = romanize_string(parsed);
+                        if let Some(table) = romanization_table {
+                            parsed = romanize_string(parsed, table);
                         }
 
                         if processing_mode == ProcessingMode::Append && !map.contains_key(&parsed) {
@@ -497,24 +539,36 @@ fn parse_list<T: BuildHasher>(
 /// # Parameters
 /// * `maps_path` - path to directory than contains game files
 /// * `output_path` - path to output directory
-/// * `romanize` - whether to romanize text
+/// * `romanization_table` - table to romanize text with, or None, if text isn't romanized
+/// * `protect_codes` - whether to replace escape codes of event text with numbered placeholders
+/// * `event_codes` - codes of event commands, which text is extracted, or None, if text of all of them is
 /// * `logging` - whether to log
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_parsed_msg` - message to log when file is parsed
 /// * `file_already_parsed_msg` - message to log when file that's about to be parsed already exists (default processing mode)
 /// * `file_is_not_parsed_msg` - message to log when file that's about to be parsed not exist (append processing mode)
 /// * `game_type` - game type for custom parsing
 /// * `processing_mode` - whether to read in default mode, force rewrite or append new text to existing files
+/// * `file_filter` - selects files, which are processed
+/// * `cache` - if some, unchanged maps are skipped in append mode
 pub fn read_map(
     maps_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_parsed_msg: &str,
     file_already_parsed_msg: &str,
     file_is_not_parsed_msg: &str,
     game_type: Option<&GameType>,
     mut processing_mode: &ProcessingMode,
     engine_type: &EngineType,
+    file_filter: &FileFilter,
+    cache: Option<&Cache>,
 ) {
     let maps_output_path: &Path = &output_path.join("maps.txt");
     let maps_trans_output_path: &Path = &output_path.join("maps_trans.txt");
@@ -522,37 +576,26 @@ pub fn read_map(
     let names_trans_output_path: &Path = &output_path.join("names_trans.txt");
 
     if processing_mode == ProcessingMode::Default && maps_trans_output_path.exists() {
-        println!("maps_trans.txt {file_already_parsed_msg}");
+        if output.is_json() {
+            output.emit(Event::FileSkipped {
+                file: "maps_trans.txt",
+                reason: SkipReason::Exists,
+            });
+        } else {
+            output.print_message(&format!("maps_trans.txt {file_already_parsed_msg}"));
+        }
+
         return;
     }
 
-    let maps_obj_vec = read_dir(maps_path)
-        .unwrap()
-        .filter_map(|entry: Result<DirEntry, std::io::Error>| match entry {
-            Ok(entry) => {
-                let filename: OsString = entry.file_name();
-                let filename_str: &str = unsafe { from_utf8_unchecked(filename.as_encoded_bytes()) };
-
-                if filename_str.starts_with("Map")
-                    && unsafe { (*filename_str.as_bytes().get_unchecked(3) as char).is_ascii_digit() }
-                    && (filename_str.ends_with("json")
-                        || filename_str.ends_with("rvdata2")
-                        || filename_str.ends_with("rvdata")
-                        || filename_str.ends_with("rxdata"))
-                {
-                    let json: Value = if engine_type == EngineType::New {
-                        from_str(&read_to_string(entry.path()).unwrap()).unwrap()
-                    } else {
-                        load(&read(entry.path()).unwrap(), None, Some("")).unwrap()
-                    };
+    // When only some maps are selected, the file is rebuilt: entries of the selected maps are dropped and extracted
+    // again in their place, while lines and translation of other maps are kept
+    let rebuild: bool =
+        file_filter.is_active() && processing_mode == ProcessingMode::Force && maps_trans_output_path.exists();
 
-                    Some((filename_str.to_string(), json))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        });
+    if rebuild {
+        processing_mode = &ProcessingMode::Append;
+    }
 
     let mut maps_lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
     let mut names_lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
@@ -585,7 +628,7 @@ pub fn read_map(
                 names_translation_map.insert(original.to_string(), translated.to_string());
             }
         } else {
-            println!("{file_is_not_parsed_msg}");
+            output.print_warning("translation_missing", file_is_not_parsed_msg);
             processing_mode = &ProcessingMode::Default;
         }
     }
@@ -595,6 +638,7 @@ pub fn read_map(
     // 356 - system lines (special texts)
     // 324, 320 - i don't know what is it but it's some used in-game lines
     const ALLOWED_CODES: [u16; 5] = [102, 320, 324, 356, 401];
+    let allowed_codes: Vec<u16> = filter_event_codes(&ALLOWED_CODES, event_codes);
 
     let (display_name_label, events_label, pages_label, list_label, code_label, parameters_label) =
         if engine_type == EngineType::New {
@@ -610,24 +654,14 @@ pub fn read_map(
             )
         };
 
-    for (filename, obj) in maps_obj_vec {
-        if let Some(display_name) = obj[display_name_label].as_str() {
-            if !display_name.is_empty() {
-                let mut display_name_string: String = display_name.to_string();
-
-                if romanize {
-                    display_name_string = romanize_string(display_name_string);
-                }
-
-                if processing_mode == ProcessingMode::Append
-                    && !names_translation_map.contains_key(&display_name_string)
-                {
-                    names_translation_map.shift_insert(names_lines.len(), display_name_string.clone(), String::new());
-                }
-
-                names_lines.insert(display_name_string);
-            }
-        }
+    let extract_map = |obj: &Value| -> (Option<String>, IndexSet<String, BuildHasherDefault<Xxh3>>) {
+        let display_name: Option<String> = obj[display_name_label]
+            .as_str()
+            .filter(|display_name: &&str| !display_name.is_empty())
+            .map(|display_name: &str| match romanization_table {
+                Some(table) => romanize_string(display_name, table),
+                None => display_name.to_string(),
+            });
 
         let events_arr: Vec<&Value> = if engine_type == EngineType::New {
             obj[events_label].as_array().unwrap().iter().skip(1).collect()
@@ -640,6 +674,8 @@ pub fn read_map(
                 .collect()
         };
 
+        let mut lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
+
         for event in events_arr.iter() {
             if !event[pages_label].is_array() {
                 continue;
@@ -648,19 +684,197 @@ pub fn read_map(
             for page in event[pages_label].as_array().unwrap().iter() {
                 parse_list(
                     page[list_label].as_array().unwrap(),
-                    &ALLOWED_CODES,
-                    romanize,
+                    &allowed_codes,
+                    romanization_table,
+                    protect_codes,
                     game_type,
                     engine_type,
-                    processing_mode,
+                    &ProcessingMode::Default,
                     (code_label, parameters_label),
-                    &mut maps_lines,
-                    &mut maps_translation_map,
+                    &mut lines,
+                    &mut IndexMap::default(),
                 );
             }
         }
 
-        if logging {
+        (display_name, lines)
+    };
+
+    // Unchanged maps aren't parsed again, so their lines are restored from the hashes, that were cached on the
+    // previous read, and new lines of other maps are still inserted at the right position
+    let restore_skipped: bool = cache.is_some() && processing_mode == ProcessingMode::Append && !rebuild;
+    let lines_by_hash: HashMap<u64, &str, BuildHasherDefault<Xxh3>> = maps_translation_map
+        .keys()
+        .filter(|_| restore_skipped)
+        .map(|line: &String| (xxh3_64_with_seed(line.as_bytes(), 0), line.as_str()))
+        .collect();
+    let names_by_hash: HashMap<u64, &str, BuildHasherDefault<Xxh3>> = names_translation_map
+        .keys()
+        .filter(|_| restore_skipped)
+        .map(|name: &String| (xxh3_64_with_seed(name.as_bytes(), NAMES_SEED), name.as_str()))
+        .collect();
+
+    // Lines of maps, which aren't selected, keep the position of new lines of the selected maps
+    let keep_unselected: bool = file_filter.is_active() && processing_mode == ProcessingMode::Append;
+
+    // Files are extracted in parallel, and then merged in the order of directory entries,
+    // so the output is identical to sequential reading
+    let maps_extracted: Vec<ExtractedMap> = read_dir(maps_path)
+        .unwrap()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter_map(|entry: Result<DirEntry, std::io::Error>| match entry {
+            Ok(entry) => {
+                let filename: OsString = entry.file_name();
+                let filename_str: &str = unsafe { from_utf8_unchecked(filename.as_encoded_bytes()) };
+
+                if filename_str.starts_with("Map")
+                    && unsafe { (*filename_str.as_bytes().get_unchecked(3) as char).is_ascii_digit() }
+                    && (filename_str.ends_with("json")
+                        || filename_str.ends_with("rvdata2")
+                        || filename_str.ends_with("rvdata")
+                        || filename_str.ends_with("rxdata"))
+                    && (keep_unselected || file_filter.matches(filename_str))
+                {
+                    let bytes: Vec<u8> = read(entry.path()).unwrap();
+
+                    // Maps, which aren't selected, are only parsed to know their lines, and nothing is extracted
+                    // from them
+                    if !file_filter.matches(filename_str) {
+                        let json: Value = if engine_type == EngineType::New {
+                            from_slice(&bytes).unwrap()
+                        } else {
+                            load(&bytes, None, Some("")).unwrap()
+                        };
+
+                        let (display_name, lines) = extract_map(&json);
+                        return Some((filename_str.to_string(), display_name, lines, true));
+                    }
+
+                    let source_hash: Option<u64> = cache.map(|cache: &Cache| cache.hash_source(&bytes));
+
+                    if let (Some(cache), Some(source_hash)) = (cache, source_hash) {
+                        // Entries of older versions have no keys, so their maps are parsed again
+                        let cached_keys: Option<Vec<u64>> = cache
+                            .get(&format!("read/{filename_str}"))
+                            .filter(|entry: &CacheEntry| {
+                                restore_skipped && entry.source == source_hash && !entry.keys.is_empty()
+                            })
+                            .map(|entry: CacheEntry| entry.keys);
+
+                        if let Some(cached_keys) = cached_keys {
+                            progress.advance();
+
+                            if output.is_json() {
+                                output.emit(Event::FileSkipped {
+                                    file: filename_str,
+                                    reason: SkipReason::Unchanged,
+                                });
+                            }
+
+                            let display_name: Option<String> = cached_keys
+                                .iter()
+                                .find_map(|key: &u64| names_by_hash.get(key))
+                                .map(|name: &&str| name.to_string());
+                            let lines: IndexSet<String, BuildHasherDefault<Xxh3>> = cached_keys
+                                .iter()
+                                .filter_map(|key: &u64| lines_by_hash.get(key))
+                                .map(|line: &&str| line.to_string())
+                                .collect();
+
+                            return Some((filename_str.to_string(), display_name, lines, true));
+                        }
+                    }
+
+                    if output.is_json() {
+                        output.emit(Event::FileStarted { file: filename_str });
+                    }
+
+                    let json: Value = if engine_type == EngineType::New {
+                        from_slice(&bytes).unwrap()
+                    } else {
+                        load(&bytes, None, Some("")).unwrap()
+                    };
+
+                    let (display_name, lines) = extract_map(&json);
+
+                    if let (Some(cache), Some(source_hash)) = (cache, source_hash) {
+                        let keys: Vec<u64> = display_name
+                            .iter()
+                            .map(|name: &String| xxh3_64_with_seed(name.as_bytes(), NAMES_SEED))
+                            .chain(lines.iter().map(|line: &String| xxh3_64_with_seed(line.as_bytes(), 0)))
+                            .collect();
+
+                        cache.insert(
+                            format!("read/{filename_str}"),
+                            CacheEntry {
+                                source: source_hash,
+                                translation: 0,
+                                keys,
+                            },
+                        );
+                    }
+
+                    progress.advance();
+                    Some((filename_str.to_string(), display_name, lines, false))
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        })
+        .collect();
+
+    if rebuild {
+        let unselected_lines: HashSet<&String, BuildHasherDefault<Xxh3>> = maps_extracted
+            .iter()
+            .filter(|(_, _, _, kept)| *kept)
+            .flat_map(|(_, _, lines, _)| lines.iter())
+            .collect();
+        let unselected_names: HashSet<&String, BuildHasherDefault<Xxh3>> = maps_extracted
+            .iter()
+            .filter(|(_, _, _, kept)| *kept)
+            .filter_map(|(_, display_name, _, _)| display_name.as_ref())
+            .collect();
+
+        maps_translation_map.retain(|line: &String, _| unselected_lines.contains(line));
+        names_translation_map.retain(|name: &String, _| unselected_names.contains(name));
+    }
+
+    for (filename, display_name, lines, kept) in maps_extracted {
+        // Lines of kept maps only take their place, so lines, which aren't in the translation, are never added
+        if kept {
+            if let Some(display_name) = display_name.filter(|name: &String| names_translation_map.contains_key(name)) {
+                names_lines.insert(display_name);
+            }
+
+            for line in lines {
+                if maps_translation_map.contains_key(&line) {
+                    maps_lines.insert(line);
+                }
+            }
+
+            continue;
+        }
+
+        if let Some(display_name) = display_name {
+            insert_line(
+                display_name,
+                processing_mode,
+                &mut names_lines,
+                &mut names_translation_map,
+            );
+        }
+
+        let count: usize = lines.len();
+
+        for line in lines {
+            insert_line(line, processing_mode, &mut maps_lines, &mut maps_translation_map);
+        }
+
+        if output.is_json() {
+            output.emit(Event::FileParsed { file: &filename, count });
+        } else if logging {
             println!("{file_parsed_msg} {filename}.");
         }
     }
@@ -695,58 +909,105 @@ pub fn read_map(
 /// # Parameters
 /// * `other_path` - path to directory than contains game files
 /// * `output_path` - path to output directory
-/// * `romanize` - whether to romanize text
+/// * `romanization_table` - table to romanize text with, or None, if text isn't romanized
+/// * `protect_codes` - whether to replace escape codes of event text with numbered placeholders
+/// * `event_codes` - codes of event commands, which text is extracted, or None, if text of all of them is
+/// * `note_tag_rules` - if not empty, only contents of these tags are extracted from notes
 /// * `logging` - whether to log
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_parsed_msg` - message to log when file is parsed
 /// * `file_already_parsed_msg` - message to log when file that's about to be parsed already exists (default processing mode)
 /// * `file_is_not_parsed_msg` - message to log when file that's about to be parsed not exist (append processing mode)
 /// * `game_type` - game type for custom parsing
 /// * `processing_mode` - whether to read in default mode, force rewrite or append new text to existing files
+/// * `file_filter` - selects files, which are processed
+/// * `cache` - if some, unchanged files are skipped in append mode
 pub fn read_other(
     other_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
+    note_tag_rules: &[NoteTagRule],
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_parsed_msg: &str,
     file_already_parsed_msg: &str,
     file_is_not_parsed_msg: &str,
     game_type: Option<&GameType>,
     processing_mode: &ProcessingMode,
     engine_type: &EngineType,
+    file_filter: &FileFilter,
+    cache: Option<&Cache>,
 ) {
-    let other_obj_arr_iter = read_dir(other_path)
-        .unwrap()
-        .filter_map(|entry: Result<DirEntry, std::io::Error>| match entry {
-            Ok(entry) => {
-                let filename_os_string: OsString = entry.file_name();
-                let filename: &str = unsafe { from_utf8_unchecked(filename_os_string.as_encoded_bytes()) };
-                let (real_name, extension) = filename.split_once('.').unwrap();
-
-                if !real_name.starts_with("Map")
-                    && !matches!(real_name, "Tilesets" | "Animations" | "System")
-                    && ["json", "rvdata2", "rvdata", "rxdata"].contains(&extension)
-                {
-                    if game_type.is_some_and(|game_type: &GameType| game_type == GameType::Termina)
-                        && real_name == "States"
+    let other_obj_arr_iter =
+        read_dir(other_path)
+            .unwrap()
+            .par_bridge()
+            .filter_map(|entry: Result<DirEntry, std::io::Error>| match entry {
+                Ok(entry) => {
+                    let filename_os_string: OsString = entry.file_name();
+                    let filename: &str = unsafe { from_utf8_unchecked(filename_os_string.as_encoded_bytes()) };
+                    let (real_name, extension) = filename.split_once('.').unwrap();
+
+                    if !real_name.starts_with("Map")
+                        && !matches!(real_name, "Tilesets" | "Animations" | "System")
+                        && ["json", "rvdata2", "rvdata", "rxdata"].contains(&extension)
+                        && file_filter.matches(filename)
                     {
-                        return None;
-                    }
+                        if game_type.is_some_and(|game_type: &GameType| game_type == GameType::Termina)
+                            && real_name == "States"
+                        {
+                            return None;
+                        }
 
-                    let json: Value = if engine_type == EngineType::New {
-                        from_str(&read_to_string(entry.path()).unwrap()).unwrap()
-                    } else {
-                        load(&read(entry.path()).unwrap(), None, Some("")).unwrap()
-                    };
+                        let bytes: Vec<u8> = read(entry.path()).unwrap();
 
-                    Some((filename.to_string(), json))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        });
+                        if let Some(cache) = cache {
+                            let key: String = format!("read/{filename}");
+                            let source_hash: u64 = cache.hash_source(&bytes);
+
+                            if processing_mode == ProcessingMode::Append
+                                && cache.is_unchanged(&key, source_hash, 0)
+                                && output_path.join(real_name.to_lowercase() + "_trans.txt").exists()
+                            {
+                                progress.advance();
 
-    let mut inner_processing_mode: &ProcessingMode = processing_mode;
+                                if output.is_json() {
+                                    output.emit(Event::FileSkipped {
+                                        file: filename,
+                                        reason: SkipReason::Unchanged,
+                                    });
+                                }
+
+                                return None;
+                            }
+
+                            cache.insert(
+                                key,
+                                CacheEntry {
+                                    source: source_hash,
+                                    translation: 0,
+                                    keys: Vec::new(),
+                                },
+                            );
+                        }
+
+                        let json: Value = if engine_type == EngineType::New {
+                            from_slice(&bytes).unwrap()
+                        } else {
+                            load(&bytes, None, Some("")).unwrap()
+                        };
+
+                        Some((filename.to_string(), json))
+                    } else {
+                        None
+                    }
+                }
+                Err(_) => None,
+            });
 
     // 401 - dialogue lines
     // 405 - credits lines
@@ -754,6 +1015,7 @@ pub fn read_other(
     // 356 - system lines (special texts)
     // 324, 320 - i don't know what is it but it's some used in-game lines
     const ALLOWED_CODES: [u16; 6] = [102, 320, 324, 356, 401, 405];
+    let allowed_codes: Vec<u16> = filter_event_codes(&ALLOWED_CODES, event_codes);
 
     let (
         name_label,
@@ -800,17 +1062,36 @@ pub fn read_other(
         )
     };
 
-    for (filename, obj_arr) in other_obj_arr_iter {
+    other_obj_arr_iter.into_par_iter().for_each(|(filename, obj_arr)| {
         let other_processed_filename: String = filename[0..filename.rfind('.').unwrap()].to_lowercase();
 
         let other_output_path: &Path = &output_path.join(other_processed_filename.clone() + ".txt");
         let other_trans_output_path: &Path = &output_path.join(other_processed_filename + "_trans.txt");
 
         if processing_mode == ProcessingMode::Default && other_trans_output_path.exists() {
-            println!("{} {file_already_parsed_msg}", other_trans_output_path.display());
-            continue;
+            progress.advance();
+
+            if output.is_json() {
+                output.emit(Event::FileSkipped {
+                    file: &other_trans_output_path.file_name().unwrap().to_string_lossy(),
+                    reason: SkipReason::Exists,
+                });
+            } else {
+                output.print_message(&format!(
+                    "{} {file_already_parsed_msg}",
+                    other_trans_output_path.display()
+                ));
+            }
+
+            return;
+        }
+
+        if output.is_json() {
+            output.emit(Event::FileStarted { file: &filename });
         }
 
+        let mut inner_processing_mode: &ProcessingMode = processing_mode;
+
         let mut other_lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
         let mut other_translation_map: IndexMap<String, String, BuildHasherDefault<Xxh3>> = IndexMap::default();
 
@@ -831,7 +1112,7 @@ pub fn read_other(
                     other_translation_map.insert(original.to_string(), translated.to_string());
                 }
             } else {
-                println!("{file_is_not_parsed_msg}");
+                output.print_warning("translation_missing", file_is_not_parsed_msg);
                 inner_processing_mode = &ProcessingMode::Default;
             }
         }
@@ -869,6 +1150,31 @@ pub fn read_other(
                         variable_str = variable_str.trim();
 
                         if !variable_str.is_empty() {
+                            // Only contents of translatable tags are extracted, each as a separate line
+                            if variable_type == Variable::Note && !note_tag_rules.is_empty() {
+                                for range in find_note_fragments(variable_str, note_tag_rules) {
+                                    let mut fragment: String = variable_str[range].to_string();
+
+                                    if let Some(table) = romanization_table {
+                                        fragment = romanize_string(fragment, table);
+                                    }
+
+                                    let replaced: String =
+                                        fragment.split('\n').map(str::trim).collect::<Vec<_>>().join(r"\#");
+
+                                    if !STRING_IS_ONLY_SYMBOLS_RE.is_match(&replaced) {
+                                        insert_line(
+                                            replaced,
+                                            inner_processing_mode,
+                                            &mut other_lines,
+                                            &mut other_translation_map,
+                                        );
+                                    }
+                                }
+
+                                continue;
+                            }
+
                             let parsed: Option<(String, bool)> = parse_variable(
                                 variable_str.to_string(),
                                 &variable_type,
@@ -898,8 +1204,8 @@ pub fn read_other(
 
                                 prev_variable_type = Some(variable_type);
 
-                                if romanize {
-                                    parsed = romanize_string(parsed);
+                                if let Some(table) = romanization_table {
+                                    parsed = romanize_string(parsed, table);
                                 }
 
                                 let replaced: String =
@@ -948,11 +1254,12 @@ pub fn read_other(
 
                     parse_list(
                         list.as_array().unwrap(),
-                        &ALLOWED_CODES,
-                        romanize,
+                        &allowed_codes,
+                        romanization_table,
+                        protect_codes,
                         game_type,
                         engine_type,
-                        processing_mode,
+                        inner_processing_mode,
                         (code_label, parameters_label),
                         &mut other_lines,
                         &mut other_translation_map,
@@ -961,7 +1268,7 @@ pub fn read_other(
             }
         }
 
-        let (original_content, translation_content) = if processing_mode == ProcessingMode::Append {
+        let (original_content, translation_content) = if inner_processing_mode == ProcessingMode::Append {
             let collected: (Vec<String>, Vec<String>) = other_translation_map.into_iter().unzip();
             (collected.0.join("\n"), collected.1.join("\n"))
         } else {
@@ -971,10 +1278,17 @@ pub fn read_other(
         write(other_output_path, original_content).unwrap();
         write(other_trans_output_path, translation_content).unwrap();
 
-        if logging {
+        progress.advance();
+
+        if output.is_json() {
+            output.emit(Event::FileParsed {
+                file: &filename,
+                count: other_lines.len(),
+            });
+        } else if logging {
             println!("{file_parsed_msg} {filename}");
         }
-    }
+    });
 }
 
 // ! In current implementation, function performs extremely inefficient inserting of owned string to both hashmap and a hashset
@@ -982,35 +1296,80 @@ pub fn read_other(
 /// # Parameters
 /// * `system_file_path` - path to directory than contains game files
 /// * `output_path` - path to output directory
-/// * `romanize` - whether to romanize text
+/// * `romanization_table` - table to romanize text with, or None, if text isn't romanized
 /// * `logging` - whether to log
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_parsed_msg` - message to log when file is parsed
 /// * `file_already_parsed_msg` - message to log when file that's about to be parsed already exists (default processing mode)
 /// * `file_is_not_parsed_msg` - message to log when file that's about to be parsed not exist (append processing mode)
 /// * `processing_mode` - whether to read in default mode, force rewrite or append new text to existing files
+/// * `cache` - if some, unchanged file is skipped in append mode
 pub fn read_system(
     system_file_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_parsed_msg: &str,
     file_already_parsed_msg: &str,
     file_is_not_parsed_msg: &str,
     mut processing_mode: &ProcessingMode,
     engine_type: &EngineType,
+    cache: Option<&Cache>,
 ) {
     let system_output_path: &Path = &output_path.join("system.txt");
     let system_trans_output_path: &Path = &output_path.join("system_trans.txt");
 
     if processing_mode == ProcessingMode::Default && system_trans_output_path.exists() {
-        println!("system_trans.txt {file_already_parsed_msg}");
+        if output.is_json() {
+            output.emit(Event::FileSkipped {
+                file: "system_trans.txt",
+                reason: SkipReason::Exists,
+            });
+        } else {
+            output.print_message(&format!("system_trans.txt {file_already_parsed_msg}"));
+        }
+
         return;
     }
 
+    let bytes: Vec<u8> = read(system_file_path).unwrap();
+
+    if let Some(cache) = cache {
+        let source_hash: u64 = cache.hash_source(&bytes);
+
+        if processing_mode == ProcessingMode::Append
+            && system_trans_output_path.exists()
+            && cache.is_unchanged("read/system", source_hash, 0)
+        {
+            progress.advance();
+
+            if output.is_json() {
+                output.emit(Event::FileSkipped {
+                    file: &system_file_path.file_name().unwrap().to_string_lossy(),
+                    reason: SkipReason::Unchanged,
+                });
+            }
+
+            return;
+        }
+
+        cache.insert(
+            String::from("read/system"),
+            CacheEntry {
+                source: source_hash,
+                translation: 0,
+                keys: Vec::new(),
+            },
+        );
+    }
+
     let system_obj: Value = if engine_type == EngineType::New {
-        from_str(&read_to_string(system_file_path).unwrap()).unwrap()
+        from_slice(&bytes).unwrap()
     } else {
-        load(&read(system_file_path).unwrap(), None, Some("")).unwrap()
+        load(&bytes, None, Some("")).unwrap()
     };
 
     let mut system_lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
@@ -1033,7 +1392,7 @@ pub fn read_system(
                 system_translation_map.insert(original.to_string(), translated.to_string());
             }
         } else {
-            println!("{file_is_not_parsed_msg}");
+            output.print_warning("translation_missing", file_is_not_parsed_msg);
             processing_mode = &ProcessingMode::Default;
         }
     }
@@ -1044,8 +1403,8 @@ pub fn read_system(
         if !str.is_empty() {
             let mut string: String = str.to_string();
 
-            if romanize {
-                string = romanize_string(string)
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table)
             }
 
             if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1089,8 +1448,8 @@ pub fn read_system(
         if !str.is_empty() {
             let mut string: String = str.to_string();
 
-            if romanize {
-                string = romanize_string(string)
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table)
             }
 
             if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1109,8 +1468,8 @@ pub fn read_system(
         if !str.is_empty() {
             let mut string: String = str.to_string();
 
-            if romanize {
-                string = romanize_string(string)
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table)
             }
 
             if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1129,8 +1488,8 @@ pub fn read_system(
             if !str.is_empty() {
                 let mut string: String = str.to_string();
 
-                if romanize {
-                    string = romanize_string(string)
+                if let Some(table) = romanization_table {
+                    string = romanize_string(string, table)
                 }
 
                 if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1149,8 +1508,8 @@ pub fn read_system(
         if !str.is_empty() {
             let mut string: String = str.to_string();
 
-            if romanize {
-                string = romanize_string(string)
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table)
             }
 
             if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1175,8 +1534,8 @@ pub fn read_system(
                     if !str.is_empty() {
                         let mut string: String = str.to_string();
 
-                        if romanize {
-                            string = romanize_string(string)
+                        if let Some(table) = romanization_table {
+                            string = romanize_string(string, table)
                         }
 
                         if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1198,8 +1557,8 @@ pub fn read_system(
                 if !str.is_empty() {
                     let mut string: String = str.to_string();
 
-                    if romanize {
-                        string = romanize_string(string)
+                    if let Some(table) = romanization_table {
+                        string = romanize_string(string, table)
                     }
 
                     if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1220,8 +1579,8 @@ pub fn read_system(
         if !str.is_empty() {
             let mut string: String = str.to_string();
 
-            if romanize {
-                string = romanize_string(string)
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table)
             }
 
             if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&string) {
@@ -1237,8 +1596,8 @@ pub fn read_system(
     {
         let mut game_title_string: String = system_obj[game_title_label].as_str().unwrap().trim().to_string();
 
-        if romanize {
-            game_title_string = romanize_string(game_title_string)
+        if let Some(table) = romanization_table {
+            game_title_string = romanize_string(game_title_string, table)
         }
 
         if processing_mode == ProcessingMode::Append && !system_translation_map.contains_key(&game_title_string) {
@@ -1261,12 +1620,27 @@ pub fn read_system(
     write(system_output_path, original_content).unwrap();
     write(system_trans_output_path, translated_content).unwrap();
 
-    if logging {
+    progress.advance();
+
+    if output.is_json() {
+        output.emit(Event::FileParsed {
+            file: &system_file_path.file_name().unwrap().to_string_lossy(),
+            count: system_lines.len(),
+        });
+    } else if logging {
         println!("{file_parsed_msg} {}.", system_file_path.display());
     }
 }
 
-pub fn read_scripts(scripts_file_path: &Path, other_path: &Path, romanize: bool, logging: bool, file_parsed_msg: &str) {
+pub fn read_scripts(
+    scripts_file_path: &Path,
+    other_path: &Path,
+    romanization_table: Option<&RomanizationTable>,
+    logging: bool,
+    output: Output,
+    progress: &Progress,
+    file_parsed_msg: &str,
+) {
     let mut strings: Vec<String> = Vec::new();
 
     let scripts_entries: Value = load(&read(scripts_file_path).unwrap(), Some(StringMode::Binary), None).unwrap();
@@ -1279,28 +1653,34 @@ pub fn read_scripts(scripts_file_path: &Path, other_path: &Path, romanize: bool,
         encoding_rs::GB18030,
     ];
 
-    let mut codes_content: Vec<String> = Vec::with_capacity(256);
-
-    for code in scripts_entries.as_array().unwrap() {
-        let bytes_stream: Vec<u8> = from_value(&code[2]["data"]).unwrap();
+    let codes_content: Vec<String> = scripts_entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .collect::<Vec<&Value>>()
+        .into_par_iter()
+        .map(|code: &Value| {
+            let bytes_stream: Vec<u8> = from_value(&code[2]["data"]).unwrap();
 
-        let mut inflated: Vec<u8> = Vec::new();
-        ZlibDecoder::new(&*bytes_stream).read_to_end(&mut inflated).unwrap();
+            let mut inflated: Vec<u8> = Vec::new();
+            ZlibDecoder::new(&*bytes_stream).read_to_end(&mut inflated).unwrap();
 
-        let mut code_string: String = String::with_capacity(16_777_216);
+            let mut code_string: String = String::with_capacity(16_777_216);
 
-        for encoding in encodings {
-            let (result, _, had_errors) = encoding
-                .new_decoder()
-                .decode_to_string(&inflated, &mut code_string, true);
+            for encoding in encodings {
+                let (result, _, had_errors) =
+                    encoding
+                        .new_decoder()
+                        .decode_to_string(&inflated, &mut code_string, true);
 
-            if result == CoderResult::InputEmpty && !had_errors {
-                break;
+                if result == CoderResult::InputEmpty && !had_errors {
+                    break;
+                }
             }
-        }
 
-        codes_content.push(code_string);
-    }
+            code_string
+        })
+        .collect();
 
     let extracted_strings: IndexSet<String> = extract_strings(&codes_content.join(""), false).0;
 
@@ -1329,14 +1709,21 @@ pub fn read_scripts(scripts_file_path: &Path, other_path: &Path, romanize: bool,
             }
         }
 
-        if romanize {
-            extracted = romanize_string(extracted);
+        if let Some(table) = romanization_table {
+            extracted = romanize_string(extracted, table);
         }
 
         strings.push(extracted);
     }
 
-    if logging {
+    progress.advance();
+
+    if output.is_json() {
+        output.emit(Event::FileParsed {
+            file: &scripts_file_path.file_name().unwrap().to_string_lossy(),
+            count: strings.len(),
+        });
+    } else if logging {
         println!("{file_parsed_msg} {}", scripts_file_path.display());
     }
 
@@ -1350,3 +1737,51 @@ pub fn read_scripts(scripts_file_path: &Path, other_path: &Path, romanize: bool,
 
 // read_plugins is not implemented and will NEVER be, as plugins can differ from each other incredibly.
 // Change plugins.js with your own hands.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sonic_rs::from_str;
+
+    #[test]
+    fn append_keeps_translation_of_protected_dialogue() {
+        let list: Value = from_str(
+            r#"[
+                {"code": 101, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "parameters": ["Hello, \\C[2]hero\\C[0]!"]},
+                {"code": 401, "parameters": ["{0} new line"]},
+                {"code": 0, "parameters": []}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut set: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
+        let mut map: IndexMap<String, String, BuildHasherDefault<Xxh3>> = IndexMap::default();
+        map.insert(
+            String::from(r"Hello, {1}hero{2}!\#{{0}} new line"),
+            String::from(r"Привет, {1}герой{2}!\#{{0}} новая строка"),
+        );
+        map.insert(String::from("Other line"), String::from("Другая строка"));
+
+        parse_list(
+            list.as_array().unwrap(),
+            &[401],
+            None,
+            true,
+            None,
+            &EngineType::New,
+            &ProcessingMode::Append,
+            ("code", "parameters"),
+            &mut set,
+            &mut map,
+        );
+
+        assert_eq!(set.len(), 1);
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map[r"Hello, {1}hero{2}!\#{{0}} new line"],
+            r"Привет, {1}герой{2}!\#{{0}} новая строка"
+        );
+        assert_eq!(map["Other line"], "Другая строка");
+    }
+}
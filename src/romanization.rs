@@ -0,0 +1,190 @@
+use indexmap::IndexMap;
+use sonic_rs::{prelude::*, Object, Value};
+use std::hash::BuildHasherDefault;
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+/// Table, that maps characters to their romanized replacements.
+pub type RomanizationTable = IndexMap<char, String, BuildHasherDefault<Xxh3>>;
+
+/// Source languages, which have built-in romanization tables.
+pub const PRESETS: [&str; 3] = ["ja", "zh", "ko"];
+
+/// Each entry maps all characters of the first string to the second string.
+const JAPANESE: &[(&str, &str)] = &[
+    ("。", "."),
+    ("、，", ","),
+    ("・", "·"),
+    ("゠", "–"),
+    ("＝ー", "—"),
+    ("「」〈〉", "'"),
+    ("『』《》", "\""),
+    ("（〔｟〘", "("),
+    ("）〕｠〙", ")"),
+    ("｛", "{"),
+    ("｝", "}"),
+    ("［【〖〚", "["),
+    ("］】〗〛", "]"),
+    ("〜", "~"),
+    ("？", "?"),
+    ("！", "!"),
+    ("：", ":"),
+    ("※", "·"),
+    ("…‥", "..."),
+    ("　", " "),
+    ("Ⅰ", "I"),
+    ("ⅰ", "i"),
+    ("Ⅱ", "II"),
+    ("ⅱ", "ii"),
+    ("Ⅲ", "III"),
+    ("ⅲ", "iii"),
+    ("Ⅳ", "IV"),
+    ("ⅳ", "iv"),
+    ("Ⅴ", "V"),
+    ("ⅴ", "v"),
+    ("Ⅵ", "VI"),
+    ("ⅵ", "vi"),
+    ("Ⅶ", "VII"),
+    ("ⅶ", "vii"),
+    ("Ⅷ", "VIII"),
+    ("ⅷ", "viii"),
+    ("Ⅸ", "IX"),
+    ("ⅸ", "ix"),
+    ("Ⅹ", "X"),
+    ("ⅹ", "x"),
+    ("Ⅺ", "XI"),
+    ("ⅺ", "xi"),
+    ("Ⅻ", "XII"),
+    ("ⅻ", "xii"),
+    ("Ⅼ", "L"),
+    ("ⅼ", "l"),
+    ("Ⅽ", "C"),
+    ("ⅽ", "c"),
+    ("Ⅾ", "D"),
+    ("ⅾ", "d"),
+    ("Ⅿ", "M"),
+    ("ⅿ", "m"),
+];
+
+const CHINESE: &[(&str, &str)] = &[
+    ("。．", "."),
+    ("，、", ","),
+    ("；", ";"),
+    ("：", ":"),
+    ("？", "?"),
+    ("！", "!"),
+    ("“”「」", "\""),
+    ("‘’『』", "'"),
+    ("《》〈〉", "\""),
+    ("（", "("),
+    ("）", ")"),
+    ("【〔［", "["),
+    ("】〕］", "]"),
+    ("｛", "{"),
+    ("｝", "}"),
+    ("～〜", "~"),
+    ("…", "..."),
+    ("·・", "·"),
+    ("　", " "),
+];
+
+const KOREAN: &[(&str, &str)] = &[
+    ("。．", "."),
+    ("、，", ","),
+    ("；", ";"),
+    ("：", ":"),
+    ("？", "?"),
+    ("！", "!"),
+    ("「」『』“”", "\""),
+    ("‘’〈〉", "'"),
+    ("《》", "\""),
+    ("（", "("),
+    ("）", ")"),
+    ("【［", "["),
+    ("】］", "]"),
+    ("～〜", "~"),
+    ("…‥", "..."),
+    ("・", "·"),
+    ("　", " "),
+];
+
+/// Returns the built-in romanization table of the source language.
+/// # Parameters
+/// * `language` - one of the `PRESETS`
+pub fn get_preset(language: &str) -> RomanizationTable {
+    let entries: &[(&str, &str)] = match language {
+        "zh" => CHINESE,
+        "ko" => KOREAN,
+        _ => JAPANESE,
+    };
+
+    entries
+        .iter()
+        .flat_map(|(chars, replacement)| chars.chars().map(|char: char| (char, replacement.to_string())))
+        .collect()
+}
+
+/// Parses romanization table from a file, which contains `<character>=<replacement>` lines. Empty lines are
+/// skipped. Returns number of the first invalid line, if any.
+pub fn parse_table(content: &str) -> Result<RomanizationTable, usize> {
+    let mut table: RomanizationTable = RomanizationTable::default();
+
+    for (i, line) in content.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut chars = line.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(char), Some('=')) => {
+                table.insert(char, chars.collect());
+            }
+            _ => return Err(i + 1),
+        }
+    }
+
+    Ok(table)
+}
+
+/// Converts table to an object, so it can be stored in the metadata.
+pub fn table_to_object(table: &RomanizationTable) -> Object {
+    let mut obj: Object = Object::new();
+
+    for (char, replacement) in table.iter() {
+        obj.insert(&char.to_string(), replacement.as_str());
+    }
+
+    obj
+}
+
+/// Restores table from the object of the metadata.
+pub fn table_from_object(obj: &Value) -> Option<RomanizationTable> {
+    obj.as_object()?
+        .iter()
+        .map(|(key, replacement)| {
+            let mut chars = key.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(char), None) => Some((char, replacement.as_str()?.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Hashes the table, so changing it invalidates the cache. Order of entries doesn't affect the hash.
+pub fn hash_table(table: &RomanizationTable) -> u64 {
+    let mut entries: Vec<(&char, &String)> = table.iter().collect();
+    entries.sort();
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for (char, replacement) in entries {
+        bytes.extend(char.to_string().as_bytes());
+        bytes.push(0);
+        bytes.extend(replacement.as_bytes());
+        bytes.push(0);
+    }
+
+    xxh3_64(&bytes)
+}
@@ -0,0 +1,611 @@
+use crate::{
+    events::Output, file_filter::FileFilter, fonts::GlyphIssue, glossary::GlossaryIssue, migrate::read_lines,
+    sources::Sources, tui::PAIR_SUBDIRS, watch::Writer, ProcessingMode,
+};
+use indexmap::IndexMap;
+use sonic_rs::{from_str, prelude::*, to_string, to_value, Array, Object, Value};
+use std::{
+    any::Any,
+    fs::{metadata, read_dir, write},
+    io::{self, stdin, stdout, BufRead, BufReader, Read, Take, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+// Error codes, that are defined by the JSON-RPC 2.0 specification
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Error code of the operations, which failed with the program error, like the missing translation.
+const OPERATION_FAILED: i64 = -32000;
+
+/// Maximal size of the request line and headers of HTTP requests.
+const MAX_HEADERS_SIZE: u64 = 64 * 1024;
+/// Maximal size of the body of HTTP requests, so a wrong Content-Length can't exhaust the memory.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+/// Maximal time of waiting for the data of HTTP requests, so a client, that stalls, can't block the server, which
+/// handles connections one by one.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the server receives requests from.
+pub enum Transport {
+    /// Requests are read from stdin and responses are written to stdout, a single JSON per line
+    Stdio,
+    /// Requests are received as HTTP POST bodies on 127.0.0.1 and the port, or on any free port, if it's 0
+    Http(u16),
+}
+
+/// Results of the glossary and glyph checks, or none, if glossary or fonts don't exist.
+pub type CheckResult = (Option<Vec<GlossaryIssue>>, Option<Vec<GlyphIssue>>);
+
+/// Translation pair, which lines are held in memory between requests.
+struct TranslationPair {
+    translation_path: PathBuf,
+    /// Modification times of the files, which were loaded, so pairs, edited by other programs, are loaded again
+    modified: (Option<SystemTime>, Option<SystemTime>),
+    originals: Vec<String>,
+    translations: Vec<String>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Returns the message of the panic, which is localized, if it was raised by the program.
+fn get_panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(String::new, |message: &&str| message.to_string()),
+    }
+}
+
+/// Runs the operation, and turns its panic into the error, so the server keeps running.
+fn run_operation<T>(operation: impl FnOnce() -> T) -> Result<T, RpcError> {
+    catch_unwind(AssertUnwindSafe(operation)).map_err(|payload| RpcError {
+        code: OPERATION_FAILED,
+        message: get_panic_message(payload),
+    })
+}
+
+fn get_str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(|value| value.as_str())
+        .ok_or_else(|| RpcError::invalid_params(format!("\"{name}\" must be a string")))
+}
+
+struct Server<'a, R, W, C> {
+    translation_path: &'a Path,
+    /// Filter of the command line, which requests can only narrow
+    file_filter: &'a FileFilter,
+    /// Parsed original data files, which are dropped when the game is read again
+    sources: &'a Sources,
+    pairs: IndexMap<String, TranslationPair>,
+    run_readers: R,
+    run_writers: W,
+    run_checks: C,
+}
+
+impl<R, W, C> Server<'_, R, W, C>
+where
+    R: FnMut(&ProcessingMode),
+    W: FnMut(&[Writer], &FileFilter),
+    C: FnMut() -> CheckResult,
+{
+    /// Lists translation pairs again, and loads the ones, which are new or were changed since they were loaded.
+    fn refresh_pairs(&mut self) {
+        let mut pairs: IndexMap<String, TranslationPair> = IndexMap::new();
+
+        for subdir in PAIR_SUBDIRS {
+            let subdir_path: PathBuf = self.translation_path.join(subdir);
+
+            let Ok(entries) = read_dir(&subdir_path) else {
+                continue;
+            };
+
+            let mut names: Vec<String> = entries
+                .flatten()
+                .filter_map(|entry| {
+                    let filename: String = entry.file_name().into_string().ok()?;
+                    let name: &str = filename.strip_suffix(".txt")?;
+                    (!name.ends_with("_trans")).then(|| name.to_string())
+                })
+                .collect();
+            names.sort();
+
+            for name in names {
+                let original_path: PathBuf = subdir_path.join(format!("{name}.txt"));
+                let translation_path: PathBuf = subdir_path.join(format!("{name}_trans.txt"));
+
+                if pairs.contains_key(&name) || !translation_path.exists() {
+                    continue;
+                }
+
+                let modified: (Option<SystemTime>, Option<SystemTime>) =
+                    (get_modified(&original_path), get_modified(&translation_path));
+
+                let pair: TranslationPair = match self.pairs.swap_remove(&name) {
+                    Some(pair) if pair.modified == modified => pair,
+                    _ => TranslationPair {
+                        originals: read_lines(&original_path),
+                        translations: read_lines(&translation_path),
+                        translation_path,
+                        modified,
+                    },
+                };
+
+                pairs.insert(name, pair);
+            }
+        }
+
+        self.pairs = pairs;
+    }
+
+    fn read(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let processing_mode: ProcessingMode = match params.get("mode").map(|value| value.as_str()) {
+            None | Some(Some("default")) => ProcessingMode::Default,
+            Some(Some("append")) => ProcessingMode::Append,
+            Some(Some("force")) => ProcessingMode::Force,
+            _ => {
+                return Err(RpcError::invalid_params(
+                    r#""mode" must be "default", "append" or "force""#,
+                ))
+            }
+        };
+
+        let start_time: Instant = Instant::now();
+        let result: Result<(), RpcError> = run_operation(|| (self.run_readers)(&processing_mode));
+
+        // Parsed sources may be outdated, if originals were replaced before reading them again
+        self.sources.clear();
+        result?;
+        self.refresh_pairs();
+
+        let mut result: Object = Object::new();
+        result.insert("files", self.pairs.len());
+        result.insert("duration", to_value(&start_time.elapsed().as_secs_f64()).unwrap());
+        Ok(result.into())
+    }
+
+    fn write(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let files: Option<Vec<String>> = match params.get("files") {
+            None => None,
+            Some(files) => Some(
+                files
+                    .as_array()
+                    .and_then(|files| {
+                        files
+                            .iter()
+                            .map(|file: &Value| file.as_str().map(str::to_string))
+                            .collect::<Option<Vec<String>>>()
+                    })
+                    .ok_or_else(|| RpcError::invalid_params(r#""files" must be an array of file names"#))?,
+            ),
+        };
+
+        let mut writers: Vec<Writer> = match params.get("phases") {
+            None => Writer::ALL.to_vec(),
+            Some(phases) => {
+                let phases_error = || {
+                    RpcError::invalid_params(
+                        r#""phases" must contain "maps", "other", "system", "plugins" or "scripts""#,
+                    )
+                };
+                let mut writers: Vec<Writer> = Vec::new();
+
+                for phase in phases.as_array().ok_or_else(phases_error)?.iter() {
+                    writers.push(match phase.as_str() {
+                        Some("maps") => Writer::Maps,
+                        Some("other") => Writer::Other,
+                        Some("system") => Writer::System,
+                        Some("plugins") => Writer::Plugins,
+                        Some("scripts") => Writer::Scripts,
+                        _ => return Err(phases_error()),
+                    });
+                }
+
+                // Keep the same order as in regular write
+                Writer::ALL
+                    .into_iter()
+                    .filter(|writer: &Writer| writers.contains(writer))
+                    .collect()
+            }
+        };
+
+        let file_filter: FileFilter = match &files {
+            Some(files) => self.file_filter.with_requested(files),
+            None => self.file_filter.clone(),
+        };
+
+        // Same files select these writers, as with --only
+        writers.retain(|writer: &Writer| match writer {
+            Writer::System => file_filter.matches("System"),
+            Writer::Plugins => file_filter.matches("plugins"),
+            Writer::Scripts => file_filter.matches("Scripts"),
+            Writer::Maps | Writer::Other => true,
+        });
+
+        let start_time: Instant = Instant::now();
+        run_operation(|| (self.run_writers)(&writers, &file_filter))?;
+
+        let mut result: Object = Object::new();
+        result.insert("duration", to_value(&start_time.elapsed().as_secs_f64()).unwrap());
+        Ok(result.into())
+    }
+
+    fn stats(&mut self) -> Value {
+        self.refresh_pairs();
+
+        let mut files: Array = Array::new();
+        let (mut total_lines, mut total_translated): (usize, usize) = (0, 0);
+
+        for (name, pair) in self.pairs.iter() {
+            let (mut lines, mut translated): (usize, usize) = (0, 0);
+
+            for (original, translation) in pair.originals.iter().zip(pair.translations.iter()) {
+                if original.trim().is_empty() {
+                    continue;
+                }
+
+                lines += 1;
+
+                if !translation.trim().is_empty() {
+                    translated += 1;
+                }
+            }
+
+            let mut file: Object = Object::new();
+            file.insert("file", name.as_str());
+            file.insert("lines", lines);
+            file.insert("translated", translated);
+            files.push(file);
+
+            total_lines += lines;
+            total_translated += translated;
+        }
+
+        let mut result: Object = Object::new();
+        result.insert("files", files);
+        result.insert("lines", total_lines);
+        result.insert("translated", total_translated);
+        result.into()
+    }
+
+    fn check(&mut self) -> Result<Value, RpcError> {
+        let (glossary_issues, glyph_issues): CheckResult = run_operation(|| (self.run_checks)())?;
+
+        let mut result: Object = Object::new();
+
+        result.insert(
+            "glossary",
+            glossary_issues.map_or_else(Value::new, |issues: Vec<GlossaryIssue>| {
+                let mut array: Array = Array::new();
+
+                for issue in issues {
+                    let mut obj: Object = Object::new();
+                    obj.insert("file", issue.filename.as_str());
+                    obj.insert("line", issue.line);
+                    obj.insert("term", issue.term.as_str());
+                    obj.insert("rendering", issue.rendering.as_str());
+                    array.push(obj);
+                }
+
+                array.into()
+            }),
+        );
+
+        result.insert(
+            "glyphs",
+            glyph_issues.map_or_else(Value::new, |issues: Vec<GlyphIssue>| {
+                let mut array: Array = Array::new();
+
+                for issue in issues {
+                    let mut obj: Object = Object::new();
+                    obj.insert("file", issue.filename.as_str());
+                    obj.insert("line", issue.line);
+                    obj.insert("chars", issue.chars.iter().collect::<String>().as_str());
+                    array.push(obj);
+                }
+
+                array.into()
+            }),
+        );
+
+        Ok(result.into())
+    }
+
+    fn lookup_by_original(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let original: &str = get_str_param(params, "original")?;
+        let file: Option<&str> = params.get("file").and_then(|value| value.as_str());
+
+        self.refresh_pairs();
+
+        let mut matches: Array = Array::new();
+
+        for (name, pair) in self.pairs.iter() {
+            if file.is_some_and(|file: &str| file != name) {
+                continue;
+            }
+
+            for (i, (pair_original, translation)) in pair.originals.iter().zip(pair.translations.iter()).enumerate() {
+                if pair_original == original {
+                    let mut obj: Object = Object::new();
+                    obj.insert("file", name.as_str());
+                    obj.insert("line", i + 1);
+                    obj.insert("original", pair_original.as_str());
+                    obj.insert("translation", translation.as_str());
+                    matches.push(obj);
+                }
+            }
+        }
+
+        Ok(matches.into())
+    }
+
+    fn update_translation(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let file: &str = get_str_param(params, "file")?;
+        let translation: &str = get_str_param(params, "translation")?;
+        let line: usize = params
+            .get("line")
+            .and_then(|value| value.as_u64())
+            .filter(|line: &u64| *line > 0)
+            .ok_or_else(|| RpcError::invalid_params(r#""line" must be a positive number"#))?
+            as usize;
+
+        // Line breaks of the entry are stored as \#, so real ones would shift all following lines
+        if translation.contains('\n') {
+            return Err(RpcError::invalid_params(
+                r#""translation" mustn't contain line breaks, \# must be used instead"#,
+            ));
+        }
+
+        self.refresh_pairs();
+
+        let pair: &mut TranslationPair = self
+            .pairs
+            .get_mut(file)
+            .ok_or_else(|| RpcError::invalid_params(format!("translation pair \"{file}\" doesn't exist")))?;
+
+        if pair.originals.len() != pair.translations.len() {
+            return Err(RpcError::invalid_params(format!(
+                "translation pair \"{file}\" has different line counts"
+            )));
+        }
+
+        if line > pair.translations.len() {
+            return Err(RpcError::invalid_params(format!(
+                "translation pair \"{file}\" has only {} lines",
+                pair.translations.len()
+            )));
+        }
+
+        let previous: String = std::mem::replace(&mut pair.translations[line - 1], translation.to_string());
+
+        run_operation(|| write(&pair.translation_path, pair.translations.join("\n")).unwrap())?;
+        pair.modified.1 = get_modified(&pair.translation_path);
+
+        let mut result: Object = Object::new();
+        result.insert("previous", previous.as_str());
+        Ok(result.into())
+    }
+
+    fn handle_method(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "read" => self.read(params),
+            "write" => self.write(params),
+            "stats" => Ok(self.stats()),
+            "check" => self.check(),
+            "lookup_by_original" => self.lookup_by_original(params),
+            "update_translation" => self.update_translation(params),
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("method \"{method}\" doesn't exist"),
+            }),
+        }
+    }
+
+    /// Handles a single JSON-RPC request. Returns the response, or none, if the request is a notification.
+    fn handle_request(&mut self, request: &str) -> Option<String> {
+        let (id, result): (Value, Result<Value, RpcError>) = match from_str::<Value>(request) {
+            Err(err) => (
+                Value::new(),
+                Err(RpcError {
+                    code: PARSE_ERROR,
+                    message: err.to_string(),
+                }),
+            ),
+            Ok(request) => {
+                let id: Option<Value> = request.get("id").cloned();
+
+                let result: Result<Value, RpcError> = match (
+                    request.get("jsonrpc").and_then(|value| value.as_str()),
+                    request.get("method").and_then(|value| value.as_str()),
+                ) {
+                    (Some("2.0"), Some(method)) => {
+                        let params: Value = request.get("params").cloned().unwrap_or_default();
+                        self.handle_method(method, &params)
+                    }
+                    _ => Err(RpcError {
+                        code: INVALID_REQUEST,
+                        message: String::from(r#"request must be an object with "jsonrpc": "2.0" and "method""#),
+                    }),
+                };
+
+                // Notifications have no id, and aren't responded to
+                (id?, result)
+            }
+        };
+
+        let mut response: Object = Object::new();
+        response.insert("jsonrpc", "2.0");
+        response.insert("id", id);
+
+        match result {
+            Ok(result) => response.insert("result", result),
+            Err(err) => {
+                let mut error: Object = Object::new();
+                error.insert("code", err.code);
+                error.insert("message", err.message.as_str());
+                response.insert("error", error)
+            }
+        };
+
+        Some(to_string(&response).unwrap())
+    }
+}
+
+/// Reads the HTTP request from the connection and writes the response. Connections are closed after a single request.
+fn handle_connection(stream: TcpStream, handle_request: &mut impl FnMut(&str) -> Option<String>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader: BufReader<&TcpStream> = BufReader::new(&stream);
+    let mut headers_reader: Take<&mut BufReader<&TcpStream>> = (&mut reader).take(MAX_HEADERS_SIZE);
+
+    let mut request_line: String = String::new();
+    headers_reader.read_line(&mut request_line)?;
+
+    // None, if the header isn't a valid number
+    let mut content_length: Option<usize> = Some(0);
+    let mut is_json: bool = false;
+
+    loop {
+        let mut header: String = String::new();
+
+        if headers_reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok();
+            } else if name.eq_ignore_ascii_case("content-type") {
+                is_json = value.trim().starts_with("application/json");
+            }
+        }
+    }
+
+    // Browsers can't send JSON to other origins without a preflight request, which isn't answered, so web pages
+    // can't drive the server
+    let (status, body): (&str, String) = if !request_line.starts_with("POST ") {
+        ("405 Method Not Allowed", String::new())
+    } else if !is_json {
+        ("415 Unsupported Media Type", String::new())
+    } else {
+        match content_length {
+            None => ("400 Bad Request", String::new()),
+            Some(content_length) if content_length > MAX_BODY_SIZE => ("413 Payload Too Large", String::new()),
+            Some(content_length) => {
+                let mut request: Vec<u8> = vec![0; content_length];
+                reader.read_exact(&mut request)?;
+
+                match handle_request(&String::from_utf8_lossy(&request)) {
+                    Some(response) => ("200 OK", response),
+                    None => ("204 No Content", String::new()),
+                }
+            }
+        }
+    };
+
+    write!(
+        &stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Serves JSON-RPC 2.0 requests, holding translation pairs in memory between them. Requests are handled one at a
+/// time, and panics of operations are returned as errors, so the server keeps running.
+///
+/// Methods:
+/// * `read` - reads the game, `mode` is `default`, `append` or `force`
+/// * `write` - writes the game, `phases` optionally limits writers to `maps`, `other`, `system`, `plugins` or
+///   `scripts`, and `files` optionally limits written files to the ones, which names match the patterns, like
+///   `["Map012"]`. Files, which source and translation are unchanged since the previous write, are skipped, and
+///   parsed originals are kept in memory until the next `read`.
+/// * `stats` - number of lines and translated lines of each translation pair
+/// * `check` - issues of glossary and glyph checks
+/// * `lookup_by_original` - lines, which original is `original`, optionally only of the `file` pair
+/// * `update_translation` - replaces translation of the 1-based `line` of the `file` pair with `translation`
+/// # Parameters
+/// * `translation_path` - path to the translation directory
+/// * `transport` - where requests are received from
+/// * `file_filter` - selects files, which are written
+/// * `sources` - parsed original data files, which are kept between writes
+/// * `listening_msg` - message to print when the server starts
+/// * `output` - where and how events and messages are printed
+/// * `run_readers` - function that reads the game with the processing mode
+/// * `run_writers` - function that runs the passed writers on files, which the filter selects
+/// * `run_checks` - function that runs glossary and glyph checks
+#[allow(clippy::too_many_arguments)]
+pub fn serve(
+    translation_path: &Path,
+    transport: Transport,
+    file_filter: &FileFilter,
+    sources: &Sources,
+    listening_msg: &str,
+    output: Output,
+    run_readers: impl FnMut(&ProcessingMode),
+    run_writers: impl FnMut(&[Writer], &FileFilter),
+    run_checks: impl FnMut() -> CheckResult,
+) {
+    let mut server = Server {
+        translation_path,
+        file_filter,
+        sources,
+        pairs: IndexMap::new(),
+        run_readers,
+        run_writers,
+        run_checks,
+    };
+    server.refresh_pairs();
+
+    let mut handle_request = |request: &str| server.handle_request(request);
+
+    match transport {
+        Transport::Stdio => {
+            output.print_message(&format!("{listening_msg} stdio"));
+
+            for request in stdin().lock().lines().map_while(Result::ok) {
+                if request.trim().is_empty() {
+                    continue;
+                }
+
+                if let Some(response) = handle_request(&request) {
+                    let mut stdout = stdout().lock();
+                    writeln!(stdout, "{response}").unwrap();
+                    stdout.flush().unwrap();
+                }
+            }
+        }
+        Transport::Http(port) => {
+            let listener: TcpListener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).unwrap();
+            let address: SocketAddr = listener.local_addr().unwrap();
+
+            output.print_message(&format!("{listening_msg} http://{address}"));
+
+            for stream in listener.incoming().flatten() {
+                // Failed connection doesn't affect other ones
+                let _ = handle_connection(stream, &mut handle_request);
+            }
+        }
+    }
+}
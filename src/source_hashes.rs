@@ -0,0 +1,92 @@
+use sonic_rs::{prelude::*, Object, Value};
+use std::{
+    ffi::OsString,
+    fs::{read, read_dir},
+    path::Path,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Extensions of data files, which readers extract text from.
+const DATA_EXTENSIONS: [&str; 4] = ["json", "rvdata2", "rvdata", "rxdata"];
+
+/// Data files, which contain no text, so their changes don't affect the translation.
+const TEXTLESS_FILES: [&str; 3] = ["Tilesets", "Animations", "MapInfos"];
+
+pub enum SourceChangeKind {
+    Changed,
+    Added,
+    Removed,
+}
+
+/// Source file, which differs from the one, that translation was read from.
+pub struct SourceChange {
+    pub filename: String,
+    pub kind: SourceChangeKind,
+}
+
+/// Hashes data files, which `read_map`, `read_other`, `read_system` and `read_scripts` read, so changes of the
+/// originals after reading can be detected. Returns object of filenames and their hex xxh3 hashes.
+/// # Parameters
+/// * `original_path` - path to the directory with original data files
+pub fn hash_sources(original_path: &Path) -> Object {
+    let mut filenames: Vec<String> = read_dir(original_path)
+        .unwrap()
+        .flatten()
+        .filter_map(|entry| {
+            let filename: OsString = entry.file_name();
+            let filename: &str = filename.to_str()?;
+            let (name, extension) = filename.split_once('.')?;
+
+            (DATA_EXTENSIONS.contains(&extension) && !TEXTLESS_FILES.contains(&name)).then(|| filename.to_string())
+        })
+        .collect();
+
+    filenames.sort();
+
+    let mut hashes: Object = Object::new();
+
+    for filename in filenames {
+        let bytes: Vec<u8> = read(original_path.join(&filename)).unwrap();
+        hashes.insert(&filename, format!("{:016x}", xxh3_64(&bytes)).as_str());
+    }
+
+    hashes
+}
+
+/// Compares hashes, that were recorded when reading, with hashes of the current originals. Returns changes sorted
+/// by filename.
+/// # Parameters
+/// * `recorded` - hashes from the metadata
+/// * `current` - hashes of the current originals
+pub fn compare_sources(recorded: &Value, current: &Object) -> Vec<SourceChange> {
+    let mut changes: Vec<SourceChange> = Vec::new();
+
+    let Some(recorded) = recorded.as_object() else {
+        return changes;
+    };
+
+    for (filename, hash) in current.iter() {
+        let kind: SourceChangeKind = match recorded.get(&filename) {
+            Some(recorded_hash) if recorded_hash.as_str() == hash.as_str() => continue,
+            Some(_) => SourceChangeKind::Changed,
+            None => SourceChangeKind::Added,
+        };
+
+        changes.push(SourceChange {
+            filename: filename.to_string(),
+            kind,
+        });
+    }
+
+    for (filename, _) in recorded.iter() {
+        if current.get(&filename).is_none() {
+            changes.push(SourceChange {
+                filename: filename.to_string(),
+                kind: SourceChangeKind::Removed,
+            });
+        }
+    }
+
+    changes.sort_by(|a: &SourceChange, b: &SourceChange| a.filename.cmp(&b.filename));
+    changes
+}
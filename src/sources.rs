@@ -0,0 +1,63 @@
+use sonic_rs::Value;
+use std::{
+    collections::HashMap,
+    hash::BuildHasherDefault,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+/// Parsed original data files and hashes of the bytes, which they were parsed from.
+type ParsedFiles = HashMap<PathBuf, (u64, Arc<Value>), BuildHasherDefault<Xxh3>>;
+
+/// Parsed original data files, which the server keeps between writes, so repeated writes don't parse unchanged files
+/// again.
+#[derive(Default)]
+pub struct Sources {
+    files: Mutex<ParsedFiles>,
+}
+
+impl Sources {
+    /// Drops parsed original data files, so they're parsed again on the next write.
+    pub fn clear(&self) {
+        self.files.lock().unwrap().clear();
+    }
+}
+
+/// Returns the parsed original data file. If sources are kept, and the file was parsed from the same bytes before,
+/// its kept value is returned instead of parsing it again.
+/// # Parameters
+/// * `sources` - kept sources, or None, if every write parses files again
+/// * `path` - path to the original data file
+/// * `bytes` - bytes of the file
+/// * `parse` - function that parses the bytes
+pub fn parse_source(sources: Option<&Sources>, path: &Path, bytes: &[u8], parse: impl FnOnce(&[u8]) -> Value) -> Value {
+    let Some(sources) = sources else {
+        return parse(bytes);
+    };
+
+    let hash: u64 = xxh3_64(bytes);
+
+    let kept: Option<Arc<Value>> = sources
+        .files
+        .lock()
+        .unwrap()
+        .get(path)
+        .filter(|(kept_hash, _)| *kept_hash == hash)
+        .map(|(_, value)| Arc::clone(value));
+
+    // Writers change the value, so the kept one is cloned
+    if let Some(value) = kept {
+        return (*value).clone();
+    }
+
+    let value: Value = parse(bytes);
+
+    sources
+        .files
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (hash, Arc::new(value.clone())));
+
+    value
+}
@@ -0,0 +1,503 @@
+use crate::migrate::read_lines;
+use ratatui::{
+    crossterm::event::{read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::{
+    fs::write,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Separator of lines inside of a single translation entry.
+const LINE_SEPARATOR: &str = r"\#";
+
+/// Symbol, which shows separators in the table, where entries take a single row.
+const LINE_SEPARATOR_SYMBOL: &str = " ↵ ";
+
+/// Subdirectories of the translation directory, which contain translation pairs.
+pub const PAIR_SUBDIRS: [&str; 3] = ["maps", "other", "plugins"];
+
+/// Messages of the editor, which are set from the localization.
+pub struct EditorLabels<'a> {
+    pub original_label: &'a str,
+    pub translation_label: &'a str,
+    pub browse_help: &'a str,
+    pub edit_help: &'a str,
+    pub search_prompt: &'a str,
+    pub saved_msg: &'a str,
+    pub save_refused_msg: &'a str,
+    pub unsaved_msg: &'a str,
+    pub no_untranslated_msg: &'a str,
+    pub not_found_msg: &'a str,
+    pub nothing_to_undo_msg: &'a str,
+}
+
+pub enum EditorError {
+    /// Pair doesn't exist in any of the subdirectories
+    Missing,
+    /// Original and translation files have different line counts, so lines can't be paired
+    Misaligned,
+}
+
+/// Multiline edit box of a single entry. Lines are separated with `\#` in the translation file.
+struct EditBox {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    column: usize,
+}
+
+impl EditBox {
+    fn new(translation: &str) -> Self {
+        let lines: Vec<Vec<char>> = translation
+            .split(LINE_SEPARATOR)
+            .map(|line: &str| line.chars().collect())
+            .collect();
+
+        let row: usize = lines.len() - 1;
+        let column: usize = lines[row].len();
+
+        Self { lines, row, column }
+    }
+
+    fn to_translation(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line: &Vec<char>| line.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join(LINE_SEPARATOR)
+    }
+
+    fn insert_char(&mut self, char: char) {
+        self.lines[self.row].insert(self.column, char);
+        self.column += 1;
+    }
+
+    fn insert_line_break(&mut self) {
+        let rest: Vec<char> = self.lines[self.row].split_off(self.column);
+        self.row += 1;
+        self.column = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    fn delete_backward(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+            self.lines[self.row].remove(self.column);
+        } else if self.row > 0 {
+            let line: Vec<char> = self.lines.remove(self.row);
+            self.row -= 1;
+            self.column = self.lines[self.row].len();
+            self.lines[self.row].extend(line);
+        }
+    }
+
+    fn delete_forward(&mut self) {
+        if self.column < self.lines[self.row].len() {
+            self.lines[self.row].remove(self.column);
+        } else if self.row + 1 < self.lines.len() {
+            let line: Vec<char> = self.lines.remove(self.row + 1);
+            self.lines[self.row].extend(line);
+        }
+    }
+
+    fn move_cursor(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Left if self.column > 0 => self.column -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.column = self.lines[self.row].len();
+            }
+            KeyCode::Right if self.column < self.lines[self.row].len() => self.column += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.column = 0;
+            }
+            KeyCode::Up if self.row > 0 => self.row -= 1,
+            KeyCode::Down if self.row + 1 < self.lines.len() => self.row += 1,
+            KeyCode::Home => self.column = 0,
+            KeyCode::End => self.column = self.lines[self.row].len(),
+            _ => {}
+        }
+
+        self.column = self.column.min(self.lines[self.row].len());
+    }
+}
+
+enum Mode {
+    Browse,
+    Search(String),
+    Edit(EditBox),
+    ConfirmQuit,
+}
+
+struct Editor<'a> {
+    original_path: PathBuf,
+    translation_path: PathBuf,
+    originals: Vec<String>,
+    translations: Vec<String>,
+    table_state: TableState,
+    mode: Mode,
+    /// Indices of edited lines and their previous translation, latest last
+    history: Vec<(usize, String)>,
+    modified: bool,
+    query: String,
+    status: String,
+    labels: &'a EditorLabels<'a>,
+}
+
+impl<'a> Editor<'a> {
+    fn selected(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    fn select(&mut self, index: usize) {
+        self.table_state
+            .select(Some(index.min(self.originals.len().saturating_sub(1))));
+    }
+
+    /// Selects the first line after the selected one, which matches the predicate, wrapping around. Returns whether
+    /// any line matched.
+    fn select_next(&mut self, predicate: impl Fn(&str, &str) -> bool) -> bool {
+        let len: usize = self.originals.len();
+
+        for step in 1..=len {
+            let index: usize = (self.selected() + step) % len;
+
+            if predicate(&self.originals[index], &self.translations[index]) {
+                self.select(index);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn search_next(&mut self) {
+        let query: String = self.query.to_lowercase();
+
+        if !query.is_empty()
+            && !self.select_next(|original: &str, translation: &str| {
+                original.to_lowercase().contains(&query) || translation.to_lowercase().contains(&query)
+            })
+        {
+            self.status = format!("{} {}", self.labels.not_found_msg, self.query);
+        }
+    }
+
+    fn set_translation(&mut self, index: usize, translation: String) {
+        if self.translations[index] != translation {
+            let previous: String = std::mem::replace(&mut self.translations[index], translation);
+            self.history.push((index, previous));
+            self.modified = true;
+        }
+    }
+
+    fn undo(&mut self) {
+        match self.history.pop() {
+            Some((index, previous)) => {
+                self.translations[index] = previous;
+                self.modified = true;
+                self.select(index);
+            }
+            None => self.status = self.labels.nothing_to_undo_msg.to_string(),
+        }
+    }
+
+    /// Writes the translation back, unless it would change the line count, like if the original file was re-read
+    /// with a different number of lines, while the editor was open.
+    fn save(&mut self) {
+        let content: String = self.translations.join("\n");
+
+        if content.split('\n').count() != self.originals.len()
+            || read_lines(&self.original_path).len() != self.originals.len()
+        {
+            self.status = self.labels.save_refused_msg.to_string();
+            return;
+        }
+
+        match write(&self.translation_path, content) {
+            Ok(()) => {
+                self.modified = false;
+                self.status = format!("{} {}", self.labels.saved_msg, self.translation_path.display());
+            }
+            Err(err) => self.status = err.to_string(),
+        }
+    }
+
+    /// Handles the key. Returns whether the editor should be closed.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.status.clear();
+
+        match &mut self.mode {
+            Mode::Browse | Mode::ConfirmQuit => {
+                let confirmed_quit: bool = matches!(self.mode, Mode::ConfirmQuit);
+                self.mode = Mode::Browse;
+
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        if !self.modified || confirmed_quit {
+                            return true;
+                        }
+
+                        self.mode = Mode::ConfirmQuit;
+                        self.status = self.labels.unsaved_msg.to_string();
+                    }
+                    KeyCode::Char('s') => self.save(),
+                    KeyCode::Up | KeyCode::Char('k') => self.select(self.selected().saturating_sub(1)),
+                    KeyCode::Down | KeyCode::Char('j') => self.select(self.selected() + 1),
+                    KeyCode::PageUp => self.select(self.selected().saturating_sub(20)),
+                    KeyCode::PageDown => self.select(self.selected() + 20),
+                    KeyCode::Home | KeyCode::Char('g') => self.select(0),
+                    KeyCode::End | KeyCode::Char('G') => self.select(self.originals.len()),
+                    KeyCode::Char('n') => {
+                        let found: bool = self.select_next(|original: &str, translation: &str| {
+                            !original.trim().is_empty() && translation.trim().is_empty()
+                        });
+
+                        if !found {
+                            self.status = self.labels.no_untranslated_msg.to_string();
+                        }
+                    }
+                    KeyCode::Char('/') => self.mode = Mode::Search(String::new()),
+                    KeyCode::Char('f') => self.search_next(),
+                    KeyCode::Char('u') => self.undo(),
+                    KeyCode::Enter | KeyCode::Char('e') => {
+                        self.mode = Mode::Edit(EditBox::new(&self.translations[self.selected()]))
+                    }
+                    _ => {}
+                }
+            }
+            Mode::Search(query) => match key.code {
+                KeyCode::Esc => self.mode = Mode::Browse,
+                KeyCode::Enter => {
+                    self.query = std::mem::take(query);
+                    self.mode = Mode::Browse;
+                    self.search_next();
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(char) => query.push(char),
+                _ => {}
+            },
+            Mode::Edit(edit_box) => match key.code {
+                KeyCode::Esc => self.mode = Mode::Browse,
+                // Terminals can't tell Shift+Enter from Enter, so line breaks are inserted with Alt+Enter or Ctrl+N
+                KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => edit_box.insert_line_break(),
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => edit_box.insert_line_break(),
+                KeyCode::Enter => {
+                    let translation: String = edit_box.to_translation();
+                    self.mode = Mode::Browse;
+                    self.set_translation(self.selected(), translation);
+                }
+                KeyCode::Backspace => edit_box.delete_backward(),
+                KeyCode::Delete => edit_box.delete_forward(),
+                KeyCode::Char(char) => edit_box.insert_char(char),
+                code => edit_box.move_cursor(code),
+            },
+        }
+
+        false
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, detail_area, status_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(6), Constraint::Length(1)]).areas(frame.area());
+
+        let untranslated_style: Style = Style::default().fg(Color::Yellow);
+
+        let rows: Vec<Row> = self
+            .originals
+            .iter()
+            .zip(self.translations.iter())
+            .enumerate()
+            .map(|(i, (original, translation))| {
+                let row: Row = Row::new([
+                    Cell::from((i + 1).to_string()),
+                    Cell::from(original.replace(LINE_SEPARATOR, LINE_SEPARATOR_SYMBOL)),
+                    Cell::from(translation.replace(LINE_SEPARATOR, LINE_SEPARATOR_SYMBOL)),
+                ]);
+
+                if translation.trim().is_empty() && !original.trim().is_empty() {
+                    row.style(untranslated_style)
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let title: String = format!(
+            "{}{}",
+            self.translation_path.display(),
+            if self.modified { " *" } else { "" }
+        );
+
+        let table: Table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Percentage(47),
+                Constraint::Percentage(47),
+            ],
+        )
+        .header(
+            Row::new(["#", self.labels.original_label, self.labels.translation_label])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+
+        let selected: usize = self.selected();
+        let [original_area, translation_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(detail_area);
+
+        for (area, label, text) in [
+            (original_area, self.labels.original_label, &self.originals[selected]),
+            (
+                translation_area,
+                self.labels.translation_label,
+                &self.translations[selected],
+            ),
+        ] {
+            frame.render_widget(
+                Paragraph::new(Text::from(
+                    text.split(LINE_SEPARATOR).map(Line::from).collect::<Vec<Line>>(),
+                ))
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(label)),
+                area,
+            );
+        }
+
+        let status: String = match &self.mode {
+            Mode::Search(query) => format!("{} {query}", self.labels.search_prompt),
+            Mode::Edit(_) => self.labels.edit_help.to_string(),
+            _ if !self.status.is_empty() => self.status.clone(),
+            _ => self.labels.browse_help.to_string(),
+        };
+
+        frame.render_widget(Paragraph::new(status), status_area);
+
+        if let Mode::Edit(edit_box) = &self.mode {
+            let area: Rect = centered_rect(table_area, edit_box.lines.len() as u16 + 2);
+            let lines: Vec<Line> = edit_box
+                .lines
+                .iter()
+                .map(|line: &Vec<char>| Line::from(line.iter().collect::<String>()))
+                .collect();
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+                    "{} {}",
+                    self.labels.translation_label,
+                    selected + 1
+                ))),
+                area,
+            );
+
+            // Wide characters, like CJK ones, take two cells
+            let column: u16 = Line::from(
+                edit_box.lines[edit_box.row][..edit_box.column]
+                    .iter()
+                    .collect::<String>(),
+            )
+            .width() as u16;
+
+            frame.set_cursor_position(Position::new(
+                (area.x + 1 + column).min(area.right().saturating_sub(2)),
+                area.y + 1 + edit_box.row as u16,
+            ));
+        }
+    }
+}
+
+/// Returns the area in the middle of the given one, which is as wide as 80% of it, and has the given height.
+fn centered_rect(area: Rect, height: u16) -> Rect {
+    let height: u16 = height.min(area.height);
+    let width: u16 = area.width * 4 / 5;
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Finds the translation pair by its name, like `maps`, `actors` or `plugins`, in the subdirectories of the
+/// translation directory. Names may also end with `.txt` or `_trans.txt`. Returns paths to the original and
+/// translation files.
+/// # Parameters
+/// * `translation_path` - path to the translation directory
+/// * `name` - name of the translation pair
+pub fn find_translation_pair(translation_path: &Path, name: &str) -> Option<(PathBuf, PathBuf)> {
+    let name: &str = name.strip_suffix(".txt").unwrap_or(name);
+    let name: &str = name.strip_suffix("_trans").unwrap_or(name);
+
+    PAIR_SUBDIRS.iter().find_map(|subdir: &&str| {
+        let original_path: PathBuf = translation_path.join(format!("{subdir}/{name}.txt"));
+        let translation_file_path: PathBuf = translation_path.join(format!("{subdir}/{name}_trans.txt"));
+
+        (original_path.exists() && translation_file_path.exists()).then_some((original_path, translation_file_path))
+    })
+}
+
+/// Opens the translation pair side by side in the terminal, and lets its translation be edited line by line.
+/// # Parameters
+/// * `translation_path` - path to the translation directory
+/// * `name` - name of the translation pair, like `maps` or `actors`
+/// * `labels` - messages of the editor
+pub fn run_editor(translation_path: &Path, name: &str, labels: &EditorLabels) -> Result<(), EditorError> {
+    let (original_path, translation_file_path) =
+        find_translation_pair(translation_path, name).ok_or(EditorError::Missing)?;
+
+    let originals: Vec<String> = read_lines(&original_path);
+    let translations: Vec<String> = read_lines(&translation_file_path);
+
+    if originals.len() != translations.len() {
+        return Err(EditorError::Misaligned);
+    }
+
+    let mut editor: Editor = Editor {
+        original_path,
+        translation_path: translation_file_path,
+        originals,
+        translations,
+        table_state: TableState::default().with_selected(Some(0)),
+        mode: Mode::Browse,
+        history: Vec::new(),
+        modified: false,
+        query: String::new(),
+        status: String::new(),
+        labels,
+    };
+
+    // Terminal is restored on panics too
+    let mut terminal: DefaultTerminal = ratatui::init();
+    let result: io::Result<()> = run_loop(&mut terminal, &mut editor);
+    ratatui::restore();
+
+    result.unwrap();
+    Ok(())
+}
+
+fn run_loop(terminal: &mut DefaultTerminal, editor: &mut Editor) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame: &mut Frame| editor.draw(frame))?;
+
+        if let Event::Key(key) = read()? {
+            // Windows reports releases of the keys too
+            if key.kind == KeyEventKind::Press && editor.handle_key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
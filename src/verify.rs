@@ -0,0 +1,295 @@
+use marshal_rs::load::load;
+use rayon::prelude::*;
+use sonic_rs::{from_slice, from_value, prelude::*, to_string, Value};
+use std::{
+    ffi::OsString,
+    fs::{read, read_dir, remove_dir_all, DirEntry},
+    path::{Path, PathBuf},
+};
+
+/// Single difference between the original and the written file.
+pub struct Discrepancy {
+    /// Path to the differing value, like `$.events[1].pages[0].list[3].parameters[0]`.
+    /// Marshal objects have their class in angle brackets, like `$.data<Table>`.
+    pub path: String,
+    pub expected: String,
+    pub found: String,
+}
+
+pub enum Verification {
+    ByteIdentical,
+    StructurallyIdentical,
+    Different(Vec<Discrepancy>),
+}
+
+/// Placeholder, that's used in discrepancies when the value is missing on one of the sides.
+const MISSING: &str = "-";
+
+/// Temporary directory, which is removed when dropped, so it doesn't leak, if verifying panics.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(path: PathBuf) -> Self {
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
+
+/// Formats the object key as a path segment. Marshal symbols and integer hash keys are stored with
+/// prefixes, which are stripped.
+fn format_key(key: &str) -> String {
+    if let Some(symbol) = key.strip_prefix("__symbol__") {
+        format!(".{symbol}")
+    } else if let Some(integer) = key.strip_prefix("__integer__") {
+        format!("[{integer}]")
+    } else {
+        format!(".{key}")
+    }
+}
+
+fn get_class(value: &Value) -> Option<&str> {
+    let class: &str = value["__class"].as_str()?;
+    Some(class.strip_prefix("__symbol__").unwrap_or(class))
+}
+
+/// Returns a short readable representation of the value.
+fn describe(value: &Value) -> String {
+    if let Some(string) = value.as_str() {
+        if let Some(symbol) = string.strip_prefix("__symbol__") {
+            return format!(":{symbol}");
+        }
+    }
+
+    if value["__type"].as_str() == Some("bytes") {
+        let bytes: Vec<u8> = from_value(&value["data"]).unwrap_or_default();
+        return to_string(&String::from_utf8_lossy(&bytes)).unwrap();
+    }
+
+    if let Some(class) = get_class(value) {
+        if let Some(data) = value["__userDefined"].as_array() {
+            return format!("{class}({} bytes)", data.len());
+        }
+
+        return format!("#<{class}>");
+    }
+
+    let mut described: String = to_string(value).unwrap();
+
+    if described.chars().count() > 80 {
+        described = described.chars().take(77).collect::<String>() + "...";
+    }
+
+    described
+}
+
+/// Recursively compares values and collects discrepancies between them.
+/// # Parameters
+/// * `original` - value from the original file
+/// * `written` - value from the written file
+/// * `path` - path to the compared values
+/// * `discrepancies` - vector to push discrepancies to
+fn compare_values(original: &Value, written: &Value, path: &str, discrepancies: &mut Vec<Discrepancy>) {
+    if original == written {
+        return;
+    }
+
+    match (original.as_object(), written.as_object()) {
+        (Some(original_obj), Some(written_obj)) => {
+            let path: String = match get_class(original) {
+                Some(class) => format!("{path}<{class}>"),
+                None => path.to_string(),
+            };
+
+            // User-defined objects (Table, Color, Tone) and byte strings are opaque, so they're compared as a whole
+            if original_obj.contains_key(&"__userDefined") || original["__type"].as_str() == Some("bytes") {
+                let (mut expected, mut found): (String, String) = (describe(original), describe(written));
+
+                // Descriptions of user objects of the same size are equal, so the first differing byte is reported
+                if let (Some(original_data), Some(written_data)) = (
+                    original["__userDefined"].as_array(),
+                    written["__userDefined"].as_array(),
+                ) {
+                    if let Some(i) = (0..original_data.len().min(written_data.len()))
+                        .find(|&i: &usize| original_data[i] != written_data[i])
+                    {
+                        expected += &format!(", byte {i} = {}", original_data[i]);
+                        found += &format!(", byte {i} = {}", written_data[i]);
+                    }
+                }
+
+                discrepancies.push(Discrepancy { path, expected, found });
+                return;
+            }
+
+            for (key, original_value) in original_obj.iter() {
+                let key_path: String = path.clone() + &format_key(key);
+
+                match written_obj.get(&key) {
+                    Some(written_value) => compare_values(original_value, written_value, &key_path, discrepancies),
+                    None => discrepancies.push(Discrepancy {
+                        path: key_path,
+                        expected: describe(original_value),
+                        found: MISSING.to_string(),
+                    }),
+                }
+            }
+
+            for (key, written_value) in written_obj.iter() {
+                if !original_obj.contains_key(&key) {
+                    discrepancies.push(Discrepancy {
+                        path: path.clone() + &format_key(key),
+                        expected: MISSING.to_string(),
+                        found: describe(written_value),
+                    });
+                }
+            }
+        }
+        _ => match (original.as_array(), written.as_array()) {
+            (Some(original_arr), Some(written_arr)) => {
+                for i in 0..original_arr.len().max(written_arr.len()) {
+                    let index_path: String = format!("{path}[{i}]");
+
+                    match (original_arr.get(i), written_arr.get(i)) {
+                        (Some(original_value), Some(written_value)) => {
+                            compare_values(original_value, written_value, &index_path, discrepancies)
+                        }
+                        (Some(original_value), None) => discrepancies.push(Discrepancy {
+                            path: index_path,
+                            expected: describe(original_value),
+                            found: MISSING.to_string(),
+                        }),
+                        (None, Some(written_value)) => discrepancies.push(Discrepancy {
+                            path: index_path,
+                            expected: MISSING.to_string(),
+                            found: describe(written_value),
+                        }),
+                        (None, None) => unreachable!(),
+                    }
+                }
+            }
+            _ => discrepancies.push(Discrepancy {
+                path: path.to_string(),
+                expected: describe(original),
+                found: describe(written),
+            }),
+        },
+    }
+}
+
+/// Compares the written file with the original one, first byte-wise and then structurally.
+///
+/// Marshal `load` normalizes some differences, like repeated symbols and their links, so Marshal files, that are
+/// loaded into the same structure, must still be byte-identical, and the first differing byte is reported otherwise.
+/// Formatting of JSON files doesn't matter to the engine, so they only have to be structurally identical.
+/// # Parameters
+/// * `original_file_path` - path to the original file
+/// * `written_file_path` - path to the written file
+pub fn verify_file(original_file_path: &Path, written_file_path: &Path) -> Verification {
+    let original_bytes: Vec<u8> = read(original_file_path).unwrap();
+    let written_bytes: Vec<u8> = read(written_file_path).unwrap();
+
+    if original_bytes == written_bytes {
+        return Verification::ByteIdentical;
+    }
+
+    let is_json: bool = original_file_path
+        .extension()
+        .is_some_and(|extension| extension == "json");
+
+    let parse = |bytes: &[u8]| -> Value {
+        if is_json {
+            from_slice(bytes).unwrap()
+        } else {
+            load(bytes, None, Some("")).unwrap()
+        }
+    };
+
+    let mut discrepancies: Vec<Discrepancy> = Vec::new();
+    compare_values(&parse(&original_bytes), &parse(&written_bytes), "$", &mut discrepancies);
+
+    if discrepancies.is_empty() && !is_json {
+        let i: usize = (0..original_bytes.len().min(written_bytes.len()))
+            .find(|&i: &usize| original_bytes[i] != written_bytes[i])
+            .unwrap_or(original_bytes.len().min(written_bytes.len()));
+
+        let describe_byte = |bytes: &[u8]| -> String {
+            bytes
+                .get(i)
+                .map_or(MISSING.to_string(), |byte: &u8| format!("byte {i} = {byte}"))
+        };
+
+        discrepancies.push(Discrepancy {
+            path: String::from("$"),
+            expected: describe_byte(&original_bytes),
+            found: describe_byte(&written_bytes),
+        });
+    }
+
+    if discrepancies.is_empty() {
+        Verification::StructurallyIdentical
+    } else {
+        Verification::Different(discrepancies)
+    }
+}
+
+/// Compares every file of the written directory with the file of the same name from the original directory.
+/// Returns results sorted by filename.
+/// # Parameters
+/// * `original_path` - path to the directory with original files
+/// * `written_path` - path to the directory with written files
+pub fn verify(original_path: &Path, written_path: &Path) -> Vec<(String, Verification)> {
+    let mut filenames: Vec<OsString> = read_dir(written_path)
+        .unwrap()
+        .flatten()
+        .map(|entry: DirEntry| entry.file_name())
+        .collect();
+
+    filenames.sort();
+
+    filenames
+        .into_par_iter()
+        .map(|filename: OsString| {
+            let verification: Verification = verify_file(&original_path.join(&filename), &written_path.join(&filename));
+            (filename.to_string_lossy().into_owned(), verification)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env::temp_dir, fs::write};
+
+    #[test]
+    fn marshal_differences_hidden_by_load_are_reported() {
+        let dir: TempDir = TempDir::new(temp_dir().join(format!("rvpacker-txt-rs-verify-test-{}", std::process::id())));
+        std::fs::create_dir_all(dir.path()).unwrap();
+
+        // [:a, :a], where the second symbol is a link to the first one, and where it's written again
+        let original_path: PathBuf = dir.path().join("original.rvdata2");
+        let written_path: PathBuf = dir.path().join("written.rvdata2");
+        write(&original_path, b"\x04\x08[\x07:\x06a;\x00").unwrap();
+        write(&written_path, b"\x04\x08[\x07:\x06a:\x06a").unwrap();
+
+        let Verification::Different(discrepancies) = verify_file(&original_path, &written_path) else {
+            panic!("difference isn't reported");
+        };
+
+        assert_eq!(discrepancies.len(), 1);
+        assert_eq!(discrepancies[0].expected, "byte 7 = 59");
+        assert_eq!(discrepancies[0].found, "byte 7 = 58");
+
+        let path: PathBuf = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::events::{self, Output};
+use notify::{recommended_watcher, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    fs::read_dir,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+    time::Duration,
+};
+
+/// Writers, that can be re-run separately when their translation files change.
+#[derive(PartialEq, Clone, Copy)]
+pub enum Writer {
+    Maps,
+    Other,
+    System,
+    Plugins,
+    Scripts,
+}
+
+impl Writer {
+    pub const ALL: [Writer; 5] = [
+        Writer::Maps,
+        Writer::Other,
+        Writer::System,
+        Writer::Plugins,
+        Writer::Scripts,
+    ];
+}
+
+/// Determines which writer should be re-run when the file at path changes.
+/// # Parameters
+/// * `path` - path to the changed file
+/// * `maps_path` - canonicalized path to the translation/maps directory
+/// * `other_path` - canonicalized path to the translation/other directory
+/// * `plugins_path` - canonicalized path to the translation/plugins directory
+fn get_affected_writer(path: &Path, maps_path: &Path, other_path: &Path, plugins_path: &Path) -> Option<Writer> {
+    let extension: &str = path.extension()?.to_str()?;
+
+    if !matches!(extension, "txt" | "json") {
+        return None;
+    }
+
+    let parent: &Path = path.parent()?;
+    let filename: &str = path.file_name()?.to_str()?;
+
+    if parent == maps_path {
+        Some(Writer::Maps)
+    } else if parent == plugins_path {
+        Some(Writer::Plugins)
+    } else if parent == other_path {
+        if filename.starts_with("system") {
+            Some(Writer::System)
+        } else if filename.starts_with("scripts") {
+            Some(Writer::Scripts)
+        } else {
+            Some(Writer::Other)
+        }
+    } else {
+        None
+    }
+}
+
+/// Returns the absolute path, which events of the watcher contain. Path, that doesn't exist yet, is resolved against
+/// its canonicalized parent.
+fn canonicalize_path(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .ok()
+        .or_else(|| Some(path.parent()?.canonicalize().ok()?.join(path.file_name()?)))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Watches translation directories and re-runs affected writers when translation files change.
+///
+/// Events are debounced, so saving multiple files at once results in a single write. Panics of writers
+/// are caught and reported, so the watcher keeps running. Directories, that don't exist yet, like
+/// translation/plugins, are watched once they're created in their parent directory.
+/// # Parameters
+/// * `maps_path` - path to the translation/maps directory
+/// * `other_path` - path to the translation/other directory
+/// * `plugins_path` - path to the translation/plugins directory
+/// * `debounce` - time to wait for further changes before writing
+/// * `watching_msg` - message to print when watching starts
+/// * `write_failed_msg` - message to print when writer panics
+/// * `output` - where and how events and messages are printed
+/// * `run_writers` - function that runs the passed writers
+#[allow(clippy::too_many_arguments)]
+pub fn watch(
+    maps_path: &Path,
+    other_path: &Path,
+    plugins_path: &Path,
+    debounce: Duration,
+    watching_msg: &str,
+    write_failed_msg: &str,
+    output: Output,
+    mut run_writers: impl FnMut(&[Writer]),
+) {
+    let (sender, receiver): (Sender<notify::Result<Event>>, Receiver<notify::Result<Event>>) = channel();
+    let mut watcher: RecommendedWatcher = recommended_watcher(sender).unwrap();
+
+    // Events contain absolute paths, so watched paths are canonicalized to compare them
+    let [maps_path, other_path, plugins_path]: [PathBuf; 3] =
+        [maps_path, other_path, plugins_path].map(canonicalize_path);
+
+    // Directories, which are watched after their parent reports their creation
+    let mut pending_paths: Vec<PathBuf> = Vec::new();
+
+    for path in [&maps_path, &other_path, &plugins_path] {
+        if path.exists() {
+            watcher.watch(path, RecursiveMode::NonRecursive).unwrap();
+        } else if let Some(parent) = path.parent().filter(|parent: &&Path| parent.exists()) {
+            if !pending_paths
+                .iter()
+                .any(|pending: &PathBuf| pending.parent() == Some(parent))
+            {
+                watcher.watch(parent, RecursiveMode::NonRecursive).unwrap();
+            }
+
+            pending_paths.push(path.clone());
+        }
+    }
+
+    output.print_message(watching_msg);
+
+    let mut collect_writers = |event: notify::Result<Event>, writers: &mut Vec<Writer>| {
+        let Ok(event) = event else {
+            return;
+        };
+
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        for path in event.paths {
+            if let Some(index) = pending_paths
+                .iter()
+                .position(|pending: &PathBuf| *pending == path && path.is_dir())
+            {
+                watcher.watch(&path, RecursiveMode::NonRecursive).unwrap();
+                pending_paths.remove(index);
+
+                // Files, which were created before the directory was watched, have no events of their own
+                for entry in read_dir(&path).into_iter().flatten().flatten() {
+                    if let Some(writer) = get_affected_writer(&entry.path(), &maps_path, &other_path, &plugins_path) {
+                        if !writers.contains(&writer) {
+                            writers.push(writer);
+                        }
+                    }
+                }
+
+                continue;
+            }
+
+            if let Some(writer) = get_affected_writer(&path, &maps_path, &other_path, &plugins_path) {
+                if !writers.contains(&writer) {
+                    writers.push(writer);
+                }
+            }
+        }
+    };
+
+    while let Ok(event) = receiver.recv() {
+        let mut writers: Vec<Writer> = Vec::new();
+        collect_writers(event, &mut writers);
+
+        loop {
+            match receiver.recv_timeout(debounce) {
+                Ok(event) => collect_writers(event, &mut writers),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        if writers.is_empty() {
+            continue;
+        }
+
+        // Keep the same order as in regular write
+        let writers: Vec<Writer> = Writer::ALL
+            .into_iter()
+            .filter(|writer: &Writer| writers.contains(writer))
+            .collect();
+
+        if catch_unwind(AssertUnwindSafe(|| run_writers(&writers))).is_err() {
+            if output.is_json() {
+                output.emit(events::Event::Error {
+                    message: write_failed_msg,
+                });
+            } else {
+                eprintln!("{write_failed_msg}");
+            }
+        }
+    }
+}
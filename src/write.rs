@@ -1,13 +1,27 @@
 #![allow(clippy::too_many_arguments)]
-use crate::{romanize_string, Code, EngineType, GameType, Variable, ENDS_WITH_IF_RE, LISA_PREFIX_RE, SELECT_WORDS_RE};
+use crate::{
+    cache::{hash_files, hash_keys_translation, hash_translation_pairs, Cache, CacheEntry, NAMES_SEED},
+    config::filter_event_codes,
+    events::{Event, Output, SkipReason},
+    file_filter::FileFilter,
+    note_tags::{splice_note_fragments, NoteTagRule},
+    placeholders::{protect_escape_codes, restore_escape_codes},
+    progress::Progress,
+    read::parse_list,
+    romanization::RomanizationTable,
+    romanize_string,
+    sources::{parse_source, Sources},
+    Code, EngineType, GameType, ProcessingMode, Variable, ENDS_WITH_IF_RE, ESCAPE_CODE_RE, HTML_TITLE_RE,
+    LISA_PREFIX_RE, SELECT_WORDS_RE,
+};
 use encoding_rs::{CoderResult, Encoding};
 use fastrand::shuffle;
 use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use marshal_rs::{dump::dump, load::load};
 use rayon::prelude::*;
 use regex::{Captures, Match};
-use sonic_rs::{from_str, from_value, json, prelude::*, to_string, Array, Object, Value};
+use sonic_rs::{from_slice, from_str, from_value, json, prelude::*, to_string, Array, Object, Value};
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
@@ -19,7 +33,7 @@ use std::{
     str::{from_utf8_unchecked, CharIndices, Chars},
     sync::Arc,
 };
-use xxhash_rust::xxh3::Xxh3;
+use xxhash_rust::xxh3::{xxh3_64_with_seed, Xxh3};
 
 trait EachLine {
     fn each_line(&self) -> Vec<String>;
@@ -46,6 +60,67 @@ impl EachLine for str {
     }
 }
 
+/// Accented replacements of ASCII letters, that are used in pseudo-localization.
+const ACCENTED_LOWERCASE: &str = "áƀçđéƒĝĥíĵķĺɱñóþǫŕšţúṽŵẋýž";
+const ACCENTED_UPPERCASE: &str = "ÁƁÇĐÉƑĜĤÍĴĶĹṀÑÓÞǪŔŠŢÚṼŴẊÝŽ";
+
+fn accent(char: char) -> char {
+    match char {
+        'a'..='z' => ACCENTED_LOWERCASE.chars().nth((char as u8 - b'a') as usize).unwrap(),
+        'A'..='Z' => ACCENTED_UPPERCASE.chars().nth((char as u8 - b'A') as usize).unwrap(),
+        _ => char,
+    }
+}
+
+/// Pseudo-localizes a single line: accents its letters, pads it with `~` by `expansion` percent of its length and
+/// wraps it in brackets. Escape codes are kept unchanged and aren't counted in the length.
+fn pseudolocalize_line(line: &str, expansion: u16) -> String {
+    if line.trim().is_empty() {
+        return line.to_string();
+    }
+
+    let mut result: String = String::from("[");
+    let mut length: usize = 0;
+    let mut last_end: usize = 0;
+
+    let mut push_accented = |text: &str, result: &mut String| {
+        for char in text.chars() {
+            result.push(accent(char));
+            length += 1;
+        }
+    };
+
+    for code in ESCAPE_CODE_RE.find_iter(line) {
+        push_accented(&line[last_end..code.start()], &mut result);
+        result.push_str(code.as_str());
+        last_end = code.end();
+    }
+
+    push_accented(&line[last_end..], &mut result);
+
+    result.push_str(&"~".repeat((length * expansion as usize).div_ceil(100)));
+    result.push(']');
+    result
+}
+
+/// Replaces the string with its pseudo-localized form, so truncated and untranslated text can be spotted in-game.
+/// Each line between line breaks and `\#` separators is pseudo-localized separately.
+/// # Parameters
+/// * `string` - original string
+/// * `expansion` - percent, by which length of each line is expanded
+pub fn pseudolocalize(string: &str, expansion: u16) -> String {
+    string
+        .split('\n')
+        .map(|line: &str| {
+            line.split(r"\#")
+                .map(|part: &str| pseudolocalize_line(part, expansion))
+                .collect::<Vec<String>>()
+                .join(r"\#")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 pub fn shuffle_words(string: &str) -> String {
     let mut words: Vec<&str> = SELECT_WORDS_RE.find_iter(string).map(|m: Match| m.as_str()).collect();
 
@@ -63,6 +138,7 @@ fn get_translated_parameter<'a>(
     hashmap: &'a HashMap<String, String, BuildHasherDefault<Xxh3>>,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
+    protect_codes: bool,
 ) -> Option<String> {
     let mut remaining_strings: Vec<String> = Vec::new();
 
@@ -106,10 +182,19 @@ fn get_translated_parameter<'a>(
         }
     }
 
-    let translated: Option<String> = hashmap.get(parameter).map(|translated: &String| {
-        let mut result: String = translated.to_owned();
-        result
-    });
+    let translated: Option<String> = if protect_codes {
+        // Translation contains placeholders, which are replaced back with the codes of the original
+        let (protected, codes): (String, Vec<String>) = protect_escape_codes(parameter);
+
+        hashmap
+            .get(&protected)
+            .map(|translated: &String| restore_escape_codes(translated, &codes))
+    } else {
+        hashmap.get(parameter).map(|translated: &String| {
+            let mut result: String = translated.to_owned();
+            result
+        })
+    };
 
     if let Some(mut translated) = translated {
         if translated.is_empty() {
@@ -138,6 +223,8 @@ fn get_translated_variable(
     hashmap: &HashMap<String, String, BuildHasherDefault<Xxh3>>,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
+    romanization_table: Option<&RomanizationTable>,
+    note_tag_rules: &[NoteTagRule],
 ) -> Option<String> {
     let mut remaining_strings: Vec<String> = Vec::new();
 
@@ -146,6 +233,23 @@ fn get_translated_variable(
     // false inserts at start
     let mut insert_positions: Vec<bool> = Vec::new();
 
+    // Only translatable fragments are replaced, and the rest of the note is kept as is
+    if variable_type == Variable::Note && !note_tag_rules.is_empty() {
+        return splice_note_fragments(&variable_text, note_tag_rules, |fragment: &str| {
+            let mut fragment: String = fragment.to_string();
+
+            if let Some(table) = romanization_table {
+                fragment = romanize_string(fragment, table);
+            }
+
+            let key: String = fragment.split('\n').map(str::trim).collect::<Vec<_>>().join("\n");
+            hashmap
+                .get(&key)
+                .filter(|translated: &&String| !translated.is_empty())
+                .cloned()
+        });
+    }
+
     if engine_type != EngineType::New {
         variable_text = variable_text.replace("\r\n", "\n");
     }
@@ -299,7 +403,8 @@ fn get_translated_variable(
 fn write_list(
     list: &mut Array,
     allowed_codes: &[u16],
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
     map: &HashMap<String, String, BuildHasherDefault<Xxh3>>,
@@ -325,12 +430,12 @@ fn write_list(
             if !line.is_empty() {
                 let mut joined: String = line.join("\n").trim().to_string();
 
-                if romanize {
-                    joined = romanize_string(joined)
+                if let Some(table) = romanization_table {
+                    joined = romanize_string(joined, table)
                 }
 
                 let translated: Option<String> =
-                    get_translated_parameter(Code::Dialogue, &joined, map, game_type, engine_type);
+                    get_translated_parameter(Code::Dialogue, &joined, map, game_type, engine_type, protect_codes);
 
                 if let Some(translated) = translated {
                     let split: Vec<&str> = translated.split('\n').collect();
@@ -427,12 +532,18 @@ fn write_list(
                         .trim()
                         .to_string();
 
-                    if romanize {
-                        subparameter_string = romanize_string(subparameter_string);
+                    if let Some(table) = romanization_table {
+                        subparameter_string = romanize_string(subparameter_string, table);
                     }
 
-                    let translated: Option<String> =
-                        get_translated_parameter(Code::Choice, &subparameter_string, map, game_type, engine_type);
+                    let translated: Option<String> = get_translated_parameter(
+                        Code::Choice,
+                        &subparameter_string,
+                        map,
+                        game_type,
+                        engine_type,
+                        protect_codes,
+                    );
 
                     if let Some(translated) = translated {
                         if engine_type == EngineType::New {
@@ -469,12 +580,18 @@ fn write_list(
                     .trim()
                     .to_string();
 
-                if romanize {
-                    parameter_string = romanize_string(parameter_string);
+                if let Some(table) = romanization_table {
+                    parameter_string = romanize_string(parameter_string, table);
                 }
 
-                let translated: Option<String> =
-                    get_translated_parameter(Code::System, &parameter_string, map, game_type, engine_type);
+                let translated: Option<String> = get_translated_parameter(
+                    Code::System,
+                    &parameter_string,
+                    map,
+                    game_type,
+                    engine_type,
+                    protect_codes,
+                );
 
                 if let Some(translated) = translated {
                     if engine_type == EngineType::New {
@@ -510,12 +627,18 @@ fn write_list(
                     .trim()
                     .to_string();
 
-                if romanize {
-                    parameter_string = romanize_string(parameter_string);
+                if let Some(table) = romanization_table {
+                    parameter_string = romanize_string(parameter_string, table);
                 }
 
-                let translated: Option<String> =
-                    get_translated_parameter(Code::Unknown, &parameter_string, map, game_type, engine_type);
+                let translated: Option<String> = get_translated_parameter(
+                    Code::Unknown,
+                    &parameter_string,
+                    map,
+                    game_type,
+                    engine_type,
+                    protect_codes,
+                );
 
                 if let Some(translated) = translated {
                     if engine_type == EngineType::New {
@@ -531,27 +654,338 @@ fn write_list(
     }
 }
 
+/// Collects hashes of all maps.txt and names.txt originals, that the map contains, to store them in the cache.
+fn collect_map_keys(
+    obj: &Value,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
+    game_type: Option<&GameType>,
+    engine_type: &EngineType,
+    (display_name_label, events_label, pages_label, list_label, code_label, parameters_label): (
+        &str,
+        &str,
+        &str,
+        &str,
+        &str,
+        &str,
+    ),
+) -> Vec<u64> {
+    // Codes must be the same as in read_map, as only they produce the maps.txt entries
+    const ALLOWED_CODES: [u16; 5] = [102, 320, 324, 356, 401];
+    let allowed_codes: Vec<u16> = filter_event_codes(&ALLOWED_CODES, event_codes);
+
+    let mut keys: Vec<u64> = Vec::new();
+
+    if let Some(display_name) = obj[display_name_label].as_str() {
+        let mut display_name: String = display_name.to_string();
+
+        if let Some(table) = romanization_table {
+            display_name = romanize_string(display_name, table)
+        }
+
+        keys.push(xxh3_64_with_seed(display_name.as_bytes(), NAMES_SEED));
+    }
+
+    let mut lines: IndexSet<String, BuildHasherDefault<Xxh3>> = IndexSet::default();
+    let mut unused_map: IndexMap<String, String, BuildHasherDefault<Xxh3>> = IndexMap::default();
+
+    let events_arr: Vec<&Value> = if engine_type == EngineType::New {
+        obj[events_label].as_array().unwrap().iter().skip(1).collect()
+    } else {
+        obj[events_label]
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(_, value)| value)
+            .collect()
+    };
+
+    for event in events_arr {
+        if !event[pages_label].is_array() {
+            continue;
+        }
+
+        for page in event[pages_label].as_array().unwrap().iter() {
+            parse_list(
+                page[list_label].as_array().unwrap(),
+                &allowed_codes,
+                romanization_table,
+                protect_codes,
+                game_type,
+                engine_type,
+                &ProcessingMode::Default,
+                (code_label, parameters_label),
+                &mut lines,
+                &mut unused_map,
+            );
+        }
+    }
+
+    keys.extend(lines.iter().map(|line: &String| xxh3_64_with_seed(line.as_bytes(), 0)));
+    keys
+}
+
+/// Parses the original data file, or returns its kept value, if the server keeps parsed sources.
+fn parse_data_file(sources: Option<&Sources>, path: &Path, bytes: &[u8], engine_type: &EngineType) -> Value {
+    parse_source(sources, path, bytes, |bytes: &[u8]| {
+        if engine_type == EngineType::New {
+            from_slice(bytes).unwrap()
+        } else {
+            load(bytes, None, Some("")).unwrap()
+        }
+    })
+}
+
+/// Advances pos past whitespace of the original JSON text, copying it to the output.
+fn copy_json_whitespace(original: &[u8], pos: &mut usize, output: &mut Vec<u8>) {
+    let start: usize = *pos;
+
+    while *pos < original.len() && original[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+
+    output.extend_from_slice(&original[start..*pos]);
+}
+
+/// Advances pos past a single JSON value of the original text and returns its raw bytes.
+fn skip_json_value<'a>(original: &'a [u8], pos: &mut usize) -> &'a [u8] {
+    let start: usize = *pos;
+    let mut depth: usize = 0;
+    let mut in_string: bool = false;
+
+    while *pos < original.len() {
+        let byte: u8 = original[*pos];
+
+        if in_string {
+            match byte {
+                b'\\' => *pos += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+        } else {
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth == 0 => break,
+                b'}' | b']' => depth -= 1,
+                b',' | b':' if depth == 0 => break,
+                _ if depth == 0 && byte.is_ascii_whitespace() => break,
+                _ => {}
+            }
+        }
+
+        *pos += 1;
+
+        if depth == 0 && !in_string && matches!(byte, b'"' | b'}' | b']') {
+            break;
+        }
+    }
+
+    &original[start..*pos]
+}
+
+/// Copies the original JSON value to the output, re-serializing only the parts, that differ from the value.
+/// Returns false, if the structure of the value doesn't match the original, so it can't be patched.
+fn patch_json_value(original: &[u8], pos: &mut usize, value: &Value, output: &mut Vec<u8>) -> bool {
+    match original[*pos] {
+        b'{' => {
+            let Some(obj) = value.as_object() else {
+                return false;
+            };
+
+            let mut keys_count: usize = 0;
+
+            output.push(b'{');
+            *pos += 1;
+
+            loop {
+                copy_json_whitespace(original, pos, output);
+
+                match original[*pos] {
+                    b'}' => break,
+                    b',' => {
+                        output.push(b',');
+                        *pos += 1;
+                        copy_json_whitespace(original, pos, output);
+                    }
+                    _ => {}
+                }
+
+                let raw_key: &[u8] = skip_json_value(original, pos);
+                let key: String = from_slice(raw_key).unwrap();
+                output.extend_from_slice(raw_key);
+
+                copy_json_whitespace(original, pos, output);
+                output.push(b':');
+                *pos += 1;
+                copy_json_whitespace(original, pos, output);
+
+                let Some(inner_value) = obj.get(&key) else {
+                    return false;
+                };
+
+                patch_json_entry(original, pos, inner_value, output);
+                keys_count += 1;
+            }
+
+            output.push(b'}');
+            *pos += 1;
+
+            keys_count == obj.len()
+        }
+        b'[' => {
+            let Some(arr) = value.as_array() else {
+                return false;
+            };
+
+            let mut index: usize = 0;
+
+            output.push(b'[');
+            *pos += 1;
+
+            loop {
+                copy_json_whitespace(original, pos, output);
+
+                match original[*pos] {
+                    b']' => break,
+                    b',' => {
+                        output.push(b',');
+                        *pos += 1;
+                        copy_json_whitespace(original, pos, output);
+                    }
+                    _ => {}
+                }
+
+                let Some(inner_value) = arr.get(index) else {
+                    return false;
+                };
+
+                patch_json_entry(original, pos, inner_value, output);
+                index += 1;
+            }
+
+            output.push(b']');
+            *pos += 1;
+
+            index == arr.len()
+        }
+        _ => {
+            let raw: &[u8] = skip_json_value(original, pos);
+
+            // Comparing parsed values, so unchanged strings and numbers keep their original escapes and formatting
+            if from_slice::<Value>(raw).is_ok_and(|original_value: Value| original_value == *value) {
+                output.extend_from_slice(raw);
+            } else {
+                output.extend_from_slice(to_string(value).unwrap().as_bytes());
+            }
+
+            true
+        }
+    }
+}
+
+/// Patches a single JSON value, and if it can't be patched, replaces it with its serialized form.
+fn patch_json_entry(original: &[u8], pos: &mut usize, value: &Value, output: &mut Vec<u8>) {
+    let (start, output_len): (usize, usize) = (*pos, output.len());
+
+    if !patch_json_value(original, pos, value, output) {
+        *pos = start;
+        skip_json_value(original, pos);
+        output.truncate(output_len);
+        output.extend_from_slice(to_string(value).unwrap().as_bytes());
+    }
+}
+
+/// Serializes the value using the original JSON text, so that only changed values differ from the original,
+/// and formatting, key order, numbers and escapes of untouched parts remain byte-identical.
+/// # Parameters
+/// * `original` - original JSON text, from which value was parsed
+/// * `value` - changed value
+fn patch_json(original: &[u8], value: &Value) -> Vec<u8> {
+    let mut output: Vec<u8> = Vec::with_capacity(original.len());
+    let mut pos: usize = 0;
+
+    copy_json_whitespace(original, &mut pos, &mut output);
+    patch_json_entry(original, &mut pos, value, &mut output);
+    output.extend_from_slice(&original[pos..]);
+
+    output
+}
+
 /// Writes .txt files from maps folder back to their initial form.
 /// # Parameters
 /// * `maps_path` - path to the maps directory
 /// * `original_path` - path to the original directory
 /// * `output_path` - path to the output directory
-/// * `romanize` - if files were read with romanize, this option will romanize original game text to compare with parsed
+/// * `romanization_table` - if files were read with romanize, table to romanize original game text to compare with parsed
+/// * `protect_codes` - if files were read with protect codes, escape codes of event text are replaced with
+///   numbered placeholders to look up translation
+/// * `event_codes` - if files were read with event codes, codes of event commands, which text is replaced, or None,
+///   if text of all of them is
 /// * `shuffle_level` - level of shuffle
+/// * `pseudo_expansion` - if some, translation is replaced with pseudo-localized original text, which lines are
+///   expanded by this percent
 /// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_written_msg` - message to log when file is written
 /// * `game_type` - game type for custom parsing
+/// * `file_filter` - selects files, which are processed
+/// * `cache` - if some, maps which source and used translation entries are unchanged are skipped
+/// * `sources` - if some, parsed original data files are kept between writes
+/// * `preserve_formatting` - if true, JSON output keeps formatting of the original file, and only changed values are re-serialized
 pub fn write_maps(
     maps_path: &Path,
     original_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
     shuffle_level: u8,
+    pseudo_expansion: Option<u16>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_written_msg: &str,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
+    file_filter: &FileFilter,
+    cache: Option<&Cache>,
+    sources: Option<&Sources>,
+    preserve_formatting: bool,
 ) {
+    let mut translation_hashes: HashMap<u64, u64, BuildHasherDefault<Xxh3>> = HashMap::default();
+
+    if cache.is_some() {
+        hash_translation_pairs(
+            &read_to_string(maps_path.join("maps.txt")).unwrap(),
+            &read_to_string(maps_path.join("maps_trans.txt")).unwrap(),
+            0,
+            &mut translation_hashes,
+        );
+        hash_translation_pairs(
+            &read_to_string(maps_path.join("names.txt")).unwrap(),
+            &read_to_string(maps_path.join("names_trans.txt")).unwrap(),
+            NAMES_SEED,
+            &mut translation_hashes,
+        );
+    }
+
+    let labels: (&str, &str, &str, &str, &str, &str) = if engine_type == EngineType::New {
+        ("displayName", "events", "pages", "list", "code", "parameters")
+    } else {
+        (
+            "__symbol__display_name",
+            "__symbol__events",
+            "__symbol__pages",
+            "__symbol__list",
+            "__symbol__code",
+            "__symbol__parameters",
+        )
+    };
+    let (display_name_label, events_label, pages_label, list_label, code_label, parameters_label) = labels;
+
     let maps_obj_vec =
         read_dir(original_path)
             .unwrap()
@@ -567,14 +1001,62 @@ pub fn write_maps(
                             || filename_str.ends_with("rvdata2")
                             || filename_str.ends_with("rvdata")
                             || filename_str.ends_with("rxdata"))
+                        && file_filter.matches(filename_str)
                     {
-                        let json: Value = if engine_type == EngineType::New {
-                            from_str(&read_to_string(entry.path()).unwrap()).unwrap()
-                        } else {
-                            load(&read(entry.path()).unwrap(), None, Some("")).unwrap()
-                        };
+                        let bytes: Vec<u8> = read(entry.path()).unwrap();
+                        let source_hash: u64 = cache.map_or(0, |cache: &Cache| cache.hash_source(&bytes));
+
+                        if let Some(cache) = cache {
+                            if let Some(cached) = cache.get(&format!("write/{filename_str}")) {
+                                if cached.source == source_hash
+                                    && cached.translation == hash_keys_translation(&cached.keys, &translation_hashes)
+                                    && output_path.join(filename_str).exists()
+                                {
+                                    progress.advance();
+
+                                    if output.is_json() {
+                                        output.emit(Event::FileSkipped {
+                                            file: filename_str,
+                                            reason: SkipReason::Unchanged,
+                                        });
+                                    }
 
-                        Some((filename_str.to_string(), json))
+                                    return None;
+                                }
+                            }
+                        }
+
+                        if output.is_json() {
+                            output.emit(Event::FileStarted { file: filename_str });
+                        }
+
+                        let json: Value = parse_data_file(sources, &entry.path(), &bytes, engine_type);
+
+                        // Entry is recorded only after the file is written, so a failed write isn't skipped later
+                        let cache_entry: Option<CacheEntry> = cache.map(|_| {
+                            let keys: Vec<u64> = collect_map_keys(
+                                &json,
+                                romanization_table,
+                                protect_codes,
+                                event_codes,
+                                game_type,
+                                engine_type,
+                                labels,
+                            );
+
+                            CacheEntry {
+                                source: source_hash,
+                                translation: hash_keys_translation(&keys, &translation_hashes),
+                                keys,
+                            }
+                        });
+
+                        Some((
+                            filename_str.to_string(),
+                            json,
+                            preserve_formatting.then_some(bytes),
+                            cache_entry,
+                        ))
                     } else {
                         None
                     }
@@ -607,7 +1089,16 @@ pub fn write_maps(
         .map(|line: &str| line.trim().to_string())
         .collect();
 
-    if shuffle_level > 0 {
+    if let Some(expansion) = pseudo_expansion {
+        maps_translated_text_vec = maps_original_text_vec
+            .par_iter()
+            .map(|text: &String| pseudolocalize(text, expansion))
+            .collect();
+        names_translated_text_vec = names_original_text_vec
+            .par_iter()
+            .map(|text: &String| pseudolocalize(text, expansion))
+            .collect();
+    } else if shuffle_level > 0 {
         shuffle(&mut maps_translated_text_vec);
         shuffle(&mut names_translated_text_vec);
 
@@ -650,86 +1141,91 @@ pub fn write_maps(
     // 356 - system lines (special texts)
     // 324, 320 - i don't know what is it but it's some used in-game lines
     const ALLOWED_CODES: [u16; 6] = [102, 320, 324, 356, 401, 402];
+    let allowed_codes: Vec<u16> = filter_event_codes(&ALLOWED_CODES, event_codes);
 
-    let (display_name_label, events_label, pages_label, list_label, code_label, parameters_label) =
-        if engine_type == EngineType::New {
-            ("displayName", "events", "pages", "list", "code", "parameters")
-        } else {
-            (
-                "__symbol__display_name",
-                "__symbol__events",
-                "__symbol__pages",
-                "__symbol__list",
-                "__symbol__code",
-                "__symbol__parameters",
-            )
-        };
+    maps_obj_vec
+        .into_par_iter()
+        .for_each(|(filename, mut obj, original, cache_entry)| {
+            if let Some(display_name) = obj[display_name_label].as_str() {
+                let mut display_name: String = display_name.to_string();
 
-    maps_obj_vec.into_par_iter().for_each(|(filename, mut obj)| {
-        if let Some(display_name) = obj[display_name_label].as_str() {
-            let mut display_name: String = display_name.to_string();
+                if let Some(table) = romanization_table {
+                    display_name = romanize_string(display_name, table)
+                }
 
-            if romanize {
-                display_name = romanize_string(display_name)
+                if let Some(location_name) = names_translation_map.get(&display_name) {
+                    if !location_name.is_empty() {
+                        obj[display_name_label] = Value::from(location_name);
+                    }
+                }
             }
 
-            if let Some(location_name) = names_translation_map.get(&display_name) {
-                obj[display_name_label] = Value::from(location_name);
-            }
-        }
+            // Skipping first element in array as it is null
+            let mut events_arr: Vec<&mut Value> = if engine_type == EngineType::New {
+                obj[events_label]
+                    .as_array_mut()
+                    .unwrap()
+                    .par_iter_mut()
+                    .skip(1)
+                    .collect()
+            } else {
+                obj[events_label]
+                    .as_object_mut()
+                    .unwrap()
+                    .iter_mut()
+                    .par_bridge()
+                    .map(|(_, value)| value)
+                    .collect()
+            };
 
-        // Skipping first element in array as it is null
-        let mut events_arr: Vec<&mut Value> = if engine_type == EngineType::New {
-            obj[events_label]
-                .as_array_mut()
-                .unwrap()
-                .par_iter_mut()
-                .skip(1)
-                .collect()
-        } else {
-            obj[events_label]
-                .as_object_mut()
-                .unwrap()
-                .iter_mut()
-                .par_bridge()
-                .map(|(_, value)| value)
-                .collect()
-        };
+            events_arr.par_iter_mut().for_each(|event: &mut &mut Value| {
+                if event.is_null() {
+                    return;
+                }
 
-        events_arr.par_iter_mut().for_each(|event: &mut &mut Value| {
-            if event.is_null() {
-                return;
-            }
+                event[pages_label]
+                    .as_array_mut()
+                    .unwrap()
+                    .par_iter_mut()
+                    .for_each(|page: &mut Value| {
+                        write_list(
+                            page[list_label].as_array_mut().unwrap(),
+                            &allowed_codes,
+                            romanization_table,
+                            protect_codes,
+                            game_type,
+                            engine_type,
+                            &maps_translation_map,
+                            (code_label, parameters_label),
+                        );
+                    });
+            });
 
-            event[pages_label]
-                .as_array_mut()
-                .unwrap()
-                .par_iter_mut()
-                .for_each(|page: &mut Value| {
-                    write_list(
-                        page[list_label].as_array_mut().unwrap(),
-                        &ALLOWED_CODES,
-                        romanize,
-                        game_type,
-                        engine_type,
-                        &maps_translation_map,
-                        (code_label, parameters_label),
-                    );
-                });
-        });
+            let output_data: Vec<u8> = if let Some(original) = original {
+                patch_json(&original, &obj)
+            } else if engine_type == EngineType::New {
+                to_string(&obj).unwrap().into_bytes()
+            } else {
+                dump(obj, Some(""))
+            };
 
-        let output_data: Vec<u8> = if engine_type == EngineType::New {
-            to_string(&obj).unwrap().into_bytes()
-        } else {
-            dump(obj, Some(""))
-        };
+            progress.advance();
 
-        if logging {
-            println!("{file_written_msg} {filename}");
-        }
+            if output.is_json() {
+                output.emit(Event::FileWritten {
+                    file: &filename,
+                    bytes: output_data.len(),
+                });
+            } else if logging {
+                println!("{file_written_msg} {filename}");
+            }
 
-        write(output_path.join(filename), output_data).unwrap();
-    });
+            write(output_path.join(&filename), output_data).unwrap();
+
+            if let (Some(cache), Some(cache_entry)) = (cache, cache_entry) {
+                cache.insert(format!("write/{filename}"), cache_entry);
+            }
+        });
 }
 
 /// Writes .txt files from other folder back to their initial form.
@@ -737,23 +1233,55 @@ pub fn write_maps(
 /// * `other_path` - path to the other directory
 /// * `original_path` - path to the original directory
 /// * `output_path` - path to the output directory
-/// * `romanize` - if files were read with romanize, this option will romanize original game text to compare with parsed
+/// * `romanization_table` - if files were read with romanize, table to romanize original game text to compare with parsed
+/// * `protect_codes` - if files were read with protect codes, escape codes of event text are replaced with
+///   numbered placeholders to look up translation
+/// * `event_codes` - if files were read with event codes, codes of event commands, which text is replaced, or None,
+///   if text of all of them is
+/// * `note_tag_rules` - if files were read with note tag rules, only contents of these tags are replaced in notes
 /// * `shuffle_level` - level of shuffle
+/// * `pseudo_expansion` - if some, translation is replaced with pseudo-localized original text, which lines are
+///   expanded by this percent
 /// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_written_msg` - message to log when file is written
 /// * `game_type` - game type for custom parsing
+/// * `file_filter` - selects files, which are processed
+/// * `cache` - if some, files which source and translation files are unchanged are skipped
+/// * `sources` - if some, parsed original data files are kept between writes
+/// * `preserve_formatting` - if true, JSON output keeps formatting of the original file, and only changed values are re-serialized
 pub fn write_other(
     other_path: &Path,
     original_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    protect_codes: bool,
+    event_codes: Option<&[u16]>,
+    note_tag_rules: &[NoteTagRule],
     shuffle_level: u8,
+    pseudo_expansion: Option<u16>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_written_msg: &str,
     game_type: Option<&GameType>,
     engine_type: &EngineType,
+    file_filter: &FileFilter,
+    cache: Option<&Cache>,
+    sources: Option<&Sources>,
+    preserve_formatting: bool,
 ) {
-    let other_obj_arr_vec =
+    let processed_filename_length = |filename: &str| -> usize {
+        filename.len()
+            - match engine_type {
+                EngineType::New => 5,
+                EngineType::VXAce => 8,
+                EngineType::VX | EngineType::XP => 7,
+            }
+    };
+
+    let other_obj =
         read_dir(original_path)
             .unwrap()
             .par_bridge()
@@ -766,6 +1294,7 @@ pub fn write_other(
                     if !real_name.starts_with("Map")
                         && !matches!(real_name, "Tilesets" | "Animations" | "System" | "Scripts")
                         && ["json", "rvdata2", "rvdata", "rxdata"].contains(&extension)
+                        && file_filter.matches(filename)
                     {
                         if game_type.is_some_and(|game_type: &GameType| game_type == GameType::Termina)
                             && real_name == "States"
@@ -773,13 +1302,55 @@ pub fn write_other(
                             return None;
                         }
 
-                        let json: Value = if engine_type == EngineType::New {
-                            from_str(&read_to_string(entry.path()).unwrap()).unwrap()
-                        } else {
-                            load(&read(entry.path()).unwrap(), None, Some("")).unwrap()
-                        };
+                        let bytes: Vec<u8> = read(entry.path()).unwrap();
+                        let mut cache_entry: Option<CacheEntry> = None;
+
+                        if let Some(cache) = cache {
+                            let other_processed_filename: String =
+                                filename[..processed_filename_length(filename)].to_lowercase();
+
+                            let key: String = format!("write/{filename}");
+                            let source_hash: u64 = cache.hash_source(&bytes);
+                            let translation_hash: u64 = hash_files(&[
+                                &other_path.join(format!("{other_processed_filename}.txt")),
+                                &other_path.join(format!("{other_processed_filename}_trans.txt")),
+                            ]);
+
+                            if cache.is_unchanged(&key, source_hash, translation_hash)
+                                && output_path.join(filename).exists()
+                            {
+                                progress.advance();
+
+                                if output.is_json() {
+                                    output.emit(Event::FileSkipped {
+                                        file: filename,
+                                        reason: SkipReason::Unchanged,
+                                    });
+                                }
+
+                                return None;
+                            }
 
-                        Some((filename.to_string(), json))
+                            // Entry is recorded only after the file is written, so a failed write isn't skipped later
+                            cache_entry = Some(CacheEntry {
+                                source: source_hash,
+                                translation: translation_hash,
+                                keys: Vec::new(),
+                            });
+                        }
+
+                        if output.is_json() {
+                            output.emit(Event::FileStarted { file: filename });
+                        }
+
+                        let json: Value = parse_data_file(sources, &entry.path(), &bytes, engine_type);
+
+                        Some((
+                            filename.to_string(),
+                            json,
+                            preserve_formatting.then_some(bytes),
+                            cache_entry,
+                        ))
                     } else {
                         None
                     }
@@ -795,201 +1366,268 @@ pub fn write_other(
     // 356 - system lines (special texts)
     // 324, 320 - i don't know what is it but it's some used in-game lines
     const ALLOWED_CODES: [u16; 7] = [102, 320, 324, 356, 401, 402, 405];
+    let allowed_codes: Vec<u16> = filter_event_codes(&ALLOWED_CODES, event_codes);
 
-    other_obj_arr_vec.into_par_iter().for_each(|(filename, mut obj_arr)| {
-        let other_processed_filename: &str = &filename[..filename.len()
-            - match engine_type {
-                EngineType::New => 5,
-                EngineType::VXAce => 8,
-                EngineType::VX | EngineType::XP => 7,
-            }];
-
-        let other_original_text: Vec<String> =
-            read_to_string(other_path.join(format!("{other_processed_filename}.txt")))
-                .unwrap()
-                .par_split('\n')
-                .map(|line: &str| line.replace(r"\#", "\n").trim().to_string())
-                .collect();
-
-        let mut other_translated_text: Vec<String> =
-            read_to_string(other_path.join(format!("{other_processed_filename}_trans.txt")))
-                .unwrap()
-                .par_split('\n')
-                .map(|line: &str| line.replace(r"\#", "\n").trim().to_string())
-                .collect();
+    other_obj
+        .into_par_iter()
+        .for_each(|(filename, mut obj_arr, original, cache_entry)| {
+            let other_processed_filename: String = filename[..processed_filename_length(&filename)].to_lowercase();
 
-        if shuffle_level > 0 {
-            shuffle(&mut other_translated_text);
+            let other_original_text: Vec<String> =
+                read_to_string(other_path.join(format!("{other_processed_filename}.txt")))
+                    .unwrap()
+                    .par_split('\n')
+                    .map(|line: &str| line.replace(r"\#", "\n").trim().to_string())
+                    .collect();
 
-            if shuffle_level == 2 {
-                for translated_text in other_translated_text.iter_mut() {
-                    *translated_text = shuffle_words(translated_text);
+            let mut other_translated_text: Vec<String> =
+                read_to_string(other_path.join(format!("{other_processed_filename}_trans.txt")))
+                    .unwrap()
+                    .par_split('\n')
+                    .map(|line: &str| line.replace(r"\#", "\n").trim().to_string())
+                    .collect();
+
+            if let Some(expansion) = pseudo_expansion {
+                other_translated_text = other_original_text
+                    .par_iter()
+                    .map(|text: &String| pseudolocalize(text, expansion))
+                    .collect();
+            } else if shuffle_level > 0 {
+                shuffle(&mut other_translated_text);
+
+                if shuffle_level == 2 {
+                    for translated_text in other_translated_text.iter_mut() {
+                        *translated_text = shuffle_words(translated_text);
+                    }
                 }
             }
-        }
 
-        let other_translation_map: HashMap<String, String, BuildHasherDefault<Xxh3>> = other_original_text
-            .into_par_iter()
-            .zip(other_translated_text.into_par_iter())
-            .fold(
-                HashMap::default,
-                |mut map: HashMap<String, String, BuildHasherDefault<Xxh3>>, (key, value): (String, String)| {
-                    map.insert(key, value);
-                    map
-                },
-            )
-            .reduce(HashMap::default, |mut a, b| {
-                a.extend(b);
-                a
-            });
+            let other_translation_map: HashMap<String, String, BuildHasherDefault<Xxh3>> = other_original_text
+                .into_par_iter()
+                .zip(other_translated_text.into_par_iter())
+                .fold(
+                    HashMap::default,
+                    |mut map: HashMap<String, String, BuildHasherDefault<Xxh3>>, (key, value): (String, String)| {
+                        map.insert(key, value);
+                        map
+                    },
+                )
+                .reduce(HashMap::default, |mut a, b| {
+                    a.extend(b);
+                    a
+                });
 
-        // Other files except CommonEvents and Troops have the structure that consists
-        // of name, nickname, description and note
-        if !filename.starts_with("Co") && !filename.starts_with("Tr") {
-            let variable_tuples: Arc<[(&str, Variable); 8]> = Arc::new(if engine_type == EngineType::New {
-                [
-                    ("name", Variable::Name),
-                    ("nickname", Variable::Nickname),
-                    ("description", Variable::Description),
-                    ("message1", Variable::Message1),
-                    ("message2", Variable::Message2),
-                    ("message3", Variable::Message3),
-                    ("message4", Variable::Message4),
-                    ("note", Variable::Note),
-                ]
-            } else {
-                [
-                    ("__symbol__name", Variable::Name),
-                    ("__symbol__nickname", Variable::Nickname),
-                    ("__symbol__description", Variable::Description),
-                    ("__symbol__message1", Variable::Message1),
-                    ("__symbol__message2", Variable::Message2),
-                    ("__symbol__message3", Variable::Message3),
-                    ("__symbol__message4", Variable::Message4),
-                    ("__symbol__note", Variable::Note),
-                ]
-            });
+            // Other files except CommonEvents and Troops have the structure that consists
+            // of name, nickname, description and note
+            if !filename.starts_with("Co") && !filename.starts_with("Tr") {
+                let variable_tuples: Arc<[(&str, Variable); 8]> = Arc::new(if engine_type == EngineType::New {
+                    [
+                        ("name", Variable::Name),
+                        ("nickname", Variable::Nickname),
+                        ("description", Variable::Description),
+                        ("message1", Variable::Message1),
+                        ("message2", Variable::Message2),
+                        ("message3", Variable::Message3),
+                        ("message4", Variable::Message4),
+                        ("note", Variable::Note),
+                    ]
+                } else {
+                    [
+                        ("__symbol__name", Variable::Name),
+                        ("__symbol__nickname", Variable::Nickname),
+                        ("__symbol__description", Variable::Description),
+                        ("__symbol__message1", Variable::Message1),
+                        ("__symbol__message2", Variable::Message2),
+                        ("__symbol__message3", Variable::Message3),
+                        ("__symbol__message4", Variable::Message4),
+                        ("__symbol__note", Variable::Note),
+                    ]
+                });
 
-            obj_arr
-                .as_array_mut()
-                .unwrap()
-                .par_iter_mut()
-                .skip(1) // Skipping first element in array as it is null
-                .for_each(|obj: &mut Value| {
-                    for (variable_label, variable_type) in variable_tuples.into_iter() {
-                        if let Some(variable_str) = obj[variable_label].as_str() {
-                            let mut variable_string: String = if variable_type != Variable::Note {
-                                variable_str.trim().to_string()
-                            } else {
-                                variable_str.to_string()
-                            };
+                obj_arr
+                    .as_array_mut()
+                    .unwrap()
+                    .par_iter_mut()
+                    .skip(1) // Skipping first element in array as it is null
+                    .for_each(|obj: &mut Value| {
+                        for (variable_label, variable_type) in variable_tuples.into_iter() {
+                            if let Some(variable_str) = obj[variable_label].as_str() {
+                                let mut variable_string: String = if variable_type != Variable::Note {
+                                    variable_str.trim().to_string()
+                                } else {
+                                    variable_str.to_string()
+                                };
 
-                            if !variable_string.is_empty() {
-                                if romanize {
-                                    variable_string = romanize_string(variable_string)
-                                }
+                                // Notes with translatable tags are spliced as they are, so other tags aren't touched
+                                let is_selective_note: bool =
+                                    variable_type == Variable::Note && !note_tag_rules.is_empty();
 
-                                variable_string = variable_string
-                                    .split('\n')
-                                    .map(|line: &str| line.trim())
-                                    .collect::<Vec<_>>()
-                                    .join("\n");
+                                if !variable_string.is_empty() {
+                                    if !is_selective_note {
+                                        if let Some(table) = romanization_table {
+                                            variable_string = romanize_string(variable_string, table)
+                                        }
 
-                                let note_text: Option<&str> = if game_type
-                                    .is_some_and(|game_type: &GameType| game_type != GameType::Termina)
-                                    && variable_type != Variable::Description
-                                {
-                                    None
-                                } else {
-                                    match obj.get(if engine_type == EngineType::New {
-                                        "note"
+                                        variable_string = variable_string
+                                            .split('\n')
+                                            .map(|line: &str| line.trim())
+                                            .collect::<Vec<_>>()
+                                            .join("\n");
+                                    }
+
+                                    let note_text: Option<&str> = if game_type
+                                        .is_some_and(|game_type: &GameType| game_type != GameType::Termina)
+                                        && variable_type != Variable::Description
+                                    {
+                                        None
                                     } else {
-                                        "__symbol__note"
-                                    }) {
-                                        Some(value) => value.as_str(),
-                                        None => None,
+                                        match obj.get(if engine_type == EngineType::New {
+                                            "note"
+                                        } else {
+                                            "__symbol__note"
+                                        }) {
+                                            Some(value) => value.as_str(),
+                                            None => None,
+                                        }
+                                    };
+
+                                    let translated: Option<String> = get_translated_variable(
+                                        variable_string,
+                                        note_text,
+                                        variable_type,
+                                        &filename,
+                                        &other_translation_map,
+                                        game_type,
+                                        engine_type,
+                                        romanization_table,
+                                        note_tag_rules,
+                                    );
+
+                                    if let Some(translated) = translated {
+                                        obj[variable_label] = Value::from(&translated);
                                     }
-                                };
+                                }
+                            }
+                        }
+                    });
+            } else {
+                let (pages_label, list_label, code_label, parameters_label) = if engine_type == EngineType::New {
+                    ("pages", "list", "code", "parameters")
+                } else {
+                    (
+                        "__symbol__pages",
+                        "__symbol__list",
+                        "__symbol__code",
+                        "__symbol__parameters",
+                    )
+                };
+
+                // Other files have the structure somewhat similar to Maps files
+                obj_arr
+                    .as_array_mut()
+                    .unwrap()
+                    .par_iter_mut()
+                    .skip(1) // Skipping first element in array as it is null
+                    .for_each(|obj: &mut Value| {
+                        // CommonEvents doesn't have pages, so we can just check if it's Troops
+                        let pages_length: usize = if filename.starts_with("Troops") {
+                            obj[pages_label].as_array().unwrap().len()
+                        } else {
+                            1
+                        };
 
-                                let translated: Option<String> = get_translated_variable(
-                                    variable_string,
-                                    note_text,
-                                    variable_type,
-                                    &filename,
-                                    &other_translation_map,
+                        for i in 0..pages_length {
+                            // If element has pages, then we'll iterate over them
+                            // Otherwise we'll just iterate over the list
+                            let list_value: &mut Value = if pages_length != 1 {
+                                &mut obj[pages_label][i][list_label]
+                            } else {
+                                &mut obj[list_label]
+                            };
+
+                            if let Some(list) = list_value.as_array_mut() {
+                                write_list(
+                                    list,
+                                    &allowed_codes,
+                                    romanization_table,
+                                    protect_codes,
                                     game_type,
                                     engine_type,
+                                    &other_translation_map,
+                                    (code_label, parameters_label),
                                 );
-
-                                if let Some(translated) = translated {
-                                    obj[variable_label] = Value::from(&translated);
-                                }
                             }
                         }
-                    }
-                });
-        } else {
-            let (pages_label, list_label, code_label, parameters_label) = if engine_type == EngineType::New {
-                ("pages", "list", "code", "parameters")
+                    });
+            }
+
+            let output_data: Vec<u8> = if let Some(original) = original {
+                patch_json(&original, &obj_arr)
+            } else if engine_type == EngineType::New {
+                to_string(&obj_arr).unwrap().into_bytes()
             } else {
-                (
-                    "__symbol__pages",
-                    "__symbol__list",
-                    "__symbol__code",
-                    "__symbol__parameters",
-                )
+                dump(obj_arr, Some(""))
             };
 
-            // Other files have the structure somewhat similar to Maps files
-            obj_arr
-                .as_array_mut()
-                .unwrap()
-                .par_iter_mut()
-                .skip(1) // Skipping first element in array as it is null
-                .for_each(|obj: &mut Value| {
-                    // CommonEvents doesn't have pages, so we can just check if it's Troops
-                    let pages_length: usize = if filename.starts_with("Troops") {
-                        obj[pages_label].as_array().unwrap().len()
-                    } else {
-                        1
-                    };
-
-                    for i in 0..pages_length {
-                        // If element has pages, then we'll iterate over them
-                        // Otherwise we'll just iterate over the list
-                        let list_value: &mut Value = if pages_length != 1 {
-                            &mut obj[pages_label][i][list_label]
-                        } else {
-                            &mut obj[list_label]
-                        };
+            progress.advance();
 
-                        if let Some(list) = list_value.as_array_mut() {
-                            write_list(
-                                list,
-                                &ALLOWED_CODES,
-                                romanize,
-                                game_type,
-                                engine_type,
-                                &other_translation_map,
-                                (code_label, parameters_label),
-                            );
-                        }
-                    }
+            if output.is_json() {
+                output.emit(Event::FileWritten {
+                    file: &filename,
+                    bytes: output_data.len(),
                 });
-        }
+            } else if logging {
+                println!("{file_written_msg} {filename}");
+            }
 
-        let output_data: Vec<u8> = if engine_type == EngineType::New {
-            to_string(&obj_arr).unwrap().into_bytes()
-        } else {
-            dump(obj_arr, Some(""))
-        };
+            write(output_path.join(&filename), output_data).unwrap();
 
-        if logging {
-            println!("{file_written_msg} {filename}");
-        }
+            if let (Some(cache), Some(cache_entry)) = (cache, cache_entry) {
+                cache.insert(format!("write/{filename}"), cache_entry);
+            }
+        });
+}
 
-        write(output_path.join(filename), output_data).unwrap();
-    });
+/// Returns the game title from the system object, trimmed and romanized the same way `read_system` extracts it.
+/// # Parameters
+/// * `system_obj` - parsed original system file
+/// * `engine_type` - engine type, determines the title key
+/// * `romanization_table` - if files were read with romanize, table to romanize the title
+fn get_game_title(
+    system_obj: &Value,
+    engine_type: &EngineType,
+    romanization_table: Option<&RomanizationTable>,
+) -> String {
+    let game_title_label: &str = if engine_type == EngineType::New {
+        "gameTitle"
+    } else {
+        "__symbol__game_title"
+    };
+
+    let game_title: String = system_obj[game_title_label]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    match romanization_table {
+        Some(table) => romanize_string(game_title, table),
+        None => game_title,
+    }
+}
+
+/// Returns the translation of the original line, or an empty string if it isn't in the file or isn't translated.
+/// # Parameters
+/// * `original` - original line to look up
+/// * `original_lines` - lines of the original text file
+/// * `translated_lines` - lines of the translation file
+fn get_translation(original: &str, original_lines: &[String], translated_lines: &[String]) -> String {
+    original_lines
+        .iter()
+        .position(|line: &String| line == original)
+        .and_then(|index: usize| translated_lines.get(index))
+        .cloned()
+        .unwrap_or_default()
 }
 
 /// Writes system.txt file back to its initial form.
@@ -999,25 +1637,64 @@ pub fn write_other(
 /// * `system_file_path` - path to the original system file
 /// * `other_path` - path to the other directory
 /// * `output_path` - path to the output directory
-/// * `romanize` - if files were read with romanize, this option will romanize original game text to compare with parsed
+/// * `romanization_table` - if files were read with romanize, table to romanize original game text to compare with parsed
 /// * `shuffle_level` - level of shuffle
+/// * `pseudo_expansion` - if some, translation is replaced with pseudo-localized original text, which lines are
+///   expanded by this percent
 /// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_written_msg` - message to log when file is written
+/// * `cache` - if some, file is skipped when its source and translation files are unchanged
+/// * `sources` - if some, parsed original data files are kept between writes
+/// * `preserve_formatting` - if true, JSON output keeps formatting of the original file, and only changed values are re-serialized
 pub fn write_system(
     system_file_path: &Path,
     other_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
     shuffle_level: u8,
+    pseudo_expansion: Option<u16>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_written_msg: &str,
     engine_type: &EngineType,
+    cache: Option<&Cache>,
+    sources: Option<&Sources>,
+    preserve_formatting: bool,
 ) {
-    let mut system_obj: Value = if engine_type == EngineType::New {
-        from_str(&read_to_string(system_file_path).unwrap()).unwrap()
-    } else {
-        load(&read(system_file_path).unwrap(), None, Some("")).unwrap()
-    };
+    let bytes: Vec<u8> = read(system_file_path).unwrap();
+    let mut cache_entry: Option<CacheEntry> = None;
+
+    if let Some(cache) = cache {
+        let source_hash: u64 = cache.hash_source(&bytes);
+        let translation_hash: u64 = hash_files(&[&other_path.join("system.txt"), &other_path.join("system_trans.txt")]);
+
+        if cache.is_unchanged("write/system", source_hash, translation_hash)
+            && output_path.join(system_file_path.file_name().unwrap()).exists()
+        {
+            progress.advance();
+
+            if output.is_json() {
+                output.emit(Event::FileSkipped {
+                    file: &system_file_path.file_name().unwrap().to_string_lossy(),
+                    reason: SkipReason::Unchanged,
+                });
+            }
+
+            return;
+        }
+
+        // Entry is recorded only after the file is written, so a failed write isn't skipped later
+        cache_entry = Some(CacheEntry {
+            source: source_hash,
+            translation: translation_hash,
+            keys: Vec::new(),
+        });
+    }
+
+    let mut system_obj: Value = parse_data_file(sources, system_file_path, &bytes, engine_type);
 
     let system_original_text: Vec<String> = read_to_string(other_path.join("system.txt"))
         .unwrap()
@@ -1025,21 +1702,27 @@ pub fn write_system(
         .map(|line: &str| line.trim().to_string())
         .collect();
 
-    let system_translated_text: (String, String) = read_to_string(other_path.join("system_trans.txt"))
+    let mut system_translated_text: Vec<String> = read_to_string(other_path.join("system_trans.txt"))
         .unwrap()
-        .rsplit_once('\n')
-        .map(|(left, right)| (left.to_string(), right.to_string()))
-        .unwrap();
-
-    let game_title: String = system_translated_text.1;
-
-    let mut system_translated_text: Vec<String> = system_translated_text
-        .0
         .par_split('\n')
         .map(|line: &str| line.trim().to_string())
         .collect();
 
-    if shuffle_level > 0 {
+    let original_game_title: String = get_game_title(&system_obj, engine_type, romanization_table);
+
+    // Title is looked up before shuffling, so it stays the same
+    let game_title: String = if let Some(expansion) = pseudo_expansion {
+        pseudolocalize(&original_game_title, expansion)
+    } else {
+        get_translation(&original_game_title, &system_original_text, &system_translated_text)
+    };
+
+    if let Some(expansion) = pseudo_expansion {
+        system_translated_text = system_original_text
+            .par_iter()
+            .map(|text: &String| pseudolocalize(text, expansion))
+            .collect();
+    } else if shuffle_level > 0 {
         shuffle(&mut system_translated_text);
 
         if shuffle_level == 2 {
@@ -1096,8 +1779,8 @@ pub fn write_system(
             .trim()
             .to_string();
 
-        if romanize {
-            string = romanize_string(string);
+        if let Some(table) = romanization_table {
+            string = romanize_string(string, table);
         }
 
         if let Some(translated) = system_translation_map.get(&string) {
@@ -1114,8 +1797,8 @@ pub fn write_system(
         .for_each(|value: &mut Value| {
             let mut string: String = value.as_str().unwrap().trim().to_string();
 
-            if romanize {
-                string = romanize_string(string);
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table);
             }
 
             if let Some(translated) = system_translation_map.get(&string) {
@@ -1134,8 +1817,8 @@ pub fn write_system(
         .for_each(|value: &mut Value| {
             let mut string: String = value.as_str().unwrap().trim().to_string();
 
-            if romanize {
-                string = romanize_string(string);
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table);
             }
 
             if let Some(translated) = system_translation_map.get(&string) {
@@ -1155,8 +1838,8 @@ pub fn write_system(
             .for_each(|value: &mut Value| {
                 let mut string: String = value.as_str().unwrap().trim().to_string();
 
-                if romanize {
-                    string = romanize_string(string);
+                if let Some(table) = romanization_table {
+                    string = romanize_string(string, table);
                 }
 
                 if let Some(translated) = system_translation_map.get(&string) {
@@ -1176,8 +1859,8 @@ pub fn write_system(
         .for_each(|value: &mut Value| {
             let mut string: String = value.as_str().unwrap().trim().to_string();
 
-            if romanize {
-                string = romanize_string(string);
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table);
             }
 
             if let Some(translated) = system_translation_map.get(&string) {
@@ -1207,8 +1890,8 @@ pub fn write_system(
                         if let Some(str) = subvalue.as_str() {
                             let mut string: String = str.trim().to_string();
 
-                            if romanize {
-                                string = romanize_string(string);
+                            if let Some(table) = romanization_table {
+                                string = romanize_string(string, table);
                             }
 
                             if let Some(translated) = system_translation_map.get(&string) {
@@ -1228,8 +1911,8 @@ pub fn write_system(
                 value.as_object_mut().unwrap().iter_mut().for_each(|(_, value)| {
                     let mut string: String = value.as_str().unwrap().trim().to_string();
 
-                    if romanize {
-                        string = romanize_string(string)
+                    if let Some(table) = romanization_table {
+                        string = romanize_string(string, table)
                     }
 
                     if let Some(translated) = system_translation_map.get(&string) {
@@ -1250,8 +1933,8 @@ pub fn write_system(
         .for_each(|value: &mut Value| {
             let mut string: String = value.as_str().unwrap().trim().to_string();
 
-            if romanize {
-                string = romanize_string(string);
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table);
             }
 
             if let Some(translated) = system_translation_map.get(&string) {
@@ -1263,19 +1946,201 @@ pub fn write_system(
             }
         });
 
-    system_obj[game_title_label] = Value::from(&game_title);
+    if !game_title.is_empty() {
+        system_obj[game_title_label] = Value::from(&game_title);
+    }
 
-    let output_data: Vec<u8> = if engine_type == EngineType::New {
+    let output_data: Vec<u8> = if preserve_formatting {
+        patch_json(&bytes, &system_obj)
+    } else if engine_type == EngineType::New {
         to_string(&system_obj).unwrap().into_bytes()
     } else {
         dump(system_obj, Some(""))
     };
 
-    if logging {
+    progress.advance();
+
+    if output.is_json() {
+        output.emit(Event::FileWritten {
+            file: &system_file_path.file_name().unwrap().to_string_lossy(),
+            bytes: output_data.len(),
+        });
+    } else if logging {
         println!("{file_written_msg} {}", system_file_path.display());
     }
 
     write(output_path.join(system_file_path.file_name().unwrap()), output_data).unwrap();
+
+    if let (Some(cache), Some(cache_entry)) = (cache, cache_entry) {
+        cache.insert(String::from("write/system"), cache_entry);
+    }
+}
+
+/// Encodings of legacy Game.ini files. Engine reads Game.ini in the system code page of the player, so the file is
+/// written in its own encoding, and only if it can't represent the translated title, in the first of these, which can.
+const INI_ENCODINGS: [&Encoding; 4] = [
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::GB18030,
+];
+
+/// Decodes Game.ini content. Returns the encoding, which the file is stored in, or None, if it's ASCII, and any of
+/// the encodings can be used. Some of the modified engines use UTF-8.
+pub fn decode_ini(bytes: &[u8]) -> (String, Option<&'static Encoding>) {
+    if let Ok(content) = std::str::from_utf8(bytes) {
+        return (content.to_string(), (!bytes.is_ascii()).then_some(encoding_rs::UTF_8));
+    }
+
+    // Single-byte code pages accept almost any input, so multibyte ones are tried first
+    for encoding in [
+        encoding_rs::SHIFT_JIS,
+        encoding_rs::GB18030,
+        encoding_rs::WINDOWS_1251,
+        encoding_rs::WINDOWS_1252,
+    ] {
+        let (content, had_errors) = encoding.decode_without_bom_handling(bytes);
+
+        if !had_errors {
+            return (content.into_owned(), Some(encoding));
+        }
+    }
+
+    (String::from_utf8_lossy(bytes).into_owned(), None)
+}
+
+/// Encodes Game.ini content in the encoding of the original file, or in the first of `INI_ENCODINGS`, that can
+/// represent the content.
+/// # Parameters
+/// * `content` - content of the file
+/// * `original_encoding` - encoding of the original file, returned by `decode_ini`
+fn encode_ini(content: &str, original_encoding: Option<&'static Encoding>) -> Vec<u8> {
+    if original_encoding == Some(encoding_rs::UTF_8) {
+        return content.as_bytes().to_vec();
+    }
+
+    for encoding in original_encoding.into_iter().chain(INI_ENCODINGS) {
+        let (bytes, _, had_errors) = encoding.encode(content);
+
+        if !had_errors {
+            return bytes.into_owned();
+        }
+    }
+
+    content.as_bytes().to_vec()
+}
+
+fn escape_html(string: &str) -> String {
+    string.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Writes files, from which the window title of the game is taken, with the translated game title: Game.ini for
+/// older engines, and package.json and index.html for MV/MZ. Files, that don't exist, are skipped.
+/// # Parameters
+/// * `game_path` - path to the game directory
+/// * `system_file_path` - path to the original system file, which contains the original title
+/// * `other_path` - path to the other directory
+/// * `output_path` - path to the output directory, which contains the data directory
+/// * `romanization_table` - if files were read with romanize, table to romanize the original title to look it up
+/// * `pseudo_expansion` - if some, title is replaced with pseudo-localized original title
+/// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `file_written_msg` - message to log when file is written
+/// * `engine_type` - engine type of the game
+/// * `sources` - if some, parsed original data files are kept between writes
+pub fn write_window_title(
+    game_path: &Path,
+    system_file_path: &Path,
+    other_path: &Path,
+    output_path: &Path,
+    romanization_table: Option<&RomanizationTable>,
+    pseudo_expansion: Option<u16>,
+    logging: bool,
+    output: Output,
+    file_written_msg: &str,
+    engine_type: &EngineType,
+    sources: Option<&Sources>,
+) {
+    let bytes: Vec<u8> = read(system_file_path).unwrap();
+    let system_obj: Value = parse_data_file(sources, system_file_path, &bytes, engine_type);
+
+    let original_title: String = get_game_title(&system_obj, engine_type, romanization_table);
+
+    let title: String = if let Some(expansion) = pseudo_expansion {
+        pseudolocalize(&original_title, expansion)
+    } else {
+        let read_trimmed_lines = |filename: &str| -> Vec<String> {
+            read_to_string(other_path.join(filename))
+                .unwrap()
+                .split('\n')
+                .map(|line: &str| line.trim().to_string())
+                .collect()
+        };
+
+        get_translation(
+            &original_title,
+            &read_trimmed_lines("system.txt"),
+            &read_trimmed_lines("system_trans.txt"),
+        )
+    };
+
+    if title.is_empty() {
+        return;
+    }
+
+    let mut outputs: Vec<(&str, Vec<u8>)> = Vec::new();
+
+    if engine_type == EngineType::New {
+        if let Ok(bytes) = read(game_path.join("package.json")) {
+            let mut package_obj: Value = from_slice(&bytes).unwrap();
+
+            if package_obj["window"]["title"].is_str() {
+                package_obj["window"]["title"] = Value::from(&title);
+                outputs.push(("package.json", patch_json(&bytes, &package_obj)));
+            }
+        }
+
+        if let Ok(content) = read_to_string(game_path.join("index.html")) {
+            let escaped: String = escape_html(&title);
+            let replaced: String = HTML_TITLE_RE
+                .replace(&content, |captures: &Captures| {
+                    format!("{}{escaped}{}", &captures[1], &captures[3])
+                })
+                .into_owned();
+
+            outputs.push(("index.html", replaced.into_bytes()));
+        }
+    } else if let Ok(bytes) = read(game_path.join("Game.ini")) {
+        let (content, encoding) = decode_ini(&bytes);
+        let mut replaced: String = String::with_capacity(content.len());
+
+        for line in content.split_inclusive('\n') {
+            match line.split_once('=') {
+                Some((key, _)) if key.trim().eq_ignore_ascii_case("title") => {
+                    let ending: &str = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                    replaced.push_str(&format!("{key}={title}{ending}"));
+                }
+                _ => replaced.push_str(line),
+            }
+        }
+
+        outputs.push(("Game.ini", encode_ini(&replaced, encoding)));
+    }
+
+    for (filename, output_data) in outputs {
+        let output_file_path: &Path = &output_path.join(filename);
+
+        if output.is_json() {
+            output.emit(Event::FileWritten {
+                file: filename,
+                bytes: output_data.len(),
+            });
+        } else if logging {
+            println!("{file_written_msg} {}", output_file_path.display());
+        }
+
+        write(output_file_path, output_data).unwrap();
+    }
 }
 
 /// Writes plugins.txt file back to its initial form. Currently works only if game_type is GameType::Termina.
@@ -1284,14 +2149,21 @@ pub fn write_system(
 /// * `plugins_path` - path to the plugins directory
 /// * `output_path` - path to the output directory
 /// * `shuffle_level` - level of shuffle
+/// * `pseudo_expansion` - if some, translation is replaced with pseudo-localized original text, which lines are
+///   expanded by this percent
 /// * `logging` - whether to log or not
+/// * `output` - where and how events and messages are printed
+/// * `progress` - progress of the phase, which is advanced for every processed file
 /// * `file_written_msg` - message to log when file is written
 pub fn write_plugins(
     pluigns_file_path: &Path,
     plugins_path: &Path,
     output_path: &Path,
     shuffle_level: u8,
+    pseudo_expansion: Option<u16>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     file_written_msg: &str,
 ) {
     let mut obj_arr: Vec<Object> = from_str(&read_to_string(pluigns_file_path).unwrap()).unwrap();
@@ -1308,7 +2180,12 @@ pub fn write_plugins(
         .map(str::to_string)
         .collect();
 
-    if shuffle_level > 0 {
+    if let Some(expansion) = pseudo_expansion {
+        plugins_translated_text = plugins_original_text
+            .par_iter()
+            .map(|text: &String| pseudolocalize(text, expansion))
+            .collect();
+    } else if shuffle_level > 0 {
         shuffle(&mut plugins_translated_text);
 
         if shuffle_level == 2 {
@@ -1396,13 +2273,17 @@ pub fn write_plugins(
         }
     });
 
-    write(
-        output_path.join("plugins.js"),
-        String::from("var $plugins =\n") + &to_string(&obj_arr).unwrap(),
-    )
-    .unwrap();
+    let output_data: String = String::from("var $plugins =\n") + &to_string(&obj_arr).unwrap();
+    write(output_path.join("plugins.js"), &output_data).unwrap();
+
+    progress.advance();
 
-    if logging {
+    if output.is_json() {
+        output.emit(Event::FileWritten {
+            file: "plugins.js",
+            bytes: output_data.len(),
+        });
+    } else if logging {
         println!("{file_written_msg} plugins.js");
     }
 }
@@ -1490,28 +2371,39 @@ pub fn write_scripts(
     scripts_file_path: &Path,
     other_path: &Path,
     output_path: &Path,
-    romanize: bool,
+    romanization_table: Option<&RomanizationTable>,
+    pseudo_expansion: Option<u16>,
     logging: bool,
+    output: Output,
+    progress: &Progress,
     engine_type: &EngineType,
     file_written_msg: &str,
+    sources: Option<&Sources>,
 ) {
-    let mut script_entries: Value = load(
+    let mut script_entries: Value = parse_source(
+        sources,
+        scripts_file_path,
         &read(scripts_file_path).unwrap(),
-        Some(marshal_rs::load::StringMode::Binary),
-        None,
-    )
-    .unwrap();
+        |bytes: &[u8]| load(bytes, Some(marshal_rs::load::StringMode::Binary), None).unwrap(),
+    );
 
     let original_scripts_text: Vec<String> = read_to_string(other_path.join("scripts.txt"))
         .unwrap()
         .split('\n')
         .map(str::to_string)
         .collect();
-    let translated_scripts_text: Vec<String> = read_to_string(other_path.join("scripts_trans.txt"))
-        .unwrap()
-        .split('\n')
-        .map(str::to_string)
-        .collect();
+    let translated_scripts_text: Vec<String> = if let Some(expansion) = pseudo_expansion {
+        original_scripts_text
+            .iter()
+            .map(|text: &String| pseudolocalize(text, expansion))
+            .collect()
+    } else {
+        read_to_string(other_path.join("scripts_trans.txt"))
+            .unwrap()
+            .split('\n')
+            .map(str::to_string)
+            .collect()
+    };
 
     let scripts_translation_map: HashMap<String, String> =
         HashMap::from_iter(original_scripts_text.into_iter().zip(translated_scripts_text));
@@ -1549,8 +2441,8 @@ pub fn write_scripts(
                 continue;
             }
 
-            if romanize {
-                string = romanize_string(string);
+            if let Some(table) = romanization_table {
+                string = romanize_string(string, table);
             }
 
             let translated: Option<&String> = scripts_translation_map.get(&string);
@@ -1573,7 +2465,16 @@ pub fn write_scripts(
         };
     }
 
-    if logging {
+    let output_data: Vec<u8> = dump(script_entries, None);
+
+    progress.advance();
+
+    if output.is_json() {
+        output.emit(Event::FileWritten {
+            file: &scripts_file_path.file_name().unwrap().to_string_lossy(),
+            bytes: output_data.len(),
+        });
+    } else if logging {
         println!("{file_written_msg} {}", scripts_file_path.display());
     }
 
@@ -1584,7 +2485,81 @@ pub fn write_scripts(
             EngineType::XP => "Scripts.rxdata",
             EngineType::New => unreachable!(),
         }),
-        dump(script_entries, None),
+        output_data,
     )
     .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ini_is_written_in_its_own_encoding() {
+        let (original, _, _) = encoding_rs::SHIFT_JIS.encode("[Game]\r\nTitle=ゲーム\r\n");
+        let (content, encoding) = decode_ini(&original);
+
+        assert_eq!(content, "[Game]\r\nTitle=ゲーム\r\n");
+        assert_eq!(encoding, Some(encoding_rs::SHIFT_JIS));
+
+        // Cyrillic fits Windows-1251 too, but the player's code page is the one of the original file
+        let (expected, _, _) = encoding_rs::SHIFT_JIS.encode("[Game]\r\nTitle=Игра\r\n");
+        assert_eq!(encode_ini("[Game]\r\nTitle=Игра\r\n", encoding), expected.into_owned());
+
+        // ASCII file has no encoding of its own
+        let (_, encoding) = decode_ini(b"[Game]\r\nTitle=Game\r\n");
+        assert_eq!(encoding, None);
+        assert_eq!(
+            encode_ini("Title=Игра", encoding),
+            encoding_rs::WINDOWS_1251.encode("Title=Игра").0.into_owned()
+        );
+    }
+
+    const ORIGINAL: &str = "{\n  \"name\" : \"Caf\\u00e9 \\\"Rest\\\"\",\n  \"price\": 1.50,\n  \"big\": 1e3,\n  \"list\": [ 1,\t2 , {\"note\": \"a\\/b\"} ],\n  \"empty\": null\n}\n";
+
+    #[test]
+    fn patch_json_keeps_unchanged_text() {
+        let value: Value = from_str(ORIGINAL).unwrap();
+        assert_eq!(patch_json(ORIGINAL.as_bytes(), &value), ORIGINAL.as_bytes());
+    }
+
+    #[test]
+    fn patch_json_replaces_only_changed_values() {
+        let mut value: Value = from_str(ORIGINAL).unwrap();
+        value["name"] = Value::from("Кафе \"Отдых\"");
+        value["list"][2]["note"] = Value::from("c");
+
+        let patched: String = String::from_utf8(patch_json(ORIGINAL.as_bytes(), &value)).unwrap();
+        let expected: String = ORIGINAL
+            .replace(r#""Caf\u00e9 \"Rest\"""#, r#""Кафе \"Отдых\"""#)
+            .replace(r#""a\/b""#, r#""c""#);
+
+        assert_eq!(patched, expected);
+        assert_eq!(from_str::<Value>(&patched).unwrap(), value);
+    }
+
+    #[test]
+    fn patch_json_serializes_changed_structure() {
+        let mut value: Value = from_str(ORIGINAL).unwrap();
+        value["list"].as_array_mut().unwrap().push(Value::from(3));
+
+        let patched: String = String::from_utf8(patch_json(ORIGINAL.as_bytes(), &value)).unwrap();
+
+        assert!(patched.contains(r#""list": [1,2,{"note":"a/b"},3],"#));
+        assert!(patched.contains("\"price\": 1.50,"));
+        assert_eq!(from_str::<Value>(&patched).unwrap(), value);
+    }
+
+    #[test]
+    fn skip_json_value_stops_at_value_end() {
+        let original: &[u8] = br#"["a\"]b", {"k": [1, 2]}, 3.25]"#;
+        let mut pos: usize = 1;
+
+        assert_eq!(skip_json_value(original, &mut pos), br#""a\"]b""#);
+        pos += 2;
+        assert_eq!(skip_json_value(original, &mut pos), br#"{"k": [1, 2]}"#);
+        pos += 2;
+        assert_eq!(skip_json_value(original, &mut pos), b"3.25");
+        assert_eq!(original[pos], b']');
+    }
+}